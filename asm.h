@@ -29,13 +29,43 @@ __clear_ ## name: \
     csrrc x0, offset, a0; \
     ret
 
+#define REG_SWAP(name, offset) \
+.section .text.__swap_ ## name; \
+.global __swap_ ## name; \
+__swap_ ## name: \
+    csrrw a0, offset, a0; \
+    ret
+
+#define REG_READ_SET(name, offset) \
+.section .text.__read_set_ ## name; \
+.global __read_set_ ## name; \
+__read_set_ ## name: \
+    csrrs a0, offset, a0; \
+    ret
+
+#define REG_READ_CLEAR(name, offset) \
+.section .text.__read_clear_ ## name; \
+.global __read_clear_ ## name; \
+__read_clear_ ## name: \
+    csrrc a0, offset, a0; \
+    ret
+
 
 #define REG_READ_WRITE(name, offset) REG_READ(name, offset); REG_WRITE(name, offset)
 #define REG_SET_CLEAR(name, offset) REG_SET(name, offset); REG_CLEAR(name, offset)
+#define REG_READ_SET_CLEAR(name, offset) REG_READ_SET(name, offset); REG_READ_CLEAR(name, offset)
 
-#define RW(offset, name) REG_READ_WRITE(name, offset); REG_SET_CLEAR(name, offset)
+#define RW(offset, name) REG_READ_WRITE(name, offset); REG_SET_CLEAR(name, offset); REG_SWAP(name, offset); REG_READ_SET_CLEAR(name, offset)
 #define RO(offset, name) REG_READ(name, offset)
 
+#if __riscv_xlen == 32
+#define LREG lw
+#define REGBYTES 4
+#else
+#define LREG ld
+#define REGBYTES 8
+#endif
+
 #if __riscv_xlen == 32
 #define RW32(offset, name) RW(offset, name)
 #define RO32(offset, name) RO(offset, name)
@@ -6,10 +6,26 @@ export PATH=$PATH:~/.cargo/bin
 
 if [ -n "${TARGET:-}" ]; then
     cargo check --target $TARGET
+    cargo check --target $TARGET --no-default-features
+    cargo check --target $TARGET --features emulation
+    cargo check --target $TARGET --features s-mode
+    cargo check --target $TARGET --features trap-entry
+    cargo check --target $TARGET --features trap-entry,s-mode
+    cargo check --target $TARGET --features critical-section-single-hart
+    cargo check --target $TARGET --features critical-section-single-hart,s-mode
+    cargo check --target $TARGET --features embedded-hal,embedded-hal-02
+    cargo check --target $TARGET --features defmt
+    cargo check --target $TARGET --features core-error
+    cargo check --target $TARGET --features rand_core
+    cargo check --target $TARGET --features soft-atomics
 
     if [ $TRAVIS_RUST_VERSION = nightly ]; then
         cargo check --target $TARGET --features inline-asm
     fi
+
+    if [ $TARGET = x86_64-unknown-linux-gnu ]; then
+        cargo test --target $TARGET --features emulation
+    fi
 fi
 
 if [ -n "${CHECK_BLOBS:-}" ]; then
@@ -1,8 +1,13 @@
 #!/bin/bash
 rustc generator.rs
-rm -f ../src/register/hypervisorx64/mod.rs;
+cat > ../src/register/hypervisorx64/mod.rs <<EOF
+/// Width of the CSRs in bits
+const XLEN: usize = core::mem::size_of::<usize>() * 8;
+
+EOF
 for i in *.txt; do 
     ./generator <$i > ../src/register/hypervisorx64/`basename -s .txt $i`.rs; 
     echo "pub mod $(basename -s .txt $i);" >> ../src/register/hypervisorx64/mod.rs; 
 done
-rm -f generator
\ No newline at end of file
+rm -f generator
+rustfmt --edition 2015 ../src/register/hypervisorx64/*.rs
@@ -73,11 +73,12 @@ impl<'a> EnumerationDescriptor<'a> {
         }
         EnumerationDescriptor { enumerations: e }
     }
-    fn generate_enum(&self, name: &str) -> String {
+    fn generate_enum(&self, name: &str, cfg: &str) -> String {
         let mut ret = String::new();
         write!(
             &mut ret,
-            "#[derive(Copy, Clone, Debug)]
+            "#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = \"defmt\", derive(defmt::Format))]
 #[repr(usize)]
 "
         )
@@ -92,7 +93,7 @@ impl<'a> EnumerationDescriptor<'a> {
         write!(
             &mut ret,
             "}}
-impl {}{{
+{}impl {}{{
     fn from(x: usize)->Self{{
         match x{{
 {}            _ => unreachable!()
@@ -100,29 +101,92 @@ impl {}{{
     }}
 }}
 ",
-            name, branches
+            cfg, name, branches
         )
         .unwrap();
         return ret;
     }
 }
+// Bit position, either fixed or counted down from XLEN (`XLEN-1`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Pos {
+    Fixed(usize),
+    FromXlen(usize),
+}
+impl Pos {
+    pub fn parse(p: &str) -> Self {
+        if p.starts_with("XLEN-") {
+            Pos::FromXlen(p[5..].parse().unwrap())
+        } else {
+            Pos::Fixed(p.parse().unwrap())
+        }
+    }
+    // Position on RV64
+    fn value64(&self) -> usize {
+        match *self {
+            Pos::Fixed(n) => n,
+            Pos::FromXlen(n) => 64 - n,
+        }
+    }
+    // End of a range whose last bit is at this position
+    fn end(&self) -> String {
+        match *self {
+            Pos::Fixed(n) => format!("{}", n + 1),
+            Pos::FromXlen(1) => "XLEN".to_string(),
+            Pos::FromXlen(n) => format!("XLEN - {}", n - 1),
+        }
+    }
+    // Operand of a shift
+    fn shift(&self) -> String {
+        match *self {
+            Pos::Fixed(n) => format!("{}", n),
+            Pos::FromXlen(n) => format!("(XLEN - {})", n),
+        }
+    }
+}
+impl Display for Pos {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match *self {
+            Pos::Fixed(n) => write!(f, "{}", n),
+            Pos::FromXlen(n) => write!(f, "XLEN - {}", n),
+        }
+    }
+}
 #[derive(Debug, Clone)]
 struct BitFieldDescriptor<'a> {
     name: &'a str,
     description: &'a str,
-    lo: usize,
-    hi: usize,
+    lo: Pos,
+    hi: Pos,
+    // `hi/hi32,lo/lo32`: RV32 positions, if they differ from the RV64 ones
+    lo32: Option<Pos>,
+    hi32: Option<Pos>,
     ed: Option<(&'a str, EnumerationDescriptor<'a>)>,
+    // The enumeration is defined elsewhere (`use:Type`)
+    external: bool,
+    // `name:attr:attr`; `nobitop` skips the set_/clear_ functions, `rv64`
+    // limits the field to RV64 and `option` decodes reserved values of a
+    // `use:Type` field to `None`
+    attrs: Vec<&'a str>,
 }
 
 impl<'a> BitFieldDescriptor<'a> {
     pub fn parse(desc: &'a str) -> Self {
         let mut parts = desc.split(",");
-        let name = parts.next().unwrap();
-        let hi = parts.next().unwrap().parse::<usize>().unwrap();
-        let lo = parts.next().unwrap().parse::<usize>().unwrap();
-        let (lo, hi) = if lo < hi { (lo, hi) } else { (hi, lo) };
+        let mut attrs = parts.next().unwrap().split(":");
+        let name = attrs.next().unwrap();
+        let attrs = attrs.collect();
+        let mut hi = parts.next().unwrap().split("/").map(Pos::parse);
+        let mut lo = parts.next().unwrap().split("/").map(Pos::parse);
+        let (hi, hi32, lo, lo32) = (hi.next().unwrap(), hi.next(), lo.next().unwrap(), lo.next());
+        let (lo, hi) = if lo.value64() < hi.value64() { (lo, hi) } else { (hi, lo) };
+        let (lo32, hi32) = match (lo32, hi32) {
+            (Some(lo32), Some(hi32)) if lo32.value64() > hi32.value64() => (Some(hi32), Some(lo32)),
+            x => x,
+        };
         let use_enum = parts.next().unwrap();
+        let external = use_enum.starts_with("use:");
+        let use_enum = use_enum.trim_start_matches("use:");
         let ed = if use_enum != "number" {
             let opts = parts.next().unwrap();
             Some((use_enum, EnumerationDescriptor::parse(opts)))
@@ -134,13 +198,70 @@ impl<'a> BitFieldDescriptor<'a> {
             name,
             lo,
             hi,
+            lo32,
+            hi32,
             description,
             ed,
+            external,
+            attrs,
+        }
+    }
+    pub fn has(&self, attr: &str) -> bool {
+        self.attrs.contains(&attr)
+    }
+    fn cfg(&self) -> &str {
+        if self.has("rv64") {
+            "#[cfg(target_pointer_width = \"64\")]\n"
+        } else {
+            ""
+        }
+    }
+    pub fn uses_xlen(&self) -> bool {
+        match (self.lo, self.hi) {
+            (Pos::Fixed(_), Pos::Fixed(_)) => false,
+            _ => true,
+        }
+    }
+    // Name of the constant holding the range of a field that moves with XLEN
+    pub fn range_const(&self) -> Option<String> {
+        if self.hi32.is_some() {
+            Some(self.name.to_uppercase())
+        } else {
+            None
+        }
+    }
+    pub fn generate_range_const(&self, xlen: usize) -> String {
+        let (lo, hi) = if xlen == 32 {
+            (self.lo32.unwrap(), self.hi32.unwrap())
+        } else {
+            (self.lo, self.hi)
+        };
+        format!(
+            "#[cfg(target_pointer_width = \"{}\")]\nconst {}: Range<usize> = {}..{};\n",
+            xlen,
+            self.range_const().unwrap(),
+            lo,
+            hi.end()
+        )
+    }
+    fn range(&self) -> String {
+        match self.range_const() {
+            Some(name) => name,
+            None => format!("{}..{}", self.lo, self.hi.end()),
+        }
+    }
+    fn return_type(&self) -> String {
+        if self.has("option") {
+            format!("Option<{}>", self.flag_type())
+        } else {
+            self.flag_type().to_string()
         }
     }
     pub fn generate_enum(&self) -> Option<String> {
-        if let Some((n, e)) = &self.ed {
-            Some(e.generate_enum(n))
+        if self.external {
+            None
+        } else if let Some((n, e)) = &self.ed {
+            Some(e.generate_enum(n, self.cfg()))
         } else {
             None
         }
@@ -157,50 +278,87 @@ impl<'a> BitFieldDescriptor<'a> {
         }
     }
     fn mask(&self) -> String {
-        format!("{}", (1usize << (self.hi - self.lo + 1)) - 1)
+        format!(
+            "{}",
+            (1usize << (self.hi.value64() - self.lo.value64() + 1)) - 1
+        )
     }
-    fn getter(&self) -> String {
+    // Decodes an enumeration defined elsewhere, which has no `from`
+    fn external_getter(&self) -> String {
+        let (n, e) = self.ed.as_ref().unwrap();
+        let variant = |value| e.enumerations.iter().find(|x| x.1 == value).unwrap().0;
         if self.lo == self.hi {
+            return format!(
+                "match self.bits.get_bit({}) {{ true => {}::{}, false => {}::{}, }}",
+                self.lo,
+                n,
+                variant(1),
+                n,
+                variant(0)
+            );
+        }
+        let option = self.has("option");
+        let none = if option { "None" } else { "unreachable!()" };
+        let mut arms = String::new();
+        for x in e.enumerations.iter() {
+            if option {
+                write!(&mut arms, "{} => Some({}::{}), ", x.1, n, x.0).unwrap();
+            } else {
+                write!(&mut arms, "{} => {}::{}, ", x.1, n, x.0).unwrap();
+            }
+        }
+        format!(
+            "match self.bits.get_bits({}) {{ {}_ => {}, }}",
+            self.range(),
+            arms,
+            none
+        )
+    }
+    fn getter(&self) -> String {
+        if self.external {
+            return self.external_getter();
+        } else if self.lo == self.hi {
             return format!("self.bits.get_bit({})", self.lo);
         } else if self.flag_type() != "usize" {
             return format!(
-                "{}::from(self.bits.get_bits({}..{}))",
+                "{}::from(self.bits.get_bits({}))",
                 self.flag_type(),
-                self.lo,
-                self.hi + 1
+                self.range()
             );
         } else {
-            return format!("self.bits.get_bits({}..{})", self.lo, self.hi + 1);
+            return format!("self.bits.get_bits({})", self.range());
         }
     }
     fn setter(&self) -> String {
-        if self.lo == self.hi {
+        if self.external && self.lo == self.hi {
+            let (n, e) = self.ed.as_ref().unwrap();
+            let set = e.enumerations.iter().find(|x| x.1 == 1).unwrap().0;
+            return format!("self.bits.set_bit({}, val == {}::{});", self.lo, n, set);
+        } else if self.lo == self.hi {
             return format!("self.bits.set_bit({}, val);", self.lo);
         } else if self.flag_type() != "usize" {
-            return format!(
-                "self.bits.set_bits({}..{}, val as usize);",
-                self.lo,
-                self.hi + 1
-            );
+            return format!("self.bits.set_bits({}, val as usize);", self.range());
         } else {
-            return format!("self.bits.set_bits({}..{}, val);", self.lo, self.hi + 1);
+            return format!("self.bits.set_bits({}, val);", self.range());
         }
     }
     fn generate_read_write(&self) -> String {
         format!(
             "    /// {}
     #[inline] 
-    pub fn {}(&self)->{}{{
+    {}pub fn {}(&self)->{}{{
         {}
     }}
     #[inline]
-    pub fn set_{}(&mut self, val: {}){{
+    {}pub fn set_{}(&mut self, val: {}){{
         {}
     }}\n",
             self.description,
+            self.cfg(),
             self.name,
-            self.flag_type(),
+            self.return_type(),
             self.getter(),
+            self.cfg(),
             self.name,
             self.flag_type(),
             self.setter()
@@ -216,28 +374,80 @@ impl<'a> BitFieldDescriptor<'a> {
         unsafe {{csr::csrrs({}) & {} !=0 }}
     }}\n",
             self.name,
-            1usize << self.lo,
-            1usize << self.lo,
+            1usize << self.lo.value64(),
+            1usize << self.lo.value64(),
             self.name,
-            1usize << self.lo,
-            1usize << self.lo
+            1usize << self.lo.value64(),
+            1usize << self.lo.value64()
         )
     }
     fn generate_bitops(&self) -> String {
         format!(
             "    set_clear_csr!(
     ///{}
-    , set_{}, clear_{}, 1 << {});\n",
-            self.description, self.name, self.name, self.lo
+{}    , set_{}, clear_{}, 1 << {});\n",
+            self.description,
+            if self.has("rv64") {
+                "    #[cfg(target_pointer_width = \"64\")]\n"
+            } else {
+                ""
+            },
+            self.name,
+            self.name,
+            self.lo.shift()
         )
     }
 }
 
+/// Hand-written code pasted into the generated module
+///
+/// Each section starts with a line holding only its marker, after the
+/// description of the CSR:
+/// - `@use`: after the imports (before `read_csr!` for plain CSRs);
+/// - `@impl`: at the end of the `impl` block of the register struct;
+/// - `@items`: after the `impl` block (after `write_csr!` for plain CSRs);
+/// - `@bitops`: after the generated bit operations.
+#[derive(Debug, Clone, Default)]
+struct Sections<'a> {
+    uses: &'a str,
+    impls: &'a str,
+    items: &'a str,
+    bitops: &'a str,
+}
+
+impl<'a> Sections<'a> {
+    /// Splits the text after `end` into the description and the sections
+    pub fn parse(text: &'a str) -> (&'a str, Self) {
+        let mut sections = Sections::default();
+        let mut starts: Vec<usize> = text.match_indices("\n@").map(|(i, _)| i + 1).collect();
+        starts.push(text.len());
+        let description = text[..starts[0]].trim_end();
+        for w in starts.windows(2) {
+            let chunk = &text[w[0] + 1..w[1]];
+            let (marker, body) = match chunk.find("\n") {
+                Some(i) => (&chunk[..i], &chunk[i + 1..]),
+                None => (chunk, ""),
+            };
+            match marker.trim() {
+                "use" => sections.uses = body,
+                "impl" => sections.impls = body,
+                "items" => sections.items = body,
+                "bitops" => sections.bitops = body,
+                x => panic!("unknown section @{}", x),
+            }
+        }
+        (description, sections)
+    }
+}
+
 #[derive(Debug, Clone)]
 struct CSRDescriptor<'a> {
     name: &'a str,
+    // `Name:rv32` for CSRs that only exist on RV32
+    rv32: bool,
     id: usize,
     description: &'a str,
+    sections: Sections<'a>,
     bfs: Vec<BitFieldDescriptor<'a>>,
 }
 
@@ -247,7 +457,8 @@ impl<'a> CSRDescriptor<'a> {
     }
     pub fn parse(d: &'a str) -> Self {
         let mut parts = d.split("\n");
-        let name = parts.next().unwrap();
+        let mut name = parts.next().unwrap().split(":");
+        let (name, rv32) = (name.next().unwrap(), name.next() == Some("rv32"));
         let id = parts.next().unwrap().parse::<usize>().unwrap();
         let mut bfs = Vec::new();
         while let Some(x) = parts.next() {
@@ -257,19 +468,44 @@ impl<'a> CSRDescriptor<'a> {
                 bfs.push(BitFieldDescriptor::parse(x));
             }
         }
+        let (description, sections) = Sections::parse(as_str_polyfill!(parts, d));
         CSRDescriptor {
             name,
+            rv32,
             id,
-            description: as_str_polyfill!(parts, d),
+            description,
+            sections,
             bfs,
         }
     }
+    // Suffix selecting the accessor macros of RV32-only CSRs
+    fn suffix(&self) -> &str {
+        if self.rv32 {
+            "_rv32"
+        } else {
+            ""
+        }
+    }
     pub fn generate(&self) -> String {
         let mut trait_impls = String::new();
         let mut bit_sets = String::new();
         let mut enums = String::new();
+        let mut uses = String::new();
+        let mut consts = String::new();
+        if self.bfs.iter().any(|bf| bf.range_const().is_some()) {
+            write!(&mut uses, "use core::ops::Range;\n").unwrap();
+            for xlen in [32, 64].iter() {
+                for bf in self.bfs.iter().filter(|bf| bf.range_const().is_some()) {
+                    write!(&mut consts, "{}", bf.generate_range_const(*xlen)).unwrap();
+                }
+            }
+            write!(&mut consts, "\n").unwrap();
+        }
+        if self.bfs.iter().any(|bf| bf.uses_xlen()) {
+            write!(&mut uses, "use super::XLEN;\n").unwrap();
+        }
         for bf in self.bfs.iter() {
-            if bf.lo == bf.hi {
+            if bf.lo == bf.hi && !bf.has("nobitop") {
                 write!(&mut bit_sets, "{}", bf.generate_bitops()).unwrap();
                 //write!(&mut trait_impls, "{}",bf.generate_bit_set()).unwrap();
             }
@@ -282,14 +518,18 @@ impl<'a> CSRDescriptor<'a> {
             format!(
                 "
 //! {}
-read_csr_as_usize!({}, __read_{});
-write_csr_as_usize!({}, __write_{});
-",
+{}read_csr_as_usize{}!({}, __read_{});
+write_csr_as_usize{}!({}, __write_{});
+{}",
                 self.description,
+                self.sections.uses,
+                self.suffix(),
                 self.id,
                 self.canonical_name(),
+                self.suffix(),
                 self.id,
-                self.canonical_name()
+                self.canonical_name(),
+                self.sections.items,
             )
         } else {
             format!(
@@ -297,8 +537,9 @@ write_csr_as_usize!({}, __write_{});
 //! {}
 
 use bit_field::BitField;
-
-#[derive(Copy, Clone, Debug)]
+{}{}
+{}#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = \"defmt\", derive(defmt::Format))]
 pub struct {}{{\n    bits: usize,\n}}
 impl {}{{
     #[inline]
@@ -313,33 +554,44 @@ impl {}{{
     pub unsafe fn write(&self){{
         _write(self.bits);
     }}
-{}
-}}
-read_csr_as!({}, {}, __read_{});
-write_csr!({}, __write_{});
-set!({}, __set_{});
-clear!({}, __clear_{});
+{}{}}}
+{}read_csr_as{}!({}, {}, __read_{});
+write_csr{}!({}, __write_{});
+set{}!({}, __set_{});
+clear{}!({}, __clear_{});
+modify_csr!({});
 // bit ops
-{}
+{}{}
 // enums
 {}
 
 ",
                 self.description,
+                uses,
+                self.sections.uses,
+                consts,
                 self.name,
                 self.name,
                 self.name,
                 trait_impls,
+                self.sections.impls,
+                self.sections.items,
+                self.suffix(),
                 self.name,
                 self.id,
                 self.canonical_name(),
+                self.suffix(),
                 self.id,
                 self.canonical_name(),
+                self.suffix(),
                 self.id,
                 self.canonical_name(),
+                self.suffix(),
                 self.id,
                 self.canonical_name(),
+                self.name,
                 bit_sets,
+                self.sections.bitops,
                 enums,
             )
         }
@@ -3,6 +3,7 @@ use bit_field::BitField;
 use core::convert::TryInto;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct GPAddrSv32X4(u64);
 
 impl Address for GPAddrSv32X4 {
@@ -61,6 +62,7 @@ impl AddressX64 for GPAddrSv32X4 {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct GPAddrSv39X4(u64);
 
 impl Address for GPAddrSv39X4 {
@@ -131,6 +133,7 @@ impl AddressX64 for GPAddrSv39X4 {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct GPAddrSv48X4(u64);
 
 impl Address for GPAddrSv48X4 {
@@ -28,6 +28,7 @@ pub trait PageWithL2 {
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct PageWith<T: VirtualAddress>(T);
 
 impl<T: AddressL4 + VirtualAddress> PageWithL4 for PageWith<T> {
@@ -98,6 +99,7 @@ impl<T: VirtualAddress> PageWith<T> {
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct FrameWith<T: PhysicalAddress>(T);
 
 impl<T: AddressL4 + PhysicalAddress> PageWithL4 for FrameWith<T> {
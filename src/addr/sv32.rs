@@ -2,6 +2,7 @@ use super::*;
 use bit_field::BitField;
 use core::convert::TryInto;
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct VirtAddrSv32(u32);
 impl Address for VirtAddrSv32 {
     fn new(addr: usize) -> Self {
@@ -52,6 +53,7 @@ impl AddressX32 for VirtAddrSv32 {
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct PhysAddrSv32(u64);
 impl Address for PhysAddrSv32 {
     fn new(addr: usize) -> Self {
@@ -2,6 +2,7 @@ use super::*;
 use bit_field::BitField;
 use core::convert::TryInto;
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct VirtAddrSv39(u64);
 
 impl VirtualAddress for VirtAddrSv39 {
@@ -64,6 +65,7 @@ impl AddressL3 for VirtAddrSv39 {
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct PhysAddrSv39(u64);
 impl Address for PhysAddrSv39 {
     fn new(addr: usize) -> Self {
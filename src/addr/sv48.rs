@@ -2,6 +2,7 @@ use super::*;
 use bit_field::BitField;
 use core::convert::TryInto;
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct VirtAddrSv48(u64);
 
 impl VirtualAddress for VirtAddrSv48 {
@@ -74,6 +75,7 @@ impl AddressL4 for VirtAddrSv48 {
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct PhysAddrSv48(u64);
 impl Address for PhysAddrSv48 {
     fn new(addr: usize) -> Self {
@@ -1,7 +1,12 @@
 //! Assembly instructions
 
+use core::ops::Range;
+
 macro_rules! instruction {
     ($(#[$attr:meta])*, $fnname:ident, $asm:expr, $asm_fn:ident) => (
+        instruction!($(#[$attr])*, $fnname, $asm, $asm_fn, unimplemented!());
+    );
+    ($(#[$attr:meta])*, $fnname:ident, $asm:expr, $asm_fn:ident, $host:expr) => (
         $(#[$attr])*
         #[inline]
         pub unsafe fn $fnname() {
@@ -19,7 +24,7 @@ macro_rules! instruction {
                 }
 
                 #[cfg(not(riscv))]
-                () => unimplemented!(),
+                () => $host,
             }
         }
     )
@@ -30,12 +35,327 @@ instruction!(
     ///
     /// Generates a breakpoint exception.
     , ebreak, "ebreak", __ebreak);
+/// Values left in `a0` and `a1` by an environment call
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct EcallResult {
+    pub a0: usize,
+    pub a1: usize,
+}
+
+/// `ECALL` instruction wrapper
+///
+/// Places `args[n]` in register `a<n>`, raises an environment call exception and returns `a0` and `a1`
+/// afterwards. The execution environment (e.g. the SBI) must preserve all other registers.
+#[inline]
+#[allow(unused_variables)]
+pub unsafe fn ecall(args: [usize; 8]) -> EcallResult {
+    match () {
+        #[cfg(all(riscv, feature = "inline-asm"))]
+        () => {
+            let (a0, a1);
+            core::arch::asm!(
+                "ecall",
+                inlateout("a0") args[0] => a0,
+                inlateout("a1") args[1] => a1,
+                in("a2") args[2],
+                in("a3") args[3],
+                in("a4") args[4],
+                in("a5") args[5],
+                in("a6") args[6],
+                in("a7") args[7],
+            );
+            EcallResult { a0, a1 }
+        }
+
+        #[cfg(all(riscv, not(feature = "inline-asm")))]
+        () => {
+            extern "C" {
+                fn __ecall(args: *const [usize; 8]) -> EcallResult;
+            }
+
+            __ecall(&args)
+        }
+
+        #[cfg(not(riscv))]
+        () => unimplemented!(),
+    }
+}
+
 instruction!(
     /// `WFI` instruction wrapper
     ///
     /// Provides a hint to the implementation that the current hart can be stalled until an interrupt might need servicing.
     /// The WFI instruction is just a hint, and a legal implementation is to implement WFI as a NOP.
-    , wfi, "wfi", __wfi);
+    /// Off-target (e.g. in host builds) it does nothing.
+    , wfi, "wfi", __wfi, ());
+instruction!(
+    /// `NOP` instruction wrapper
+    ///
+    /// Does nothing; useful to keep the compiler from optimizing away empty loops. Off-target it does nothing
+    /// as well.
+    , nop, "nop", __nop, ());
+instruction!(
+    /// `PAUSE` instruction wrapper (Zihintpause extension)
+    ///
+    /// Hints that the current hart is in a spin-wait loop, so it may reduce its energy use or yield to other
+    /// harts. The hint is encoded as a `FENCE W, 0`, which cores without the extension execute as a no-op.
+    /// Off-target it maps to `core::hint::spin_loop`.
+    , pause, ".word 16777231", __pause, core::hint::spin_loop());
+
+instruction!(
+    /// `WRS.NTO` instruction wrapper (Zawrs extension)
+    ///
+    /// Stalls the current hart until its reservation set is invalidated or an interrupt is pending. Only
+    /// useful after a load-reserved, see `wait_on`. Off-target it maps to `core::hint::spin_loop`.
+    , wrs_nto, ".word 13631603", __wrs_nto, core::hint::spin_loop());
+instruction!(
+    /// `WRS.STO` instruction wrapper (Zawrs extension)
+    ///
+    /// Like `wrs_nto`, but also returns after an implementation-defined short timeout. Off-target it maps to
+    /// `core::hint::spin_loop`.
+    , wrs_sto, ".word 30408819", __wrs_sto, core::hint::spin_loop());
+
+#[cfg(all(riscv, feature = "inline-asm"))]
+#[inline]
+fn sign_extend(value: u32) -> isize {
+    value as i32 as isize
+}
+
+/// Words `wait_on` can wait on with a load-reserved
+pub trait WaitWord: Copy + Eq {
+    #[doc(hidden)]
+    unsafe fn wait_on(ptr: *const Self, expected: Self) -> Self;
+}
+
+// Spins on `LR` (encoded by `$lr` as `lr a2, (a0)`) and `WRS.NTO` until the value at `a0` differs from `a1`.
+// `$expected` converts the expected value to XLEN the way `LR` extends the loaded word.
+macro_rules! wait_word {
+    ($ty:ty, $lr:expr, $asm_fn:ident, $expected:path) => {
+        impl WaitWord for $ty {
+            #[inline]
+            unsafe fn wait_on(ptr: *const Self, expected: Self) -> Self {
+                match () {
+                    #[cfg(all(riscv, feature = "inline-asm"))]
+                    () => {
+                        let value: $ty;
+                        core::arch::asm!(
+                            "2:",
+                            $lr,
+                            "bne a2, a1, 3f",
+                            ".word 13631603",
+                            "j 2b",
+                            "3:",
+                            in("a0") ptr,
+                            in("a1") $expected(expected),
+                            out("a2") value,
+                        );
+                        value
+                    }
+
+                    #[cfg(all(riscv, not(feature = "inline-asm")))]
+                    () => {
+                        extern "C" {
+                            fn $asm_fn(ptr: *const $ty, expected: $ty) -> $ty;
+                        }
+
+                        $asm_fn(ptr, expected)
+                    }
+
+                    #[cfg(not(riscv))]
+                    () => loop {
+                        let value = core::ptr::read_volatile(ptr);
+                        if value != expected {
+                            break value;
+                        }
+                        core::hint::spin_loop();
+                    },
+                }
+            }
+        }
+    };
+}
+
+wait_word!(u32, ".word 268772911", __wait_on_w, sign_extend);
+#[cfg(not(riscv64))]
+wait_word!(usize, ".word 268772911", __wait_on_w, usize::from);
+#[cfg(riscv64)]
+wait_word!(usize, ".word 268777007", __wait_on_d, usize::from);
+
+/// Waits until the word at `ptr` no longer holds `expected` and returns the new value
+///
+/// Registers a reservation on the word with `LR` and stalls in `WRS.NTO` until another hart writes it, so
+/// the hart does not burn cycles while waiting. Requires the A and Zawrs extensions; off-target it spins.
+///
+/// # Safety
+///
+/// `ptr` must be valid for reads and naturally aligned.
+#[inline]
+pub unsafe fn wait_on<T: WaitWord>(ptr: *const T, expected: T) -> T {
+    T::wait_on(ptr, expected)
+}
+
+/// Busy-waits for at least `cycles` clock cycles
+///
+/// Spins on `mcycle` (or `cycle` with the `s-mode` feature, which then must be enabled in `mcounteren`), so
+/// the delay does not depend on the instruction timing of the core. Use `delay_loop` on cores without
+/// readable cycle counters. Off-target it returns immediately, as the emulated counters do not advance.
+#[inline]
+#[allow(unused_variables)]
+pub fn delay(cycles: u64) {
+    match () {
+        #[cfg(riscv)]
+        () => {
+            #[cfg(feature = "s-mode")]
+            use register::cycle::read64;
+            #[cfg(not(feature = "s-mode"))]
+            use register::mcycle::read64;
+
+            let start = read64();
+            while read64().wrapping_sub(start) < cycles {}
+        }
+
+        #[cfg(not(riscv))]
+        () => {}
+    }
+}
+
+/// Busy-waits by counting down `iterations` times
+///
+/// Each iteration takes a decrement and a branch, so the duration depends on the core; calibrate against a
+/// timer where precision matters.
+#[inline]
+#[allow(unused_variables)]
+pub fn delay_loop(iterations: usize) {
+    match () {
+        #[cfg(all(riscv, feature = "inline-asm"))]
+        () => unsafe {
+            core::arch::asm!(
+                "beqz {0}, 3f",
+                "2:",
+                "addi {0}, {0}, -1",
+                "bnez {0}, 2b",
+                "3:",
+                inout(reg) iterations => _,
+            )
+        },
+
+        #[cfg(all(riscv, not(feature = "inline-asm")))]
+        () => {
+            extern "C" {
+                fn __delay_loop(iterations: usize);
+            }
+
+            unsafe { __delay_loop(iterations) }
+        }
+
+        #[cfg(not(riscv))]
+        () => {}
+    }
+}
+
+bitflags! {
+    /// Predecessor or successor set of a `FENCE` instruction
+    pub struct FenceSet: u8 {
+        /// Memory writes
+        const W = 1 << 0;
+        /// Memory reads
+        const R = 1 << 1;
+        /// Device output
+        const O = 1 << 2;
+        /// Device input
+        const I = 1 << 3;
+        const RW = Self::R.bits | Self::W.bits;
+        const IO = Self::I.bits | Self::O.bits;
+        const IORW = Self::IO.bits | Self::RW.bits;
+    }
+}
+
+/// Emits the `FENCE` whose predecessor and successor sets are encoded in `$sets` (`pred << 4 | succ`)
+#[cfg(all(riscv, feature = "inline-asm"))]
+macro_rules! fence_dispatch {
+    ($sets:expr) => {
+        fence_dispatch!(@arms $sets;
+        0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32 33 34
+        35 36 37 38 39 40 41 42 43 44 45 46 47 48 49 50 51 52 53 54 55 56 57 58 59 60 61 62 63 64 65 66
+        67 68 69 70 71 72 73 74 75 76 77 78 79 80 81 82 83 84 85 86 87 88 89 90 91 92 93 94 95 96 97 98
+        99 100 101 102 103 104 105 106 107 108 109 110 111 112 113 114 115 116 117 118 119 120 121 122
+        123 124 125 126 127 128 129 130 131 132 133 134 135 136 137 138 139 140 141 142 143 144 145 146
+        147 148 149 150 151 152 153 154 155 156 157 158 159 160 161 162 163 164 165 166 167 168 169 170
+        171 172 173 174 175 176 177 178 179 180 181 182 183 184 185 186 187 188 189 190 191 192 193 194
+        195 196 197 198 199 200 201 202 203 204 205 206 207 208 209 210 211 212 213 214 215 216 217 218
+        219 220 221 222 223 224 225 226 227 228 229 230 231 232 233 234 235 236 237 238 239 240 241 242
+        243 244 245 246 247 248 249 250 251 252 253 254 255
+        )
+    };
+    (@arms $sets:expr; $($n:literal)*) => {
+        match $sets {
+            $($n => core::arch::asm!(concat!(".word (", stringify!($n), " << 20) | 0x0F")),)*
+        }
+    };
+}
+
+/// `FENCE` instruction wrapper
+///
+/// Orders the operations in `pred` issued before the fence before the operations in `succ` issued after
+/// it. With constant sets this compiles down to a single instruction. Without the `inline-asm` feature only
+/// the pairs `rw, rw`, `r, rw`, `rw, w`, `r, r`, `w, w` and `io, io` have prebuilt instructions; any other
+/// sets execute a full `fence iorw, iorw` instead.
+#[inline]
+#[allow(unused_variables)]
+pub fn fence(pred: FenceSet, succ: FenceSet) {
+    match () {
+        #[cfg(all(riscv, feature = "inline-asm"))]
+        () => unsafe { fence_dispatch!((pred.bits() << 4) | succ.bits()) },
+
+        #[cfg(all(riscv, not(feature = "inline-asm")))]
+        () => {
+            extern "C" {
+                fn __fence();
+                fn __fence_rw_rw();
+                fn __fence_r_rw();
+                fn __fence_rw_w();
+                fn __fence_r_r();
+                fn __fence_w_w();
+                fn __fence_io();
+            }
+
+            let sets = (pred, succ);
+            unsafe {
+                if sets == (FenceSet::RW, FenceSet::RW) {
+                    __fence_rw_rw()
+                } else if sets == (FenceSet::R, FenceSet::RW) {
+                    __fence_r_rw()
+                } else if sets == (FenceSet::RW, FenceSet::W) {
+                    __fence_rw_w()
+                } else if sets == (FenceSet::R, FenceSet::R) {
+                    __fence_r_r()
+                } else if sets == (FenceSet::W, FenceSet::W) {
+                    __fence_w_w()
+                } else if sets == (FenceSet::IO, FenceSet::IO) {
+                    __fence_io()
+                } else {
+                    __fence()
+                }
+            }
+        }
+
+        #[cfg(not(riscv))]
+        () => core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst),
+    }
+}
+
+instruction!(
+    /// `FENCE RW, RW` instruction wrapper
+    ///
+    /// Orders memory accesses, but not device I/O, before the fence before those after it.
+    , fence_rw_rw, "fence rw, rw", __fence_rw_rw);
+instruction!(
+    /// `FENCE IO, IO` instruction wrapper
+    ///
+    /// Orders device input and output before the fence before those after it.
+    , fence_io, "fence io, io", __fence_io);
+
 instruction!(
     /// `SFENCE.VMA` instruction wrapper (all address spaces and page table levels)
     ///
@@ -74,9 +394,306 @@ pub unsafe fn sfence_vma(asid: usize, addr: usize) {
     }
 }
 
+/// `SFENCE.VMA` instruction wrapper (single address space, all page table levels)
+///
+/// Orders stores to the page tables before subsequent implicit references to them, for the address space
+/// `asid` only. Translations of global mappings are not affected.
+#[inline]
+#[allow(unused_variables)]
+pub unsafe fn sfence_vma_asid(asid: usize) {
+    match () {
+        #[cfg(all(riscv, feature = "inline-asm"))]
+        () => core::arch::asm!("sfence.vma x0, {0}", in(reg) asid),
+
+        #[cfg(all(riscv, not(feature = "inline-asm")))]
+        () => {
+            extern "C" {
+                fn __sfence_vma_asid(asid: usize);
+            }
+
+            __sfence_vma_asid(asid);
+        }
+
+        #[cfg(not(riscv))]
+        () => unimplemented!(),
+    }
+}
+
+/// `SFENCE.VMA` instruction wrapper (single virtual address, all address spaces)
+///
+/// Orders stores to the page table leaf entries mapping `addr` before subsequent implicit references to
+/// them, in every address space.
+#[inline]
+#[allow(unused_variables)]
+pub unsafe fn sfence_vma_addr(addr: usize) {
+    match () {
+        #[cfg(all(riscv, feature = "inline-asm"))]
+        () => core::arch::asm!("sfence.vma {0}, x0", in(reg) addr),
+
+        #[cfg(all(riscv, not(feature = "inline-asm")))]
+        () => {
+            extern "C" {
+                fn __sfence_vma_addr(addr: usize);
+            }
+
+            __sfence_vma_addr(addr);
+        }
+
+        #[cfg(not(riscv))]
+        () => unimplemented!(),
+    }
+}
+
+// Generates wrappers for instructions taking a single address in `a0`, for extensions that LLVM may not
+// recognize yet.
+macro_rules! instruction_addr {
+    ($(#[$attr:meta])*, $fnname:ident, $asm:expr, $asm_fn:ident) => (
+        $(#[$attr])*
+        #[inline]
+        #[allow(unused_variables)]
+        pub unsafe fn $fnname(addr: usize) {
+            match () {
+                #[cfg(all(riscv, feature = "inline-asm"))]
+                () => core::arch::asm!($asm, in("x10") addr),
+
+                #[cfg(all(riscv, not(feature = "inline-asm")))]
+                () => {
+                    extern "C" {
+                        fn $asm_fn(addr: usize);
+                    }
+
+                    $asm_fn(addr);
+                }
+
+                #[cfg(not(riscv))]
+                () => unimplemented!(),
+            }
+        }
+    )
+}
+
+instruction_addr!(
+    /// `CBO.CLEAN` instruction wrapper (Zicbom extension)
+    ///
+    /// Writes the cache block containing `addr` back to memory if it is dirty; the block stays valid.
+    , cbo_clean, ".word 1384463", __cbo_clean);
+instruction_addr!(
+    /// `CBO.FLUSH` instruction wrapper (Zicbom extension)
+    ///
+    /// Writes the cache block containing `addr` back to memory if it is dirty, then invalidates it.
+    , cbo_flush, ".word 2433039", __cbo_flush);
+instruction_addr!(
+    /// `CBO.INVAL` instruction wrapper (Zicbom extension)
+    ///
+    /// Invalidates the cache block containing `addr` without writing it back, unless `menvcfg`/`senvcfg`
+    /// configure the operation as a flush. Dirty data elsewhere in the block is lost.
+    , cbo_inval, ".word 335887", __cbo_inval);
+instruction_addr!(
+    /// `CBO.ZERO` instruction wrapper (Zicboz extension)
+    ///
+    /// Stores zeros to the whole cache block containing `addr`.
+    , cbo_zero, ".word 4530191", __cbo_zero);
+
+// Generates safe wrappers for the Zicbop prefetch hints, which never trap and are no-ops off-target.
+macro_rules! prefetch {
+    ($(#[$attr:meta])*, $fnname:ident, $asm:expr, $asm_fn:ident) => (
+        $(#[$attr])*
+        #[inline]
+        #[allow(unused_variables)]
+        pub fn $fnname(addr: usize) {
+            match () {
+                #[cfg(all(riscv, feature = "inline-asm"))]
+                () => unsafe { core::arch::asm!($asm, in("x10") addr, options(nostack, preserves_flags)) },
+
+                #[cfg(all(riscv, not(feature = "inline-asm")))]
+                () => {
+                    extern "C" {
+                        fn $asm_fn(addr: usize);
+                    }
+
+                    unsafe { $asm_fn(addr) }
+                }
+
+                #[cfg(not(riscv))]
+                () => {}
+            }
+        }
+    )
+}
+
+prefetch!(
+    /// `PREFETCH.R` instruction wrapper (Zicbop extension)
+    ///
+    /// Hints that the cache block containing `addr` is likely to be read soon. Cores without the extension
+    /// execute it as a no-op.
+    , prefetch_read, ".word 1400851", __prefetch_read);
+prefetch!(
+    /// `PREFETCH.W` instruction wrapper (Zicbop extension)
+    ///
+    /// Hints that the cache block containing `addr` is likely to be written soon.
+    , prefetch_write, ".word 3498003", __prefetch_write);
+prefetch!(
+    /// `PREFETCH.I` instruction wrapper (Zicbop extension)
+    ///
+    /// Hints that the cache block containing `addr` is likely to be executed soon.
+    , prefetch_instruction, ".word 352275", __prefetch_instruction);
+
+/// Applies `op` to every cache block of `block_size` bytes overlapping `range`
+#[inline]
+unsafe fn cbo_range(range: Range<usize>, block_size: usize, op: unsafe fn(usize)) {
+    debug_assert!(block_size.is_power_of_two());
+    let mut addr = range.start & !(block_size - 1);
+    while addr < range.end {
+        op(addr);
+        addr = match addr.checked_add(block_size) {
+            Some(next) => next,
+            None => break,
+        };
+    }
+}
+
+/// Cleans every cache block overlapping `range`
+///
+/// `block_size` is the cache-block size of the platform in bytes, which the ISA does not expose (it is
+/// usually found in the device tree as `riscv,cbom-block-size`). Use before a device reads a DMA buffer.
+#[inline]
+pub unsafe fn cbo_clean_range(range: Range<usize>, block_size: usize) {
+    cbo_range(range, block_size, cbo_clean)
+}
+
+/// Flushes every cache block overlapping `range`
+///
+/// See `cbo_clean_range` for `block_size`.
+#[inline]
+pub unsafe fn cbo_flush_range(range: Range<usize>, block_size: usize) {
+    cbo_range(range, block_size, cbo_flush)
+}
+
+/// Invalidates every cache block overlapping `range`
+///
+/// See `cbo_clean_range` for `block_size`. Use after a device wrote a DMA buffer. Blocks partially covered
+/// by `range` are invalidated as a whole, so the buffer should be aligned to `block_size` at both ends.
+#[inline]
+pub unsafe fn cbo_inval_range(range: Range<usize>, block_size: usize) {
+    cbo_range(range, block_size, cbo_inval)
+}
+
+/// Zeroes every cache block overlapping `range`
+///
+/// `block_size` is the Zicboz block size of the platform (`riscv,cboz-block-size` in the device tree).
+/// Whole blocks are zeroed, so `range` must be aligned to `block_size` at both ends.
+#[inline]
+pub unsafe fn cbo_zero_range(range: Range<usize>, block_size: usize) {
+    debug_assert!(
+        block_size.is_power_of_two()
+            && range.start & (block_size - 1) == 0
+            && range.end & (block_size - 1) == 0
+    );
+    cbo_range(range, block_size, cbo_zero)
+}
+
+macro_rules! read_gpr {
+    ($(#[$attr:meta])*, $fnname:ident, $asm:expr, $asm_fn:ident) => (
+        $(#[$attr])*
+        #[inline]
+        pub fn $fnname() -> usize {
+            match () {
+                #[cfg(all(riscv, feature = "inline-asm"))]
+                () => {
+                    let r: usize;
+                    unsafe { core::arch::asm!($asm, out(reg) r) };
+                    r
+                }
+
+                #[cfg(all(riscv, not(feature = "inline-asm")))]
+                () => {
+                    extern "C" {
+                        fn $asm_fn() -> usize;
+                    }
+
+                    unsafe { $asm_fn() }
+                }
+
+                #[cfg(not(riscv))]
+                () => unimplemented!(),
+            }
+        }
+    )
+}
+
+read_gpr!(
+    /// Reads the `sp` (stack pointer) register
+    , read_sp, "mv {0}, sp", __read_sp);
+read_gpr!(
+    /// Reads the `ra` (return address) register
+    ///
+    /// `ra` is only meaningful before the calling function makes a call of its own. Without the `inline-asm`
+    /// feature this returns the address following the call to the assembly routine.
+    , read_ra, "mv {0}, ra", __read_ra);
+read_gpr!(
+    /// Reads the `gp` (global pointer) register
+    , read_gp, "mv {0}, gp", __read_gp);
+read_gpr!(
+    /// Reads the `fp`/`s0` (frame pointer) register
+    ///
+    /// Only holds a frame pointer when the code is compiled with `-C force-frame-pointers=yes`.
+    , read_fp, "mv {0}, s0", __read_fp);
+
+/// Reads the `tp` (thread pointer) register
+#[inline]
+pub fn read_tp() -> usize {
+    match () {
+        #[cfg(all(riscv, feature = "inline-asm"))]
+        () => {
+            let r: usize;
+            unsafe { core::arch::asm!("mv {0}, tp", out(reg) r) };
+            r
+        }
+
+        #[cfg(all(riscv, not(feature = "inline-asm")))]
+        () => {
+            extern "C" {
+                fn __read_tp() -> usize;
+            }
+
+            unsafe { __read_tp() }
+        }
+
+        #[cfg(not(riscv))]
+        () => unimplemented!(),
+    }
+}
+
+/// Writes the `tp` (thread pointer) register
+///
+/// # Safety
+///
+/// Code generated for thread-local storage may rely on `tp`; only overwrite it when
+/// nothing else on the current hart uses it.
+#[inline]
+#[allow(unused_variables)]
+pub unsafe fn write_tp(bits: usize) {
+    match () {
+        #[cfg(all(riscv, feature = "inline-asm"))]
+        () => core::arch::asm!("mv tp, {0}", in(reg) bits),
+
+        #[cfg(all(riscv, not(feature = "inline-asm")))]
+        () => {
+            extern "C" {
+                fn __write_tp(bits: usize);
+            }
+
+            __write_tp(bits);
+        }
+
+        #[cfg(not(riscv))]
+        () => unimplemented!(),
+    }
+}
+
 mod hypervisor_extension {
     // Generating instructions for Hypervisor extension.
-    // There are two kinds of instructions: rs1/rs2 type and rs1/rd type.
+    // There are rs1/rs2 type and rs1/rd type instructions, plus fences taking no or a single operand.
     // Also special register handling is required before LLVM could generate inline assembly for extended instructions.
     macro_rules! instruction_hypervisor_extension {
         (RS1_RS2, $(#[$attr:meta])*, $fnname:ident, $asm:expr, $asm_fn:ident) => (
@@ -103,6 +720,52 @@ mod hypervisor_extension {
                 }
             }
         );
+        (NONE, $(#[$attr:meta])*, $fnname:ident, $asm:expr, $asm_fn:ident) => (
+            $(#[$attr])*
+            #[inline]
+            pub unsafe fn $fnname() {
+                match () {
+                    #[cfg(all(riscv, feature = "inline-asm"))]
+                    () => core::arch::asm!($asm),
+
+                    #[cfg(all(riscv, not(feature = "inline-asm")))]
+                    () => {
+                        extern "C" {
+                            fn $asm_fn();
+                        }
+
+                        $asm_fn();
+                    }
+
+                    #[cfg(not(riscv))]
+                    () => unimplemented!(),
+                }
+            }
+        );
+        (A0, $(#[$attr:meta])*, $fnname:ident, $arg:ident => $value:expr, $asm:expr, $asm_fn:ident) => (
+            $(#[$attr])*
+            #[inline]
+            #[allow(unused_variables)]
+            pub unsafe fn $fnname($arg: usize) {
+                match () {
+                    #[cfg(all(riscv, feature = "inline-asm"))]
+                    // The operand is assumed to be placed in a0, as for the other instructions.
+                    () => core::arch::asm!($asm, in("x10") $value),
+
+                    #[cfg(all(riscv, not(feature = "inline-asm")))]
+                    () => {
+                        extern "C" {
+                            fn $asm_fn(a0: usize);
+                        }
+
+                        $asm_fn($value);
+                    }
+
+                    #[cfg(not(riscv))]
+                    () => unimplemented!(),
+                }
+            }
+        );
         (RS1_RD, $(#[$attr:meta])*, $fnname:ident, $asm:expr, $asm_fn:ident) => (
             $(#[$attr])*
             #[inline]
@@ -134,6 +797,25 @@ mod hypervisor_extension {
 
     instruction_hypervisor_extension!(RS1_RS2,,hfence_gvma,".word 1656029299",__hfence_gvma);
     instruction_hypervisor_extension!(RS1_RS2,,hfence_vvma,".word 582287475",__hfence_vvma);
+    instruction_hypervisor_extension!(NONE,
+        /// `HFENCE.GVMA` for all guest physical addresses and VMIDs
+        , hfence_gvma_all, ".word 1644167283", __hfence_gvma_all);
+    instruction_hypervisor_extension!(A0,
+        /// `HFENCE.GVMA` for all guest physical addresses of the virtual machine `vmid`
+        , hfence_gvma_vmid, vmid => vmid, ".word 1654653043", __hfence_gvma_vmid);
+    instruction_hypervisor_extension!(A0,
+        /// `HFENCE.GVMA` for the guest physical address `gpa`, in all VMIDs
+        , hfence_gvma_gpa, gpa => gpa >> 2, ".word 1644494963", __hfence_gvma_gpa);
+    instruction_hypervisor_extension!(NONE,
+        /// `HFENCE.VVMA` for all guest virtual addresses and ASIDs of the current virtual machine
+        , hfence_vvma_all, ".word 570425459", __hfence_vvma_all);
+    instruction_hypervisor_extension!(A0,
+        /// `HFENCE.VVMA` for all guest virtual addresses of the address space `asid` of the current virtual
+        /// machine
+        , hfence_vvma_asid, asid => asid, ".word 580911219", __hfence_vvma_asid);
+    instruction_hypervisor_extension!(A0,
+        /// `HFENCE.VVMA` for the guest virtual address `gva`, in all ASIDs of the current virtual machine
+        , hfence_vvma_gva, gva => gva, ".word 570753139", __hfence_vvma_gva);
     instruction_hypervisor_extension!(RS1_RD,,hlv_b,".word 1610958195",__hlv_b);
     instruction_hypervisor_extension!(RS1_RD,,hlv_bu,".word 1612006771",__hlv_bu);
     instruction_hypervisor_extension!(RS1_RD,,hlv_h,".word 1678067059",__hlv_h);
@@ -150,3 +832,87 @@ mod hypervisor_extension {
 }
 
 pub use self::hypervisor_extension::*;
+
+#[doc(hidden)]
+pub use core::arch::asm as __asm;
+
+#[doc(hidden)]
+pub unsafe fn __custom_insn_unsupported() -> ! {
+    unimplemented!()
+}
+
+/// Emits a custom instruction through the `.insn` directive
+///
+/// Meant for vendor extensions the assembler does not know. Register operands are `usize` values, the
+/// opcode, function codes and immediates must be constants. The expansion must be wrapped in `unsafe`
+/// and requires a RISC-V target; elsewhere it panics.
+///
+/// - `custom_insn!(r, opcode, funct3, funct7, rs1, rs2)` emits an R-type instruction and returns `rd`
+/// - `custom_insn!(i, opcode, funct3, rs1, imm)` emits an I-type instruction and returns `rd`
+/// - `custom_insn!(s, opcode, funct3, rs2, imm(rs1))` emits an S-type instruction
+///
+/// ```ignore
+/// // custom-0 opcode, e.g. an accelerator multiply-accumulate
+/// let acc = unsafe { custom_insn!(r, 0x0b, 0, 1, a, b) };
+/// ```
+#[macro_export]
+macro_rules! custom_insn {
+    (r, $opcode:expr, $funct3:expr, $funct7:expr, $rs1:expr, $rs2:expr) => {{
+        let rs1: usize = $rs1;
+        let rs2: usize = $rs2;
+        let rd: usize;
+        #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+        $crate::asm::__asm!(
+            ".insn r {opcode}, {funct3}, {funct7}, {rd}, {rs1}, {rs2}",
+            opcode = const $opcode,
+            funct3 = const $funct3,
+            funct7 = const $funct7,
+            rd = lateout(reg) rd,
+            rs1 = in(reg) rs1,
+            rs2 = in(reg) rs2,
+        );
+        #[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+        {
+            let _ = (rs1, rs2);
+            rd = $crate::asm::__custom_insn_unsupported();
+        }
+        rd
+    }};
+    (i, $opcode:expr, $funct3:expr, $rs1:expr, $imm:expr) => {{
+        let rs1: usize = $rs1;
+        let rd: usize;
+        #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+        $crate::asm::__asm!(
+            ".insn i {opcode}, {funct3}, {rd}, {rs1}, {imm}",
+            opcode = const $opcode,
+            funct3 = const $funct3,
+            imm = const $imm,
+            rd = lateout(reg) rd,
+            rs1 = in(reg) rs1,
+        );
+        #[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+        {
+            let _ = rs1;
+            rd = $crate::asm::__custom_insn_unsupported();
+        }
+        rd
+    }};
+    (s, $opcode:expr, $funct3:expr, $rs2:expr, $imm:tt($rs1:expr)) => {{
+        let rs1: usize = $rs1;
+        let rs2: usize = $rs2;
+        #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+        $crate::asm::__asm!(
+            ".insn s {opcode}, {funct3}, {rs2}, {imm}({rs1})",
+            opcode = const $opcode,
+            funct3 = const $funct3,
+            imm = const $imm,
+            rs1 = in(reg) rs1,
+            rs2 = in(reg) rs2,
+        );
+        #[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+        {
+            let _ = (rs1, rs2);
+            $crate::asm::__custom_insn_unsupported();
+        }
+    }};
+}
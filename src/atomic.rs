@@ -0,0 +1,343 @@
+//! Atomics emulated with critical sections
+//!
+//! For cores without the A extension (e.g. RV32I/RV32E microcontrollers),
+//! whose targets provide atomic loads and stores but no read-modify-write
+//! operations. The types mirror the `core::sync::atomic` API; loads and
+//! stores are single instructions, and read-modify-write operations run
+//! inside `critical_section::with`.
+//!
+//! The critical section implementation decides what is excluded: the one of
+//! the `critical-section-single-hart` feature only masks interrupts on the
+//! current hart and is thus only sound on single-hart systems, while
+//! multi-hart platforms must provide one that also locks out the other
+//! harts. All operations are sequentially consistent; the `Ordering`
+//! arguments are accepted for compatibility and otherwise ignored.
+
+use core::cell::UnsafeCell;
+use core::ptr;
+use core::sync::atomic::{compiler_fence, Ordering};
+
+/// Runs `f` on the value behind `cell` in a critical section, storing the
+/// result and returning the previous value
+#[inline]
+fn modify<T: Copy, F: FnOnce(T) -> T>(cell: &UnsafeCell<T>, f: F) -> T {
+    ::critical_section::with(|_| unsafe {
+        let previous = *cell.get();
+        *cell.get() = f(previous);
+        previous
+    })
+}
+
+/// Runs `f` on the value behind `cell` in a critical section, storing the
+/// result if it is `Some`
+#[inline]
+fn update<T: Copy, F: FnOnce(T) -> Option<T>>(cell: &UnsafeCell<T>, f: F) -> Result<T, T> {
+    ::critical_section::with(|_| unsafe {
+        let previous = *cell.get();
+        match f(previous) {
+            Some(next) => {
+                *cell.get() = next;
+                Ok(previous)
+            }
+            None => Err(previous),
+        }
+    })
+}
+
+#[inline]
+fn load<T: Copy>(cell: &UnsafeCell<T>) -> T {
+    compiler_fence(Ordering::SeqCst);
+    let value = unsafe { ptr::read_volatile(cell.get()) };
+    compiler_fence(Ordering::SeqCst);
+    value
+}
+
+#[inline]
+fn store<T: Copy>(cell: &UnsafeCell<T>, value: T) {
+    compiler_fence(Ordering::SeqCst);
+    unsafe { ptr::write_volatile(cell.get(), value) };
+    compiler_fence(Ordering::SeqCst);
+}
+
+/// A boolean that can be shared between interrupt handlers and harts
+#[repr(transparent)]
+#[derive(Default)]
+pub struct AtomicBool {
+    v: UnsafeCell<bool>,
+}
+
+unsafe impl Sync for AtomicBool {}
+
+impl AtomicBool {
+    /// Creates a new `AtomicBool`
+    #[inline]
+    pub const fn new(v: bool) -> Self {
+        AtomicBool {
+            v: UnsafeCell::new(v),
+        }
+    }
+
+    /// Returns a mutable reference to the value
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut bool {
+        self.v.get_mut()
+    }
+
+    /// Consumes the atomic, returning the value
+    #[inline]
+    pub fn into_inner(self) -> bool {
+        self.v.into_inner()
+    }
+
+    /// Loads the value
+    #[inline]
+    pub fn load(&self, _order: Ordering) -> bool {
+        load(&self.v)
+    }
+
+    /// Stores `val`
+    #[inline]
+    pub fn store(&self, val: bool, _order: Ordering) {
+        store(&self.v, val)
+    }
+
+    /// Stores `val`, returning the previous value
+    #[inline]
+    pub fn swap(&self, val: bool, _order: Ordering) -> bool {
+        modify(&self.v, |_| val)
+    }
+
+    /// Stores `new` if the value is `current`
+    ///
+    /// Returns the previous value, as `Ok` if it was `current`.
+    #[inline]
+    pub fn compare_exchange(
+        &self,
+        current: bool,
+        new: bool,
+        _success: Ordering,
+        _failure: Ordering,
+    ) -> Result<bool, bool> {
+        update(&self.v, |v| if v == current { Some(new) } else { None })
+    }
+
+    /// Like `compare_exchange`, which never fails spuriously
+    #[inline]
+    pub fn compare_exchange_weak(
+        &self,
+        current: bool,
+        new: bool,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<bool, bool> {
+        self.compare_exchange(current, new, success, failure)
+    }
+
+    /// Logical "and" with `val`, returning the previous value
+    #[inline]
+    pub fn fetch_and(&self, val: bool, _order: Ordering) -> bool {
+        modify(&self.v, |v| v & val)
+    }
+
+    /// Logical "nand" with `val`, returning the previous value
+    #[inline]
+    pub fn fetch_nand(&self, val: bool, _order: Ordering) -> bool {
+        modify(&self.v, |v| !(v & val))
+    }
+
+    /// Logical "or" with `val`, returning the previous value
+    #[inline]
+    pub fn fetch_or(&self, val: bool, _order: Ordering) -> bool {
+        modify(&self.v, |v| v | val)
+    }
+
+    /// Logical "xor" with `val`, returning the previous value
+    #[inline]
+    pub fn fetch_xor(&self, val: bool, _order: Ordering) -> bool {
+        modify(&self.v, |v| v ^ val)
+    }
+
+    /// Applies `f` to the value, storing the result if it is `Some`
+    ///
+    /// Returns the previous value, as `Ok` if `f` returned `Some`.
+    #[inline]
+    pub fn fetch_update<F>(
+        &self,
+        _set_order: Ordering,
+        _fetch_order: Ordering,
+        f: F,
+    ) -> Result<bool, bool>
+    where
+        F: FnMut(bool) -> Option<bool>,
+    {
+        update(&self.v, f)
+    }
+}
+
+macro_rules! atomic_int {
+    ($(#[$attr:meta])* $name:ident, $int:ty) => {
+        $(#[$attr])*
+        #[repr(transparent)]
+        #[derive(Default)]
+        pub struct $name {
+            v: UnsafeCell<$int>,
+        }
+
+        unsafe impl Sync for $name {}
+
+        impl $name {
+            /// Creates a new atomic integer
+            #[inline]
+            pub const fn new(v: $int) -> Self {
+                $name {
+                    v: UnsafeCell::new(v),
+                }
+            }
+
+            /// Returns a mutable reference to the value
+            #[inline]
+            pub fn get_mut(&mut self) -> &mut $int {
+                self.v.get_mut()
+            }
+
+            /// Consumes the atomic, returning the value
+            #[inline]
+            pub fn into_inner(self) -> $int {
+                self.v.into_inner()
+            }
+
+            /// Loads the value
+            #[inline]
+            pub fn load(&self, _order: Ordering) -> $int {
+                load(&self.v)
+            }
+
+            /// Stores `val`
+            #[inline]
+            pub fn store(&self, val: $int, _order: Ordering) {
+                store(&self.v, val)
+            }
+
+            /// Stores `val`, returning the previous value
+            #[inline]
+            pub fn swap(&self, val: $int, _order: Ordering) -> $int {
+                modify(&self.v, |_| val)
+            }
+
+            /// Stores `new` if the value is `current`
+            ///
+            /// Returns the previous value, as `Ok` if it was `current`.
+            #[inline]
+            pub fn compare_exchange(
+                &self,
+                current: $int,
+                new: $int,
+                _success: Ordering,
+                _failure: Ordering,
+            ) -> Result<$int, $int> {
+                update(&self.v, |v| if v == current { Some(new) } else { None })
+            }
+
+            /// Like `compare_exchange`, which never fails spuriously
+            #[inline]
+            pub fn compare_exchange_weak(
+                &self,
+                current: $int,
+                new: $int,
+                success: Ordering,
+                failure: Ordering,
+            ) -> Result<$int, $int> {
+                self.compare_exchange(current, new, success, failure)
+            }
+
+            /// Adds `val` with wrap-around, returning the previous value
+            #[inline]
+            pub fn fetch_add(&self, val: $int, _order: Ordering) -> $int {
+                modify(&self.v, |v| v.wrapping_add(val))
+            }
+
+            /// Subtracts `val` with wrap-around, returning the previous value
+            #[inline]
+            pub fn fetch_sub(&self, val: $int, _order: Ordering) -> $int {
+                modify(&self.v, |v| v.wrapping_sub(val))
+            }
+
+            /// Bitwise "and" with `val`, returning the previous value
+            #[inline]
+            pub fn fetch_and(&self, val: $int, _order: Ordering) -> $int {
+                modify(&self.v, |v| v & val)
+            }
+
+            /// Bitwise "nand" with `val`, returning the previous value
+            #[inline]
+            pub fn fetch_nand(&self, val: $int, _order: Ordering) -> $int {
+                modify(&self.v, |v| !(v & val))
+            }
+
+            /// Bitwise "or" with `val`, returning the previous value
+            #[inline]
+            pub fn fetch_or(&self, val: $int, _order: Ordering) -> $int {
+                modify(&self.v, |v| v | val)
+            }
+
+            /// Bitwise "xor" with `val`, returning the previous value
+            #[inline]
+            pub fn fetch_xor(&self, val: $int, _order: Ordering) -> $int {
+                modify(&self.v, |v| v ^ val)
+            }
+
+            /// Stores the maximum of the value and `val`, returning the previous value
+            #[inline]
+            pub fn fetch_max(&self, val: $int, _order: Ordering) -> $int {
+                modify(&self.v, |v| v.max(val))
+            }
+
+            /// Stores the minimum of the value and `val`, returning the previous value
+            #[inline]
+            pub fn fetch_min(&self, val: $int, _order: Ordering) -> $int {
+                modify(&self.v, |v| v.min(val))
+            }
+
+            /// Applies `f` to the value, storing the result if it is `Some`
+            ///
+            /// Returns the previous value, as `Ok` if `f` returned `Some`.
+            #[inline]
+            pub fn fetch_update<F>(
+                &self,
+                _set_order: Ordering,
+                _fetch_order: Ordering,
+                f: F,
+            ) -> Result<$int, $int>
+            where
+                F: FnMut($int) -> Option<$int>,
+            {
+                update(&self.v, f)
+            }
+        }
+    };
+}
+
+atomic_int!(
+    /// An `i8` that can be shared between interrupt handlers and harts
+    AtomicI8, i8);
+atomic_int!(
+    /// A `u8` that can be shared between interrupt handlers and harts
+    AtomicU8, u8);
+atomic_int!(
+    /// An `i16` that can be shared between interrupt handlers and harts
+    AtomicI16, i16);
+atomic_int!(
+    /// A `u16` that can be shared between interrupt handlers and harts
+    AtomicU16, u16);
+atomic_int!(
+    /// An `i32` that can be shared between interrupt handlers and harts
+    AtomicI32, i32);
+atomic_int!(
+    /// A `u32` that can be shared between interrupt handlers and harts
+    AtomicU32, u32);
+atomic_int!(
+    /// An `isize` that can be shared between interrupt handlers and harts
+    AtomicIsize, isize);
+atomic_int!(
+    /// A `usize` that can be shared between interrupt handlers and harts
+    AtomicUsize, usize);
@@ -0,0 +1,73 @@
+//! Stack backtraces from frame pointers
+//!
+//! With frame pointers (`-C force-frame-pointers=yes`), `fp` points just above
+//! the frame of the current function, below which it saved its return address
+//! and the frame pointer of its caller:
+//!
+//! ```text
+//! fp - 1 * XLEN: return address
+//! fp - 2 * XLEN: frame pointer of the caller
+//! ```
+//!
+//! [`trace`] follows this chain from a given frame pointer, e.g. the `s0` of
+//! a trap frame, while checking every pointer against the bounds of the stack.
+
+use core::mem::size_of;
+use core::ops::Range;
+use core::ptr;
+
+/// A frame of the call stack
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Frame {
+    /// Frame pointer of the function
+    pub fp: usize,
+    /// Return address into the caller, just after the call instruction
+    pub ra: usize,
+}
+
+/// Calls `f` for each frame of the chain starting at `fp`, innermost first
+///
+/// The walk stops at a null return address, or when the next frame pointer is
+/// misaligned, outside of `stack`, or not above the current one (the stack
+/// grows downwards). Returns the number of frames visited.
+///
+/// # Safety
+///
+/// `stack` must be readable memory.
+pub unsafe fn trace<F>(fp: usize, stack: Range<usize>, mut f: F) -> usize
+where
+    F: FnMut(&Frame),
+{
+    const XLEN: usize = size_of::<usize>();
+    let mut fp = fp;
+    let mut depth = 0;
+    while fp.is_multiple_of(XLEN) && fp >= stack.start + 2 * XLEN && fp <= stack.end {
+        let ra = ptr::read_volatile((fp - XLEN) as *const usize);
+        if ra == 0 {
+            break;
+        }
+        f(&Frame { fp, ra });
+        depth += 1;
+        let next = ptr::read_volatile((fp - 2 * XLEN) as *const usize);
+        if next <= fp {
+            break;
+        }
+        fp = next;
+    }
+    depth
+}
+
+/// Calls `f` for each frame of the caller's call stack, innermost first
+///
+/// See [`trace`]; requires the code to be compiled with frame pointers.
+///
+/// # Safety
+///
+/// `stack` must be readable memory.
+#[inline(never)]
+pub unsafe fn trace_current<F>(stack: Range<usize>, f: F) -> usize
+where
+    F: FnMut(&Frame),
+{
+    trace(::asm::read_fp(), stack, f)
+}
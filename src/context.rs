@@ -0,0 +1,189 @@
+//! Cooperative context switching
+//!
+//! [`switch`] saves the callee-saved registers of the running task and loads
+//! those of another one, so a scheduler only has to keep one [`Context`] per
+//! task. On hard-float targets this includes `fs0`-`fs11` and `fcsr`; the
+//! other floating-point registers and the vector state are not switched, see
+//! [`fpu`](crate::fpu) and [`vector`](crate::vector).
+
+/// A callee-saved floating-point register
+#[cfg(target_feature = "d")]
+pub type FReg = u64;
+/// A callee-saved floating-point register
+#[cfg(all(target_feature = "f", not(target_feature = "d")))]
+pub type FReg = u32;
+
+/// Width in bytes of the saved floating-point registers, zero on soft-float
+/// targets
+#[cfg(all(riscv, target_feature = "d"))]
+const FLEN: usize = 8;
+#[cfg(all(riscv, target_feature = "f", not(target_feature = "d")))]
+const FLEN: usize = 4;
+#[cfg(all(riscv, not(target_feature = "f")))]
+const FLEN: usize = 0;
+
+/// Offsets of `Context::fs` and `Context::fcsr`
+#[cfg(riscv)]
+const FS_OFFSET: usize = 14 * core::mem::size_of::<usize>();
+#[cfg(riscv)]
+const FCSR_OFFSET: usize = FS_OFFSET + 12 * FLEN;
+
+/// Callee-saved registers of a suspended task
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Context {
+    /// Return address, where the task resumes
+    pub ra: usize,
+    /// Stack pointer
+    pub sp: usize,
+    /// Saved registers `s0`-`s11`
+    pub s: [usize; 12],
+    /// Saved floating-point registers `fs0`-`fs11`
+    #[cfg(target_feature = "f")]
+    pub fs: [FReg; 12],
+    /// Floating-point control and status register
+    #[cfg(target_feature = "f")]
+    pub fcsr: u32,
+}
+
+impl Context {
+    /// Creates the context of a task that starts at `entry` with an empty stack
+    ///
+    /// `stack_top` is the 16-byte aligned end of the stack of the task.
+    #[inline]
+    pub fn new(entry: extern "C" fn() -> !, stack_top: usize) -> Self {
+        Context {
+            ra: entry as usize,
+            sp: stack_top,
+            s: [0; 12],
+            #[cfg(target_feature = "f")]
+            fs: [0; 12],
+            #[cfg(target_feature = "f")]
+            fcsr: 0,
+        }
+    }
+}
+
+/// Saves the current task's registers to `old` and resumes the task in `new`
+///
+/// Returns when another task switches back to `old`.
+///
+/// # Safety
+///
+/// `new` must hold a context saved by `switch` or created with `Context::new`
+/// whose stack is still valid, and must not be running on any hart.
+#[inline]
+pub unsafe fn switch(old: &mut Context, new: &Context) {
+    match () {
+        #[cfg(riscv)]
+        () => {
+            extern "C" {
+                fn __switch_context(old: *mut Context, new: *const Context);
+            }
+
+            __switch_context(old, new);
+        }
+
+        #[cfg(not(riscv))]
+        () => {
+            let _ = (old, new);
+            unimplemented!()
+        }
+    }
+}
+
+#[cfg(riscv32)]
+core::arch::global_asm!(
+    ".section .text.__switch_context, \"ax\", @progbits",
+    ".global __switch_context",
+    ".align 2",
+    "__switch_context:",
+    "    sw ra, 0*4(a0)",
+    "    sw sp, 1*4(a0)",
+    "    .irp n, 0,1,2,3,4,5,6,7,8,9,10,11",
+    "    sw s\\n, (\\n+2)*4(a0)",
+    "    .endr",
+    "    .if {flen} == 4",
+    "    .irp n, 0,1,2,3,4,5,6,7,8,9,10,11",
+    "    fsw fs\\n, ({fs}+\\n*4)(a0)",
+    "    .endr",
+    "    .elseif {flen} == 8",
+    "    .irp n, 0,1,2,3,4,5,6,7,8,9,10,11",
+    "    fsd fs\\n, ({fs}+\\n*8)(a0)",
+    "    .endr",
+    "    .endif",
+    "    .if {flen}",
+    "    frcsr t0",
+    "    sw t0, {fcsr}(a0)",
+    "    .endif",
+    "    lw ra, 0*4(a1)",
+    "    lw sp, 1*4(a1)",
+    "    .irp n, 0,1,2,3,4,5,6,7,8,9,10,11",
+    "    lw s\\n, (\\n+2)*4(a1)",
+    "    .endr",
+    "    .if {flen} == 4",
+    "    .irp n, 0,1,2,3,4,5,6,7,8,9,10,11",
+    "    flw fs\\n, ({fs}+\\n*4)(a1)",
+    "    .endr",
+    "    .elseif {flen} == 8",
+    "    .irp n, 0,1,2,3,4,5,6,7,8,9,10,11",
+    "    fld fs\\n, ({fs}+\\n*8)(a1)",
+    "    .endr",
+    "    .endif",
+    "    .if {flen}",
+    "    lw t0, {fcsr}(a1)",
+    "    fscsr t0",
+    "    .endif",
+    "    ret",
+    flen = const FLEN,
+    fs = const FS_OFFSET,
+    fcsr = const FCSR_OFFSET,
+);
+
+#[cfg(riscv64)]
+core::arch::global_asm!(
+    ".section .text.__switch_context, \"ax\", @progbits",
+    ".global __switch_context",
+    ".align 2",
+    "__switch_context:",
+    "    sd ra, 0*8(a0)",
+    "    sd sp, 1*8(a0)",
+    "    .irp n, 0,1,2,3,4,5,6,7,8,9,10,11",
+    "    sd s\\n, (\\n+2)*8(a0)",
+    "    .endr",
+    "    .if {flen} == 4",
+    "    .irp n, 0,1,2,3,4,5,6,7,8,9,10,11",
+    "    fsw fs\\n, ({fs}+\\n*4)(a0)",
+    "    .endr",
+    "    .elseif {flen} == 8",
+    "    .irp n, 0,1,2,3,4,5,6,7,8,9,10,11",
+    "    fsd fs\\n, ({fs}+\\n*8)(a0)",
+    "    .endr",
+    "    .endif",
+    "    .if {flen}",
+    "    frcsr t0",
+    "    sw t0, {fcsr}(a0)",
+    "    .endif",
+    "    ld ra, 0*8(a1)",
+    "    ld sp, 1*8(a1)",
+    "    .irp n, 0,1,2,3,4,5,6,7,8,9,10,11",
+    "    ld s\\n, (\\n+2)*8(a1)",
+    "    .endr",
+    "    .if {flen} == 4",
+    "    .irp n, 0,1,2,3,4,5,6,7,8,9,10,11",
+    "    flw fs\\n, ({fs}+\\n*4)(a1)",
+    "    .endr",
+    "    .elseif {flen} == 8",
+    "    .irp n, 0,1,2,3,4,5,6,7,8,9,10,11",
+    "    fld fs\\n, ({fs}+\\n*8)(a1)",
+    "    .endr",
+    "    .endif",
+    "    .if {flen}",
+    "    lw t0, {fcsr}(a1)",
+    "    fscsr t0",
+    "    .endif",
+    "    ret",
+    flen = const FLEN,
+    fs = const FS_OFFSET,
+    fcsr = const FCSR_OFFSET,
+);
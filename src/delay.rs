@@ -0,0 +1,166 @@
+//! Busy-wait delays
+//!
+//! [`Delay`] converts durations to ticks of a free-running 64-bit counter
+//! and spins until they have elapsed. With the `embedded-hal` (1.0) or
+//! `embedded-hal-02` (0.2) features it implements the respective delay
+//! traits, so HALs can hand it out directly.
+
+use core::ptr;
+use core::time::Duration;
+
+/// Counter a [`Delay`] is driven by
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Counter {
+    /// `mcycle`, or `cycle` with the `s-mode` feature, which then must be
+    /// enabled in `mcounteren`
+    Cycle,
+    /// The `time` CSR
+    Time,
+    /// A memory-mapped `mtime` register at the given address (e.g. in a CLINT)
+    Mtime(usize),
+}
+
+impl Counter {
+    /// Reads the current counter value
+    #[inline]
+    pub fn read(self) -> u64 {
+        match self {
+            #[cfg(not(feature = "s-mode"))]
+            Counter::Cycle => ::register::mcycle::read64(),
+            #[cfg(feature = "s-mode")]
+            Counter::Cycle => ::register::cycle::read64(),
+            Counter::Time => ::register::time::read64(),
+            Counter::Mtime(addr) => unsafe { read_mtime(addr) },
+        }
+    }
+}
+
+/// Reads a 64-bit memory-mapped timer, in two halves on RV32
+#[inline]
+pub(crate) unsafe fn read_mtime(addr: usize) -> u64 {
+    match () {
+        #[cfg(riscv32)]
+        () => loop {
+            let hi = ptr::read_volatile((addr + 4) as *const u32);
+            let lo = ptr::read_volatile(addr as *const u32);
+            if hi == ptr::read_volatile((addr + 4) as *const u32) {
+                return ((hi as u64) << 32) | lo as u64;
+            }
+        },
+
+        #[cfg(not(riscv32))]
+        () => ptr::read_volatile(addr as *const u64),
+    }
+}
+
+/// Busy-wait delay driven by a free-running counter
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Delay {
+    counter: Counter,
+    frequency: u32,
+}
+
+impl Delay {
+    /// Creates a delay driven by `counter`, which ticks at `frequency` Hz
+    ///
+    /// # Safety
+    ///
+    /// `Counter::Mtime` must hold the address of a readable `mtime` register.
+    #[inline]
+    pub const unsafe fn new(counter: Counter, frequency: u32) -> Self {
+        Delay { counter, frequency }
+    }
+
+    /// Creates a delay driven by the cycle counter of a core clocked at
+    /// `frequency` Hz
+    #[inline]
+    pub const fn cycles(frequency: u32) -> Self {
+        Delay {
+            counter: Counter::Cycle,
+            frequency,
+        }
+    }
+
+    /// Creates a delay driven by the `time` CSR, which ticks at the
+    /// `frequency` Hz of the platform timebase
+    #[inline]
+    pub const fn time(frequency: u32) -> Self {
+        Delay {
+            counter: Counter::Time,
+            frequency,
+        }
+    }
+
+    /// Returns the counter driving the delay
+    #[inline]
+    pub fn counter(&self) -> Counter {
+        self.counter
+    }
+
+    /// Returns the counter frequency in Hz
+    #[inline]
+    pub fn frequency(&self) -> u32 {
+        self.frequency
+    }
+
+    /// Converts `duration` to counter ticks, rounding up
+    #[inline]
+    pub fn ticks(&self, duration: Duration) -> u64 {
+        ::time::duration_to_ticks(duration, self.frequency)
+    }
+
+    /// Spins until the counter advanced by `ticks`
+    #[inline]
+    pub fn delay_ticks(&self, ticks: u64) {
+        let start = self.counter.read();
+        while self.counter.read().wrapping_sub(start) < ticks {}
+    }
+
+    /// Spins for at least `duration`
+    #[inline]
+    pub fn delay(&self, duration: Duration) {
+        self.delay_ticks(self.ticks(duration))
+    }
+}
+
+#[cfg(feature = "embedded-hal")]
+impl ::embedded_hal::delay::DelayNs for Delay {
+    #[inline]
+    fn delay_ns(&mut self, ns: u32) {
+        Delay::delay(self, Duration::from_nanos(ns as u64))
+    }
+
+    #[inline]
+    fn delay_us(&mut self, us: u32) {
+        Delay::delay(self, Duration::from_micros(us as u64))
+    }
+
+    #[inline]
+    fn delay_ms(&mut self, ms: u32) {
+        Delay::delay(self, Duration::from_millis(ms as u64))
+    }
+}
+
+#[cfg(feature = "embedded-hal-02")]
+macro_rules! impl_delay_02 {
+    ($($ty:ty),+) => {
+        $(
+            impl ::embedded_hal_02::blocking::delay::DelayUs<$ty> for Delay {
+                #[inline]
+                fn delay_us(&mut self, us: $ty) {
+                    Delay::delay(self, Duration::from_micros(us as u64))
+                }
+            }
+
+            impl ::embedded_hal_02::blocking::delay::DelayMs<$ty> for Delay {
+                #[inline]
+                fn delay_ms(&mut self, ms: $ty) {
+                    Delay::delay(self, Duration::from_millis(ms as u64))
+                }
+            }
+        )+
+    };
+}
+
+#[cfg(feature = "embedded-hal-02")]
+impl_delay_02!(u8, u16, u32, u64);
@@ -0,0 +1,218 @@
+//! Entropy sources
+//!
+//! [`SeedRng`] reads the `seed` CSR of the Zkr extension, and [`JitterRng`]
+//! derives bits from timing jitter measured with the cycle counter on cores
+//! without it. With the `rand_core` feature both implement
+//! `rand_core::RngCore`, and `SeedRng` also `CryptoRng`.
+//!
+//! Both return raw entropy. The Zkr specification expects `seed` output to
+//! be conditioned before it is used as key material, typically by seeding a
+//! cryptographic DRBG from it rather than using it directly.
+
+use core::fmt;
+#[cfg(feature = "rand_core")]
+use core::num::NonZeroU32;
+
+use delay::Counter;
+use register::seed;
+
+/// Error returned when the entropy source failed its self-test (`seed`
+/// reports `Dead`)
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct SourceDead;
+
+impl fmt::Display for SourceDead {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("entropy source is dead")
+    }
+}
+
+#[cfg(feature = "core-error")]
+impl core::error::Error for SourceDead {}
+
+/// Random numbers from the Zkr `seed` CSR
+///
+/// Every 16-bit `ES16` sample is used once; samples are concatenated to
+/// wider values, and `BIST` and `WAIT` states are polled through. In S-mode
+/// the CSR must have been made accessible with `mseccfg.SSEED`.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SeedRng {
+    _private: (),
+}
+
+impl SeedRng {
+    /// Creates the generator
+    #[inline]
+    pub const fn new() -> Self {
+        SeedRng { _private: () }
+    }
+
+    /// Returns 16 bits of entropy
+    #[inline]
+    pub fn try_next_u16(&mut self) -> Result<u16, SourceDead> {
+        seed::get_entropy_u16().ok_or(SourceDead)
+    }
+
+    /// Returns 32 bits of entropy from two samples
+    #[inline]
+    pub fn try_next_u32(&mut self) -> Result<u32, SourceDead> {
+        let low = self.try_next_u16()? as u32;
+        let high = self.try_next_u16()? as u32;
+        Ok((high << 16) | low)
+    }
+
+    /// Returns 64 bits of entropy from four samples
+    #[inline]
+    pub fn try_next_u64(&mut self) -> Result<u64, SourceDead> {
+        let low = self.try_next_u32()? as u64;
+        let high = self.try_next_u32()? as u64;
+        Ok((high << 32) | low)
+    }
+
+    /// Fills `dest` with entropy, using one sample per two bytes
+    pub fn try_fill(&mut self, dest: &mut [u8]) -> Result<(), SourceDead> {
+        for chunk in dest.chunks_mut(2) {
+            let bytes = self.try_next_u16()?.to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+        Ok(())
+    }
+}
+
+/// Random numbers from timing jitter, for cores without Zkr
+///
+/// Each output bit is folded from several measurements of how many cycles a
+/// short sequence of memory accesses takes. The quality depends entirely on
+/// the noise of the core and its memory system: on a simple in-order core
+/// the measurements may be fully deterministic. Use it for seeding
+/// non-cryptographic generators only.
+#[derive(Copy, Clone, Debug)]
+pub struct JitterRng {
+    samples: u32,
+}
+
+impl Default for JitterRng {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JitterRng {
+    /// Creates a generator folding 64 measurements into each 32-bit output
+    #[inline]
+    pub const fn new() -> Self {
+        JitterRng { samples: 64 }
+    }
+
+    /// Creates a generator folding `samples` measurements into each 32-bit
+    /// output
+    #[inline]
+    pub const fn with_samples(samples: u32) -> Self {
+        JitterRng { samples }
+    }
+
+    /// Measures the cycles taken by a few dependent memory accesses
+    #[inline(never)]
+    fn measure(&self) -> u64 {
+        let mut scratch = [0usize; 8];
+        let start = Counter::Cycle.read();
+        for i in 0..scratch.len() {
+            let previous = unsafe { core::ptr::read_volatile(&scratch[(i + 7) % 8]) };
+            unsafe { core::ptr::write_volatile(&mut scratch[i], previous.wrapping_add(i)) };
+        }
+        Counter::Cycle.read().wrapping_sub(start)
+    }
+
+    /// Returns 32 bits folded from the measurements
+    pub fn next_u32(&mut self) -> u32 {
+        let mut pool = 0u64;
+        for _ in 0..self.samples {
+            pool = pool.rotate_left(7) ^ self.measure();
+        }
+        (pool ^ (pool >> 32)) as u32
+    }
+
+    /// Returns 64 bits from two outputs of `next_u32`
+    #[inline]
+    pub fn next_u64(&mut self) -> u64 {
+        let low = self.next_u32() as u64;
+        ((self.next_u32() as u64) << 32) | low
+    }
+
+    /// Fills `dest` with outputs of `next_u32`
+    pub fn fill(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(4) {
+            let bytes = self.next_u32().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+}
+
+#[cfg(feature = "rand_core")]
+impl From<SourceDead> for ::rand_core::Error {
+    #[inline]
+    fn from(_: SourceDead) -> Self {
+        let code = NonZeroU32::new(::rand_core::Error::CUSTOM_START).unwrap();
+        ::rand_core::Error::from(code)
+    }
+}
+
+#[cfg(feature = "rand_core")]
+impl ::rand_core::RngCore for SeedRng {
+    /// # Panics
+    ///
+    /// Panics if the entropy source is dead.
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        self.try_next_u32().expect("entropy source is dead")
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the entropy source is dead.
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.try_next_u64().expect("entropy source is dead")
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the entropy source is dead.
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill(dest).expect("entropy source is dead")
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), ::rand_core::Error> {
+        Ok(self.try_fill(dest)?)
+    }
+}
+
+#[cfg(feature = "rand_core")]
+impl ::rand_core::CryptoRng for SeedRng {}
+
+#[cfg(feature = "rand_core")]
+impl ::rand_core::RngCore for JitterRng {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        JitterRng::next_u32(self)
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        JitterRng::next_u64(self)
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.fill(dest)
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), ::rand_core::Error> {
+        self.fill(dest);
+        Ok(())
+    }
+}
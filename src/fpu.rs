@@ -0,0 +1,280 @@
+//! Lazy floating-point context switching
+//!
+//! The FS field of `mstatus` (or of `sstatus` with the `s-mode` feature)
+//! tracks whether the floating-point registers were modified. A scheduler can
+//! switch them lazily:
+//!
+//! - on a task switch, save the registers of the outgoing task only if they
+//!   are dirty ([`save_if_dirty`]) and turn the unit off ([`disable`]);
+//! - the first floating-point instruction of the incoming task then raises an
+//!   illegal instruction exception ([`is_fp_instruction`]), on which the
+//!   handler restores its registers ([`restore`]) and retries the instruction.
+//!
+//! The save and restore routines use the D extension and require it.
+
+use insn::TrappedInstruction;
+
+pub use register::mstatus::FS;
+
+/// Saved floating-point registers
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct FRegs {
+    /// Registers `f0`-`f31`
+    pub f: [u64; 32],
+    /// Floating-point control and status register
+    pub fcsr: u32,
+}
+
+/// Returns the state of the floating-point unit
+#[inline]
+pub fn state() -> FS {
+    match () {
+        #[cfg(not(feature = "s-mode"))]
+        () => ::register::mstatus::read().fs(),
+        #[cfg(feature = "s-mode")]
+        () => ::register::sstatus::read().fs(),
+    }
+}
+
+/// Sets the state of the floating-point unit
+///
+/// # Safety
+///
+/// Setting the state to `Clean` or `Initial` while the registers hold unsaved
+/// values of a task may lose them.
+#[inline]
+pub unsafe fn set_state(fs: FS) {
+    match () {
+        #[cfg(not(feature = "s-mode"))]
+        () => ::register::mstatus::set_fs(fs),
+        #[cfg(feature = "s-mode")]
+        () => ::register::sstatus::modify(|s| s.set_fs(fs)),
+    }
+}
+
+/// Returns true if the floating-point registers were written since they were
+/// last saved or restored
+#[inline]
+pub fn is_dirty() -> bool {
+    state() == FS::Dirty
+}
+
+/// Turns the floating-point unit off
+///
+/// Floating-point instructions raise an illegal instruction exception until
+/// the state is changed again.
+///
+/// # Safety
+///
+/// See [`set_state`]; the registers of the current task should have been saved.
+#[inline]
+pub unsafe fn disable() {
+    set_state(FS::Off)
+}
+
+/// Stores `f0`-`f31` and `fcsr` to `regs`
+///
+/// # Safety
+///
+/// The floating-point unit must not be `Off`, and the D extension must be
+/// implemented.
+#[inline]
+pub unsafe fn fstate_save(regs: &mut FRegs) {
+    match () {
+        #[cfg(all(riscv, feature = "inline-asm"))]
+        // fsd f0-f31, 0-248(a0)
+        () => core::arch::asm!(
+            ".word 340007, 1389607, 2439207, 3488807, 38088743, 39138343, 40187943, 41237543",
+            ".word 75837479, 76887079, 77936679, 78986279, 113586215, 114635815, 115685415, 116735015",
+            ".word 151334951, 152384551, 153434151, 154483751, 189083687, 190133287, 191182887, 192232487",
+            ".word 226832423, 227882023, 228931623, 229981223, 264581159, 265630759, 266680359, 267729959",
+            "csrr {0}, 0x003",
+            "sw {0}, 256(a0)",
+            out(reg) _,
+            in("x10") regs as *mut FRegs,
+            options(nostack)
+        ),
+
+        #[cfg(all(riscv, not(feature = "inline-asm")))]
+        () => {
+            extern "C" {
+                fn __fstate_save(regs: *mut FRegs);
+            }
+
+            __fstate_save(regs);
+        }
+
+        #[cfg(not(riscv))]
+        () => {
+            let _ = regs;
+            unimplemented!()
+        }
+    }
+}
+
+// fld f0-f31, 0-248(a0), followed by the given operands
+#[cfg(all(riscv, feature = "inline-asm"))]
+macro_rules! fstate_restore_asm {
+    ($regs:expr, $($operands:tt)*) => {
+        core::arch::asm!(
+            "lw {0}, 256(a0)",
+            "csrw 0x003, {0}",
+            ".word 339975, 8728711, 17117447, 25506183, 33894919, 42283655, 50672391, 59061127",
+            ".word 67449863, 75838599, 84227335, 92616071, 101004807, 109393543, 117782279, 126171015",
+            ".word 134559751, 142948487, 151337223, 159725959, 168114695, 176503431, 184892167, 193280903",
+            ".word 201669639, 210058375, 218447111, 226835847, 235224583, 243613319, 252002055, 260390791",
+            out(reg) _,
+            in("x10") $regs,
+            $($operands)*
+            options(nostack)
+        )
+    };
+}
+
+/// Loads `f0`-`f31` and `fcsr` from `regs`
+///
+/// On hard-float targets the compiler is told that all floating-point
+/// registers are overwritten, so values of the calling code are preserved.
+///
+/// # Safety
+///
+/// The floating-point unit must not be `Off`, and the D extension must be
+/// implemented.
+#[inline]
+pub unsafe fn fstate_restore(regs: &FRegs) {
+    match () {
+        #[cfg(all(riscv, feature = "inline-asm", not(target_feature = "f")))]
+        () => fstate_restore_asm!(regs as *const FRegs,),
+
+        #[cfg(all(riscv, feature = "inline-asm", target_feature = "f"))]
+        () => fstate_restore_asm!(
+            regs as *const FRegs,
+            out("f0") _,
+            out("f1") _,
+            out("f2") _,
+            out("f3") _,
+            out("f4") _,
+            out("f5") _,
+            out("f6") _,
+            out("f7") _,
+            out("f8") _,
+            out("f9") _,
+            out("f10") _,
+            out("f11") _,
+            out("f12") _,
+            out("f13") _,
+            out("f14") _,
+            out("f15") _,
+            out("f16") _,
+            out("f17") _,
+            out("f18") _,
+            out("f19") _,
+            out("f20") _,
+            out("f21") _,
+            out("f22") _,
+            out("f23") _,
+            out("f24") _,
+            out("f25") _,
+            out("f26") _,
+            out("f27") _,
+            out("f28") _,
+            out("f29") _,
+            out("f30") _,
+            out("f31") _,
+        ),
+
+        #[cfg(all(riscv, not(feature = "inline-asm"), not(target_feature = "f")))]
+        () => {
+            extern "C" {
+                fn __fstate_restore(regs: *const FRegs);
+            }
+
+            __fstate_restore(regs);
+        }
+
+        // The blob also overwrites the callee-saved `fs0`-`fs11`
+        #[cfg(all(riscv, not(feature = "inline-asm"), target_feature = "f"))]
+        () => {
+            extern "C" {
+                fn __fstate_restore(regs: *const FRegs);
+            }
+
+            core::arch::asm!(
+                "call {0}",
+                sym __fstate_restore,
+                in("a0") regs as *const FRegs,
+                out("fs0") _,
+                out("fs1") _,
+                out("fs2") _,
+                out("fs3") _,
+                out("fs4") _,
+                out("fs5") _,
+                out("fs6") _,
+                out("fs7") _,
+                out("fs8") _,
+                out("fs9") _,
+                out("fs10") _,
+                out("fs11") _,
+                clobber_abi("C"),
+            );
+        }
+
+        #[cfg(not(riscv))]
+        () => {
+            let _ = regs;
+            unimplemented!()
+        }
+    }
+}
+
+/// Saves the registers to `regs` if they are dirty and marks them clean
+///
+/// Returns true if the registers were saved.
+///
+/// # Safety
+///
+/// See [`fstate_save`].
+#[inline]
+pub unsafe fn save_if_dirty(regs: &mut FRegs) -> bool {
+    if is_dirty() {
+        fstate_save(regs);
+        set_state(FS::Clean);
+        true
+    } else {
+        false
+    }
+}
+
+/// Turns the floating-point unit on and loads the registers from `regs`
+///
+/// The state is left `Clean`, so the registers only need saving again once
+/// they are written.
+///
+/// # Safety
+///
+/// See [`fstate_restore`].
+#[inline]
+pub unsafe fn restore(regs: &FRegs) {
+    set_state(FS::Initial);
+    fstate_restore(regs);
+    set_state(FS::Clean);
+}
+
+/// Returns true if `insn` accesses the floating-point unit
+///
+/// Used on illegal instruction exceptions to detect the first use of the
+/// unit while it is `Off`. Covers floating-point loads and stores (but not
+/// the vector ones sharing their opcodes), computational instructions, and
+/// accesses to `fflags`, `frm` and `fcsr`.
+pub fn is_fp_instruction(insn: &TrappedInstruction) -> bool {
+    match insn.opcode() {
+        // LOAD-FP, STORE-FP: widths 16 to 128 bits
+        0x07 | 0x27 => (1..=4).contains(&insn.funct3()),
+        // MADD, MSUB, NMSUB, NMADD, OP-FP
+        0x43 | 0x47 | 0x4b | 0x4f | 0x53 => true,
+        // SYSTEM: CSR instructions on fflags, frm or fcsr
+        0x73 => insn.funct3() & 0b11 != 0 && (0x001..=0x003).contains(&(insn.bits() >> 20)),
+        _ => false,
+    }
+}
@@ -0,0 +1,267 @@
+//! Trapped instruction fetch and decode
+//!
+//! Helpers for trap handlers that emulate misaligned accesses or MMIO: they
+//! obtain the instruction that trapped, expand compressed loads and stores to
+//! their 32-bit equivalents, and decode the fields needed to replay the
+//! access.
+
+use bit_field::BitField;
+use core::mem::size_of;
+use core::ptr;
+
+use register::mtinst::TrapInstruction;
+use trap::Exception;
+
+const OPCODE_LOAD: u32 = 0x03;
+const OPCODE_LOAD_FP: u32 = 0x07;
+const OPCODE_STORE: u32 = 0x23;
+const OPCODE_STORE_FP: u32 = 0x27;
+
+/// An instruction that caused a trap
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct TrappedInstruction {
+    bits: u32,
+    size: usize,
+}
+
+/// Direction of a memory access
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AccessKind {
+    Load,
+    Store,
+}
+
+/// A decoded load or store
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct MemoryAccess {
+    /// Load or store
+    pub kind: AccessKind,
+    /// Access width in bytes
+    pub width: usize,
+    /// Loaded value is sign-extended
+    pub signed: bool,
+    /// Accesses a floating-point register
+    pub float: bool,
+    /// Destination register of a load, or source register of a store
+    pub reg: usize,
+}
+
+impl TrappedInstruction {
+    /// Decodes raw instruction bits
+    ///
+    /// A 16-bit instruction (low bits other than `0b11`) is expanded to its
+    /// 32-bit equivalent if it is a load or store; other compressed
+    /// instructions are kept as is and their fields are not meaningful.
+    #[inline]
+    pub fn from_bits(bits: u32) -> Self {
+        if bits & 0b11 == 0b11 {
+            TrappedInstruction { bits, size: 4 }
+        } else {
+            let half = bits & 0xffff;
+            TrappedInstruction {
+                bits: expand_compressed(half).unwrap_or(half),
+                size: 2,
+            }
+        }
+    }
+
+    /// Reads the instruction at `pc`
+    ///
+    /// The instruction is read in 16-bit parcels, so `pc` only needs to be
+    /// two-byte aligned.
+    ///
+    /// # Safety
+    ///
+    /// `pc` must be readable in the current context. For instructions of a
+    /// less privileged mode that means setting `sstatus.SUM` for U-mode pages,
+    /// or `mstatus.MPRV` (and `MXR` for execute-only pages) in M-mode.
+    #[inline]
+    pub unsafe fn fetch(pc: usize) -> Self {
+        let low = ptr::read_volatile(pc as *const u16) as u32;
+        if low & 0b11 == 0b11 {
+            let high = ptr::read_volatile((pc + 2) as *const u16) as u32;
+            Self::from_bits(low | (high << 16))
+        } else {
+            Self::from_bits(low)
+        }
+    }
+
+    /// Obtains the instruction that raised `exception` at `pc`
+    ///
+    /// Prefers the transformed instruction in `mtinst`/`htinst` (`tinst`),
+    /// then the instruction bits that some cores write to `mtval`/`stval`
+    /// (`tval`) on illegal instruction exceptions, and fetches from `pc`
+    /// otherwise.
+    ///
+    /// # Safety
+    ///
+    /// See [`fetch`](TrappedInstruction::fetch), which is used as last resort.
+    pub unsafe fn for_trap(
+        exception: Exception,
+        pc: usize,
+        tval: usize,
+        tinst: TrapInstruction,
+    ) -> Self {
+        match tinst {
+            TrapInstruction::Transformed { insn, compressed } => TrappedInstruction {
+                bits: insn,
+                size: if compressed { 2 } else { 4 },
+            },
+            _ if exception == Exception::IllegalInstruction && tval != 0 => {
+                Self::from_bits(tval as u32)
+            }
+            _ => Self::fetch(pc),
+        }
+    }
+
+    /// Returns the (expanded) instruction bits
+    #[inline]
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    /// Returns the length of the original instruction in bytes (2 or 4)
+    ///
+    /// Add this to the exception program counter to skip the instruction.
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns true if the original instruction was compressed
+    #[inline]
+    pub fn is_compressed(&self) -> bool {
+        self.size == 2
+    }
+
+    /// Returns the major opcode (bits 0-6)
+    #[inline]
+    pub fn opcode(&self) -> u32 {
+        self.bits.get_bits(0..7)
+    }
+
+    /// Returns the `rd` field
+    #[inline]
+    pub fn rd(&self) -> usize {
+        self.bits.get_bits(7..12) as usize
+    }
+
+    /// Returns the `funct3` field
+    #[inline]
+    pub fn funct3(&self) -> u32 {
+        self.bits.get_bits(12..15)
+    }
+
+    /// Returns the `rs1` field
+    #[inline]
+    pub fn rs1(&self) -> usize {
+        self.bits.get_bits(15..20) as usize
+    }
+
+    /// Returns the `rs2` field
+    #[inline]
+    pub fn rs2(&self) -> usize {
+        self.bits.get_bits(20..25) as usize
+    }
+
+    /// Returns the `funct7` field
+    #[inline]
+    pub fn funct7(&self) -> u32 {
+        self.bits.get_bits(25..32)
+    }
+
+    /// Decodes the instruction as an integer or floating-point load or store
+    ///
+    /// Returns `None` for other instructions, including atomics.
+    pub fn access(&self) -> Option<MemoryAccess> {
+        let (kind, float) = match self.opcode() {
+            OPCODE_LOAD => (AccessKind::Load, false),
+            OPCODE_LOAD_FP => (AccessKind::Load, true),
+            OPCODE_STORE => (AccessKind::Store, false),
+            OPCODE_STORE_FP => (AccessKind::Store, true),
+            _ => return None,
+        };
+        let (width, signed) = match (kind, float, self.funct3()) {
+            (_, false, 0) => (1, true),
+            (_, _, 1) => (2, !float),
+            (_, _, 2) => (4, !float),
+            (_, _, 3) => (8, !float),
+            (AccessKind::Load, false, 4) => (1, false),
+            (AccessKind::Load, false, 5) => (2, false),
+            (AccessKind::Load, false, 6) => (4, false),
+            _ => return None,
+        };
+        let reg = match kind {
+            AccessKind::Load => self.rd(),
+            AccessKind::Store => self.rs2(),
+        };
+        Some(MemoryAccess {
+            kind,
+            width,
+            signed: signed && kind == AccessKind::Load,
+            float,
+            reg,
+        })
+    }
+}
+
+/// Builds an I-type load
+fn load(opcode: u32, funct3: u32, rd: u32, rs1: u32, imm: u32) -> u32 {
+    (imm << 20) | (rs1 << 15) | (funct3 << 12) | (rd << 7) | opcode
+}
+
+/// Builds an S-type store
+fn store(opcode: u32, funct3: u32, rs2: u32, rs1: u32, imm: u32) -> u32 {
+    ((imm >> 5) << 25) | (rs2 << 20) | (rs1 << 15) | (funct3 << 12) | ((imm & 0x1f) << 7) | opcode
+}
+
+/// Expands a compressed load or store to its 32-bit equivalent
+fn expand_compressed(c: u32) -> Option<u32> {
+    const SP: u32 = 2;
+    let rv64 = size_of::<usize>() == 8;
+    // rd'/rs2' and rs1' of the CL/CS formats
+    let rdp = c.get_bits(2..5) + 8;
+    let rs1p = c.get_bits(7..10) + 8;
+    // Offsets of the word and double-word CL/CS and CSS/CI forms
+    let w_off = (c.get_bits(10..13) << 3) | (c.get_bit(6) as u32 * 4) | (c.get_bit(5) as u32 * 64);
+    let d_off = (c.get_bits(10..13) << 3) | (c.get_bits(5..7) << 6);
+    let rd = c.get_bits(7..12);
+    let rs2 = c.get_bits(2..7);
+    let lwsp_off = (c.get_bit(12) as u32 * 32) | (c.get_bits(4..7) << 2) | (c.get_bits(2..4) << 6);
+    let ldsp_off = (c.get_bit(12) as u32 * 32) | (c.get_bits(5..7) << 3) | (c.get_bits(2..5) << 6);
+    let swsp_off = (c.get_bits(9..13) << 2) | (c.get_bits(7..9) << 6);
+    let sdsp_off = (c.get_bits(10..13) << 3) | (c.get_bits(7..10) << 6);
+
+    let insn = match (c.get_bits(0..2), c.get_bits(13..16)) {
+        // C.FLD
+        (0b00, 0b001) => load(OPCODE_LOAD_FP, 3, rdp, rs1p, d_off),
+        // C.LW
+        (0b00, 0b010) => load(OPCODE_LOAD, 2, rdp, rs1p, w_off),
+        // C.LD / C.FLW
+        (0b00, 0b011) if rv64 => load(OPCODE_LOAD, 3, rdp, rs1p, d_off),
+        (0b00, 0b011) => load(OPCODE_LOAD_FP, 2, rdp, rs1p, w_off),
+        // C.FSD
+        (0b00, 0b101) => store(OPCODE_STORE_FP, 3, rdp, rs1p, d_off),
+        // C.SW
+        (0b00, 0b110) => store(OPCODE_STORE, 2, rdp, rs1p, w_off),
+        // C.SD / C.FSW
+        (0b00, 0b111) if rv64 => store(OPCODE_STORE, 3, rdp, rs1p, d_off),
+        (0b00, 0b111) => store(OPCODE_STORE_FP, 2, rdp, rs1p, w_off),
+        // C.FLDSP
+        (0b10, 0b001) => load(OPCODE_LOAD_FP, 3, rd, SP, ldsp_off),
+        // C.LWSP
+        (0b10, 0b010) if rd != 0 => load(OPCODE_LOAD, 2, rd, SP, lwsp_off),
+        // C.LDSP / C.FLWSP
+        (0b10, 0b011) if rv64 && rd != 0 => load(OPCODE_LOAD, 3, rd, SP, ldsp_off),
+        (0b10, 0b011) if !rv64 => load(OPCODE_LOAD_FP, 2, rd, SP, lwsp_off),
+        // C.FSDSP
+        (0b10, 0b101) => store(OPCODE_STORE_FP, 3, rs2, SP, sdsp_off),
+        // C.SWSP
+        (0b10, 0b110) => store(OPCODE_STORE, 2, rs2, SP, swsp_off),
+        // C.SDSP / C.FSWSP
+        (0b10, 0b111) if rv64 => store(OPCODE_STORE, 3, rs2, SP, sdsp_off),
+        (0b10, 0b111) => store(OPCODE_STORE_FP, 2, rs2, SP, swsp_off),
+        _ => return None,
+    };
+    Some(insn)
+}
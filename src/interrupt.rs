@@ -1,58 +1,406 @@
 //! Interrupts
+//!
+//! `disable`, `enable` and `free` act on the machine-level interrupt enable
+//! (`mstatus.MIE`). Code running in S-mode, where accessing `mstatus` traps,
+//! should enable the `s-mode` feature, which switches them to the
+//! supervisor-level interrupt enable (`sstatus.SIE`). The `machine` and
+//! `supervisor` modules are always available for explicit selection.
+//!
+//! All of these only affect the calling hart. A critical section therefore
+//! does not exclude other harts, and `Mutex` only protects data that is not
+//! shared with them; see the `sync` module for multi-hart alternatives.
 
 // NOTE: Adapted from cortex-m/src/interrupt.rs
 pub use bare_metal::{CriticalSection, Mutex, Nr};
-use register::mstatus;
+use core::marker::PhantomData;
+use core::mem;
+use core::sync::atomic::{AtomicUsize, Ordering};
+pub use trap::Interrupt;
+use trap::{Trap, TrapFrame};
 
-/// Disables all interrupts
-#[inline]
-pub unsafe fn disable() {
-    match () {
-        #[cfg(riscv)]
-        () => mstatus::clear_mie(),
-        #[cfg(not(riscv))]
-        () => unimplemented!(),
+#[cfg(not(feature = "s-mode"))]
+pub use self::machine::{disable, disable_guard, enable, free, free_simple};
+#[cfg(feature = "s-mode")]
+pub use self::supervisor::{disable, disable_guard, enable, free, free_simple};
+
+/// Number of harts, by `sync::hart_id`, that can hold `DisableGuard`s
+const GUARD_HARTS: usize = 64;
+
+/// Per-hart nesting depth of live `DisableGuard`s, shifted left by one, with
+/// bit 0 holding whether interrupts were enabled before the outermost guard
+///
+/// Only accessed by the hart itself with interrupts disabled, so plain loads
+/// and stores suffice.
+static GUARD_DEPTH: [AtomicUsize; GUARD_HARTS] = [GUARD_DEPTH_INIT; GUARD_HARTS];
+
+/// Initial `GUARD_DEPTH` entry, copied into every slot by the array repeat
+#[allow(clippy::declare_interior_mutable_const)]
+const GUARD_DEPTH_INIT: AtomicUsize = AtomicUsize::new(0);
+
+/// Keeps interrupts disabled until dropped
+///
+/// Returned by `disable_guard`. Guards of a hart are counted, and only
+/// dropping the last live one re-enables interrupts, if they were enabled
+/// when the first one was created. Guards may therefore be dropped in any
+/// order, and forgetting one keeps interrupts disabled. The guard cannot be
+/// sent to another hart.
+#[must_use = "interrupts are re-enabled as soon as the guard is dropped"]
+pub struct DisableGuard {
+    was_enabled: bool,
+    enable: unsafe fn(),
+    cs: CriticalSection,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl DisableGuard {
+    /// Registers a guard on the current hart, which must have interrupts
+    /// disabled
+    #[inline]
+    fn new(was_enabled: bool, enable: unsafe fn()) -> Self {
+        let depth = &GUARD_DEPTH[::sync::hart_id()];
+        let state = depth.load(Ordering::Relaxed);
+        let state = if state == 0 {
+            2 | was_enabled as usize
+        } else {
+            state + 2
+        };
+        depth.store(state, Ordering::Relaxed);
+        DisableGuard {
+            was_enabled,
+            enable,
+            cs: unsafe { CriticalSection::new() },
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Returns the critical section token valid while the guard is alive
+    #[inline]
+    pub fn critical_section(&self) -> &CriticalSection {
+        &self.cs
+    }
+
+    /// Returns whether interrupts were enabled when the guard was created
+    #[inline]
+    pub fn was_enabled(&self) -> bool {
+        self.was_enabled
+    }
+}
+
+impl Drop for DisableGuard {
+    #[inline]
+    fn drop(&mut self) {
+        let depth = &GUARD_DEPTH[::sync::hart_id()];
+        let state = depth.load(Ordering::Relaxed) - 2;
+        if state < 2 {
+            depth.store(0, Ordering::Relaxed);
+            if state == 1 {
+                unsafe { (self.enable)() }
+            }
+        } else {
+            depth.store(state, Ordering::Relaxed);
+        }
     }
 }
 
-/// Enables all the interrupts
+/// Machine-level interrupt control (`mstatus.MIE`)
+pub mod machine {
+    use super::{CriticalSection, DisableGuard};
+    use register::mstatus;
+
+    /// Disables all interrupts
+    #[inline]
+    pub unsafe fn disable() {
+        match () {
+            #[cfg(any(riscv, feature = "emulation"))]
+            () => mstatus::clear_mie(),
+            #[cfg(not(any(riscv, feature = "emulation")))]
+            () => unimplemented!(),
+        }
+    }
+
+    /// Enables all the interrupts
+    ///
+    /// # Safety
+    ///
+    /// - Do not call this function inside an `interrupt::free` critical section
+    #[inline]
+    pub unsafe fn enable() {
+        match () {
+            #[cfg(any(riscv, feature = "emulation"))]
+            () => mstatus::set_mie(),
+            #[cfg(not(any(riscv, feature = "emulation")))]
+            () => unimplemented!(),
+        }
+    }
+
+    /// Execute closure `f` in an interrupt-free context.
+    ///
+    /// This as also known as a "critical section".
+    pub fn free<F, R>(f: F) -> R
+    where
+        F: FnOnce(&CriticalSection) -> R,
+    {
+        let mstatus = mstatus::read();
+
+        // disable interrupts
+        unsafe {
+            disable();
+        }
+
+        let r = f(unsafe { &CriticalSection::new() });
+
+        // If the interrupts were active before our `disable` call, then re-enable
+        // them. Otherwise, keep them disabled
+        if mstatus.mie() {
+            unsafe {
+                enable();
+            }
+        }
+
+        r
+    }
+
+    /// Execute closure `f` in an interrupt-free context, without passing it
+    /// the `CriticalSection` token.
+    #[inline]
+    pub fn free_simple<F, R>(f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        free(|_| f())
+    }
+
+    /// Disables all interrupts until the returned guard is dropped
+    ///
+    /// # Panics
+    ///
+    /// Panics if the id of the current hart is 64 or higher.
+    #[inline]
+    pub fn disable_guard() -> DisableGuard {
+        let was_enabled = unsafe { mstatus::read_and_clear_bits(1 << 3).mie() };
+        DisableGuard::new(was_enabled, enable)
+    }
+}
+
+/// Supervisor-level interrupt control (`sstatus.SIE`)
+pub mod supervisor {
+    use super::{CriticalSection, DisableGuard};
+    use register::sstatus;
+
+    /// Disables all interrupts
+    #[inline]
+    pub unsafe fn disable() {
+        match () {
+            #[cfg(any(riscv, feature = "emulation"))]
+            () => sstatus::clear_sie(),
+            #[cfg(not(any(riscv, feature = "emulation")))]
+            () => unimplemented!(),
+        }
+    }
+
+    /// Enables all the interrupts
+    ///
+    /// # Safety
+    ///
+    /// - Do not call this function inside an `interrupt::free` critical section
+    #[inline]
+    pub unsafe fn enable() {
+        match () {
+            #[cfg(any(riscv, feature = "emulation"))]
+            () => sstatus::set_sie(),
+            #[cfg(not(any(riscv, feature = "emulation")))]
+            () => unimplemented!(),
+        }
+    }
+
+    /// Execute closure `f` in an interrupt-free context.
+    ///
+    /// This as also known as a "critical section".
+    pub fn free<F, R>(f: F) -> R
+    where
+        F: FnOnce(&CriticalSection) -> R,
+    {
+        let sstatus = sstatus::read();
+
+        // disable interrupts
+        unsafe {
+            disable();
+        }
+
+        let r = f(unsafe { &CriticalSection::new() });
+
+        // If the interrupts were active before our `disable` call, then re-enable
+        // them. Otherwise, keep them disabled
+        if sstatus.sie() {
+            unsafe {
+                enable();
+            }
+        }
+
+        r
+    }
+
+    /// Execute closure `f` in an interrupt-free context, without passing it
+    /// the `CriticalSection` token.
+    #[inline]
+    pub fn free_simple<F, R>(f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        free(|_| f())
+    }
+
+    /// Disables all interrupts until the returned guard is dropped
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sync::hart_id` is 64 or higher. With the `s-mode` feature
+    /// that is the hart index in `tp`, which must have been set first.
+    #[inline]
+    pub fn disable_guard() -> DisableGuard {
+        let was_enabled = unsafe { sstatus::read_and_clear_bits(1 << 1).sie() };
+        DisableGuard::new(was_enabled, enable)
+    }
+}
+
+/// Interrupt source number
+///
+/// A wider counterpart of `Nr`, which is limited to `u8` and so cannot
+/// number all PLIC sources.
 ///
 /// # Safety
 ///
-/// - Do not call this function inside an `interrupt::free` critical section
-#[inline]
-pub unsafe fn enable() {
-    match () {
-        #[cfg(riscv)]
-        () => mstatus::set_mie(),
-        #[cfg(not(riscv))]
-        () => unimplemented!(),
+/// `number` must return the same valid number for equal values.
+pub unsafe trait InterruptNumber: Copy {
+    /// Returns the number associated with the interrupt source
+    fn number(self) -> u16;
+}
+
+// `Interrupt::Unknown` has no cause code; it maps to the highest number
+// rather than aliasing a real interrupt.
+unsafe impl Nr for Interrupt {
+    #[inline]
+    fn nr(&self) -> u8 {
+        self.to_code().map_or(u8::MAX, |code| code as u8)
     }
 }
 
-/// Execute closure `f` in an interrupt-free context.
+/// Interrupt source of a platform-level interrupt controller
 ///
-/// This as also known as a "critical section".
-pub fn free<F, R>(f: F) -> R
-where
-    F: FnOnce(&CriticalSection) -> R,
-{
-    let mstatus = mstatus::read();
+/// Source 0 is reserved by the PLIC specification and means "no interrupt".
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct PlicIrq(pub u16);
 
-    // disable interrupts
-    unsafe {
-        disable();
+unsafe impl InterruptNumber for PlicIrq {
+    #[inline]
+    fn number(self) -> u16 {
+        self.0
     }
+}
 
-    let r = f(unsafe { &CriticalSection::new() });
+/// Interrupt handler called by `dispatch`
+pub type Handler = fn(&mut TrapFrame);
 
-    // If the interrupts were active before our `disable` call, then re-enable
-    // them. Otherwise, keep them disabled
-    if mstatus.mie() {
-        unsafe {
-            enable();
-        }
+/// Number of interrupt causes `register` accepts handlers for
+const HANDLERS: usize = 16;
+
+/// Registered handlers as function pointers, zero if none
+static HANDLER_TABLE: [AtomicUsize; HANDLERS] = [
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+];
+
+/// Registers `handler` to be called by `dispatch` for `interrupt`
+///
+/// Replaces any handler registered before. `Interrupt::Unknown` is ignored.
+#[inline]
+pub fn register(interrupt: Interrupt, handler: Handler) {
+    if let Some(code) = interrupt.to_code() {
+        HANDLER_TABLE[code].store(handler as usize, Ordering::Release);
+    }
+}
+
+/// Removes the handler registered for `interrupt`
+#[inline]
+pub fn unregister(interrupt: Interrupt) {
+    if let Some(code) = interrupt.to_code() {
+        HANDLER_TABLE[code].store(0, Ordering::Release);
+    }
+}
+
+/// Returns the handler registered for `interrupt`
+#[inline]
+pub fn handler(interrupt: Interrupt) -> Option<Handler> {
+    let code = interrupt.to_code()?;
+    match HANDLER_TABLE[code].load(Ordering::Acquire) {
+        0 => None,
+        f => Some(unsafe { mem::transmute::<usize, Handler>(f) }),
     }
+}
 
-    r
+/// Calls the handler registered for the interrupt that caused `frame`
+///
+/// The cause is decoded from `frame.cause`, i.e. the `mcause` or `scause`
+/// value saved on trap entry. Returns `false` without calling anything if
+/// the trap is an exception or no handler is registered for the interrupt.
+pub fn dispatch(frame: &mut TrapFrame) -> bool {
+    match frame.trap() {
+        Trap::Interrupt(interrupt) => match handler(interrupt) {
+            Some(f) => {
+                f(frame);
+                true
+            }
+            None => false,
+        },
+        Trap::Exception(_) => false,
+    }
+}
+
+/// `critical-section` implementation for single-hart systems
+///
+/// Acquiring atomically clears the interrupt enable of the privilege level
+/// selected by the `s-mode` feature and restores it on release. It does not
+/// exclude other harts.
+#[cfg(feature = "critical-section-single-hart")]
+mod single_hart {
+    #[cfg(not(feature = "s-mode"))]
+    use register::mstatus;
+    #[cfg(feature = "s-mode")]
+    use register::sstatus;
+
+    struct SingleHartCriticalSection;
+    ::critical_section::set_impl!(SingleHartCriticalSection);
+
+    unsafe impl ::critical_section::Impl for SingleHartCriticalSection {
+        #[cfg(not(feature = "s-mode"))]
+        unsafe fn acquire() -> ::critical_section::RawRestoreState {
+            mstatus::read_and_clear_bits(1 << 3).mie()
+        }
+
+        #[cfg(feature = "s-mode")]
+        unsafe fn acquire() -> ::critical_section::RawRestoreState {
+            sstatus::read_and_clear_bits(1 << 1).sie()
+        }
+
+        unsafe fn release(was_active: ::critical_section::RawRestoreState) {
+            // Only re-enable interrupts if they were enabled before the critical section
+            if was_active {
+                super::enable();
+            }
+        }
+    }
 }
@@ -12,18 +12,55 @@
 //! - Access to core registers like `mstatus` or `mcause`.
 //! - Interrupt manipulation mechanisms.
 //! - Wrappers around assembly instructions like `WFI`.
+//!
+//! The `emulation` feature backs the CSR accessors with an emulated register
+//! file on non-RISC-V targets, so that code using them can be unit-tested on
+//! the host (see `register::emulation`).
 
 
 #![no_std]
 #![cfg_attr(feature = "inline-asm", feature(asm_const))]
 #![feature(asm)]
 extern crate bare_metal;
+#[cfg(all(not(riscv), feature = "emulation"))]
+extern crate std;
 #[macro_use]
 extern crate bitflags;
 extern crate bit_field;
+#[cfg(any(feature = "critical-section-single-hart", feature = "soft-atomics"))]
+extern crate critical_section;
+#[cfg(feature = "defmt")]
+extern crate defmt;
+#[cfg(feature = "embedded-hal")]
+extern crate embedded_hal;
+#[cfg(feature = "embedded-hal-02")]
+extern crate embedded_hal_02;
+#[cfg(feature = "embedded-hal-02")]
+extern crate nb;
+#[cfg(feature = "rand_core")]
+extern crate rand_core;
+#[cfg(feature = "embedded-hal-02")]
+extern crate void;
 
 pub mod addr;
 pub mod asm;
+#[cfg(feature = "soft-atomics")]
+pub mod atomic;
+pub mod backtrace;
+pub mod context;
+pub mod delay;
+pub mod entropy;
+pub mod fpu;
+pub mod insn;
 pub mod interrupt;
 pub mod paging;
+pub mod perf;
+pub mod peripheral;
 pub mod register;
+pub mod sbi;
+pub mod semihosting;
+pub mod sync;
+pub mod time;
+pub mod timer;
+pub mod trap;
+pub mod vector;
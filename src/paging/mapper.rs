@@ -1,6 +1,7 @@
 use super::frame_alloc::*;
 use super::page_table::*;
 use addr::*;
+use core::fmt;
 
 pub trait Mapper {
     type P: PhysicalAddress;
@@ -87,7 +88,7 @@ impl MapperFlushable for MapperFlush {
     }
     fn flush(self) {
         unsafe {
-            crate::asm::sfence_vma(0, self.0);
+            crate::asm::sfence_vma_addr(self.0);
         }
     }
     fn ignore(self) {}
@@ -95,6 +96,7 @@ impl MapperFlushable for MapperFlush {
 
 /// This error is returned from `map_to` and similar methods.
 #[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum MapToError {
     /// An additional frame was needed for the mapping process, but the frame allocator
     /// returned `None`.
@@ -106,8 +108,22 @@ pub enum MapToError {
     PageAlreadyMapped,
 }
 
+impl fmt::Display for MapToError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            MapToError::FrameAllocationFailed => "failed to allocate a page table frame",
+            MapToError::ParentEntryHugePage => "page is part of an already mapped huge page",
+            MapToError::PageAlreadyMapped => "page is already mapped",
+        })
+    }
+}
+
+#[cfg(feature = "core-error")]
+impl core::error::Error for MapToError {}
+
 /// An error indicating that an `unmap` call failed.
 #[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum UnmapError<P: PhysicalAddress> {
     /// An upper level page table entry has the `HUGE_PAGE` flag set, which means that the
     /// given page is part of a huge page and can't be freed individually.
@@ -118,13 +134,44 @@ pub enum UnmapError<P: PhysicalAddress> {
     InvalidFrameAddress(P),
 }
 
+impl<P: PhysicalAddress> fmt::Display for UnmapError<P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UnmapError::ParentEntryHugePage => f.write_str("page is part of a huge page"),
+            UnmapError::PageNotMapped => f.write_str("page is not mapped"),
+            UnmapError::InvalidFrameAddress(addr) => {
+                write!(
+                    f,
+                    "page is mapped to invalid frame address {:#x}",
+                    addr.as_u64()
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "core-error")]
+impl<P: PhysicalAddress> core::error::Error for UnmapError<P> {}
+
 /// An error indicating that an `update_flags` call failed.
 #[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum FlagUpdateError {
     /// The given page is not mapped to a physical frame.
     PageNotMapped,
 }
 
+impl fmt::Display for FlagUpdateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FlagUpdateError::PageNotMapped => f.write_str("page is not mapped"),
+        }
+    }
+}
+
+#[cfg(feature = "core-error")]
+impl core::error::Error for FlagUpdateError {}
+
 pub trait MapperExt {
     type Page;
     type Frame;
@@ -1,5 +1,5 @@
 use crate::addr::*;
-use crate::asm::{hfence_gvma, hfence_vvma};
+use crate::asm::{hfence_gvma_gpa, hfence_vvma_gva};
 use crate::paging::mapper::MapperFlushable;
 use crate::paging::multi_level::Rv32PageTableWith;
 use crate::paging::multi_level::{Rv39PageTableWith, Rv48PageTableWith};
@@ -13,7 +13,7 @@ impl MapperFlushable for MapperFlushGPA {
     }
     fn flush(self) {
         unsafe {
-            hfence_gvma(self.0, 0);
+            hfence_gvma_gpa(self.0);
         }
     }
     fn ignore(self) {}
@@ -28,7 +28,7 @@ impl MapperFlushable for MapperFlushGPT {
     }
     fn flush(self) {
         unsafe {
-            hfence_vvma(self.0, 0);
+            hfence_vvma_gva(self.0);
         }
     }
     fn ignore(self) {}
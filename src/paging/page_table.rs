@@ -193,6 +193,37 @@ impl<'a, P: PhysicalAddress> Debug for PageTableEntryX64Printer<'a, P> {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for PageTableEntryX32 {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "PageTableEntryX32 {{ frame: {}, flags: {} }}",
+            self.frame::<PhysAddrSv32>(),
+            self.flags()
+        )
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<'a, P: PhysicalAddress + defmt::Format> defmt::Format for PageTableEntryX64Printer<'a, P> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "PageTableEntryX64 {{ frame: {}, flags: {} }}",
+            self.0.frame::<P>(),
+            self.0.flags()
+        )
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for PageTableEntryX64 {
+    fn format(&self, f: defmt::Formatter) {
+        self.debug_sv48().format(f)
+    }
+}
+
 impl PageTableEntryX64 {
     pub fn debug_sv39<'a>(&'a self) -> PageTableEntryX64Printer<'a, PhysAddrSv39> {
         PageTableEntryX64Printer(self, PhantomData)
@@ -235,6 +266,7 @@ pub type PageTable = PageTableX64;
 pub type PageTable = PageTableX32;
 bitflags! {
     /// Possible flags for a page table entry.
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub struct PageTableFlags: usize {
         const VALID =       1 << 0;
         const READABLE =    1 << 1;
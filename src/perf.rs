@@ -0,0 +1,99 @@
+//! Cycle and instruction counting
+//!
+//! A [`Stopwatch`] samples the cycle and retired-instruction counters at its
+//! start and reports the difference. Counters are always read as 64-bit
+//! values (composing the high and low halves on RV32) and differences are
+//! computed with wrapping arithmetic, so a counter rollover between the two
+//! samples is handled transparently.
+
+use register::{cycle, instret, mcycle, minstret};
+
+/// Counters a [`Stopwatch`] reads
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Counters {
+    /// `mcycle` and `minstret`, readable in M-mode
+    Machine,
+    /// `cycle` and `instret`, readable in lower privilege modes when enabled
+    /// through `mcounteren`/`scounteren`
+    User,
+}
+
+impl Counters {
+    /// Reads the current counter values
+    #[inline]
+    pub fn sample(self) -> Sample {
+        match self {
+            Counters::Machine => Sample {
+                cycles: mcycle::read64(),
+                instructions: minstret::read64(),
+            },
+            Counters::User => Sample {
+                cycles: cycle::read64(),
+                instructions: instret::read64(),
+            },
+        }
+    }
+}
+
+/// Cycle and retired-instruction counts
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Sample {
+    pub cycles: u64,
+    pub instructions: u64,
+}
+
+impl Sample {
+    /// Returns the counts elapsed since `earlier`
+    #[inline]
+    pub fn since(&self, earlier: &Sample) -> Sample {
+        Sample {
+            cycles: self.cycles.wrapping_sub(earlier.cycles),
+            instructions: self.instructions.wrapping_sub(earlier.instructions),
+        }
+    }
+}
+
+/// Measures the cycles and instructions spent in a code region
+#[derive(Copy, Clone, Debug)]
+pub struct Stopwatch {
+    counters: Counters,
+    start: Sample,
+}
+
+impl Stopwatch {
+    /// Starts a stopwatch reading the given counters
+    #[inline]
+    pub fn start(counters: Counters) -> Self {
+        Stopwatch {
+            counters,
+            start: counters.sample(),
+        }
+    }
+
+    /// Returns the counts elapsed since the stopwatch was (re)started
+    #[inline]
+    pub fn elapsed(&self) -> Sample {
+        self.counters.sample().since(&self.start)
+    }
+
+    /// Returns the counts elapsed since the stopwatch was (re)started and
+    /// restarts it
+    #[inline]
+    pub fn restart(&mut self) -> Sample {
+        let now = self.counters.sample();
+        let elapsed = now.since(&self.start);
+        self.start = now;
+        elapsed
+    }
+}
+
+/// Runs `f` and returns its result along with the counts it took
+#[inline]
+pub fn measure<F, R>(counters: Counters, f: F) -> (R, Sample)
+where
+    F: FnOnce() -> R,
+{
+    let stopwatch = Stopwatch::start(counters);
+    let result = f();
+    (result, stopwatch.elapsed())
+}
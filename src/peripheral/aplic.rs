@@ -0,0 +1,430 @@
+//! Advanced platform-level interrupt controller (APLIC)
+//!
+//! One interrupt domain of an APLIC as defined by the RISC-V Advanced
+//! Interrupt Architecture. A domain either delivers interrupts directly to
+//! harts through its interrupt delivery controls (IDCs), or forwards them as
+//! MSIs to the IMSICs of the harts, whose interrupt files are accessed
+//! through `mtopei`/`stopei` and `miselect`/`mireg`.
+//!
+//! Functions taking a source panic if it is 0 or not below [`SOURCES`].
+
+use bit_field::BitField;
+use core::ops::Range;
+use core::ptr;
+
+use interrupt::InterruptNumber;
+pub use interrupt::PlicIrq as InterruptSource;
+
+/// Number of interrupt source numbers; source 0 does not exist
+pub const SOURCES: usize = 1024;
+
+const DOMAINCFG: usize = 0x0000;
+const SOURCECFG: usize = 0x0004;
+const MMSIADDRCFG: usize = 0x1BC0;
+const MMSIADDRCFGH: usize = 0x1BC4;
+const SMSIADDRCFG: usize = 0x1BC8;
+const SMSIADDRCFGH: usize = 0x1BCC;
+const SETIP: usize = 0x1C00;
+const SETIPNUM: usize = 0x1CDC;
+const CLRIPNUM: usize = 0x1DDC;
+const SETIE: usize = 0x1E00;
+const SETIENUM: usize = 0x1EDC;
+const CLRIENUM: usize = 0x1FDC;
+const GENMSI: usize = 0x3000;
+const TARGET: usize = 0x3004;
+const IDC: usize = 0x4000;
+const IDC_STRIDE: usize = 32;
+
+const DOMAINCFG_IE: usize = 8;
+const DOMAINCFG_DM: usize = 2;
+const DOMAINCFG_BE: usize = 0;
+
+const SOURCECFG_D: usize = 10;
+const SOURCECFG_CHILD: Range<usize> = 0..10;
+const SOURCECFG_SM: Range<usize> = 0..3;
+
+const TARGET_HART: Range<usize> = 18..32;
+const TARGET_GUEST: Range<usize> = 12..18;
+const TARGET_EIID: Range<usize> = 0..11;
+const TARGET_IPRIO: Range<usize> = 0..8;
+
+const GENMSI_BUSY: usize = 12;
+
+const IDELIVERY: usize = 0x00;
+const IFORCE: usize = 0x04;
+const ITHRESHOLD: usize = 0x08;
+const TOPI: usize = 0x18;
+const CLAIMI: usize = 0x1C;
+
+/// How a domain delivers interrupts
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DeliveryMode {
+    /// Directly to harts through the interrupt delivery controls
+    Direct = 0,
+    /// As MSIs to the IMSICs of the harts
+    Msi = 1,
+}
+
+/// Source mode of an interrupt source that is not delegated
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SourceMode {
+    /// Source is inactive in this domain
+    Inactive = 0,
+    /// Source can only be pended by software
+    Detached = 1,
+    /// Asserted on a rising edge
+    RisingEdge = 4,
+    /// Asserted on a falling edge
+    FallingEdge = 5,
+    /// Asserted while high
+    LevelHigh = 6,
+    /// Asserted while low
+    LevelLow = 7,
+}
+
+/// Configuration of an interrupt source
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SourceConfig {
+    /// Source is handled in this domain
+    Mode(SourceMode),
+    /// Source is delegated to the child domain with the given index
+    Delegated(u16),
+}
+
+/// An interrupt claimed from an interrupt delivery control
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Claimed {
+    /// Interrupt source
+    pub source: InterruptSource,
+    /// Priority of the source
+    pub priority: u8,
+}
+
+/// An APLIC interrupt domain
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Aplic {
+    base: usize,
+}
+
+impl Aplic {
+    /// Creates a handle to the APLIC domain mapped at `base`
+    ///
+    /// # Safety
+    ///
+    /// `base` must be the address of an APLIC domain, and the handle must
+    /// only be used where the domain is accessible.
+    #[inline]
+    pub const unsafe fn new(base: usize) -> Self {
+        Aplic { base }
+    }
+
+    /// Returns the base address
+    #[inline]
+    pub fn base(&self) -> usize {
+        self.base
+    }
+
+    #[inline]
+    fn read(&self, offset: usize) -> u32 {
+        unsafe { ptr::read_volatile((self.base + offset) as *const u32) }
+    }
+
+    #[inline]
+    unsafe fn write(&self, offset: usize, value: u32) {
+        ptr::write_volatile((self.base + offset) as *mut u32, value);
+    }
+
+    #[inline]
+    fn source_number<I: InterruptNumber>(source: I) -> usize {
+        let n = source.number() as usize;
+        assert!(n != 0 && n < SOURCES, "APLIC source {} out of range", n);
+        n
+    }
+
+    #[inline]
+    fn source_offset<I: InterruptNumber>(base: usize, source: I) -> usize {
+        base + 4 * (Self::source_number(source) - 1)
+    }
+
+    /// Returns true if the domain delivers interrupts
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.read(DOMAINCFG).get_bit(DOMAINCFG_IE)
+    }
+
+    /// Returns the delivery mode of the domain
+    #[inline]
+    pub fn delivery_mode(&self) -> DeliveryMode {
+        if self.read(DOMAINCFG).get_bit(DOMAINCFG_DM) {
+            DeliveryMode::Msi
+        } else {
+            DeliveryMode::Direct
+        }
+    }
+
+    /// Configures the domain for little-endian registers
+    ///
+    /// # Safety
+    ///
+    /// Enabling the domain can break mask-based critical sections; not every
+    /// implementation supports both delivery modes.
+    #[inline]
+    pub unsafe fn configure(&self, mode: DeliveryMode, enabled: bool) {
+        let mut cfg = 0u32;
+        cfg.set_bit(DOMAINCFG_IE, enabled);
+        cfg.set_bit(DOMAINCFG_DM, mode == DeliveryMode::Msi);
+        cfg.set_bit(DOMAINCFG_BE, false);
+        self.write(DOMAINCFG, cfg);
+    }
+
+    /// Returns the configuration of `source`
+    ///
+    /// Unimplemented sources read as `Mode(Inactive)`.
+    #[inline]
+    pub fn source_config<I: InterruptNumber>(&self, source: I) -> SourceConfig {
+        let cfg = self.read(Self::source_offset(SOURCECFG, source));
+        if cfg.get_bit(SOURCECFG_D) {
+            SourceConfig::Delegated(cfg.get_bits(SOURCECFG_CHILD) as u16)
+        } else {
+            SourceConfig::Mode(match cfg.get_bits(SOURCECFG_SM) {
+                1 => SourceMode::Detached,
+                4 => SourceMode::RisingEdge,
+                5 => SourceMode::FallingEdge,
+                6 => SourceMode::LevelHigh,
+                7 => SourceMode::LevelLow,
+                _ => SourceMode::Inactive,
+            })
+        }
+    }
+
+    /// Configures `source` or delegates it to a child domain
+    ///
+    /// # Safety
+    ///
+    /// Delegation hands the source over to the software of the child domain.
+    #[inline]
+    pub unsafe fn set_source_config<I: InterruptNumber>(&self, source: I, config: SourceConfig) {
+        let mut cfg = 0u32;
+        match config {
+            SourceConfig::Mode(mode) => {
+                cfg.set_bits(SOURCECFG_SM, mode as u32);
+            }
+            SourceConfig::Delegated(child) => {
+                cfg.set_bit(SOURCECFG_D, true);
+                cfg.set_bits(SOURCECFG_CHILD, child as u32);
+            }
+        }
+        self.write(Self::source_offset(SOURCECFG, source), cfg);
+    }
+
+    /// Writes the raw machine (`mmsiaddrcfg`/`mmsiaddrcfgh`) or supervisor
+    /// (`smsiaddrcfg`/`smsiaddrcfgh`) MSI address configuration
+    ///
+    /// Only implemented in the root domain of each privilege level. The low
+    /// word holds the low bits of the IMSIC page number, the high word the
+    /// high bits together with the hart and guest index layout and the lock
+    /// bit.
+    ///
+    /// # Safety
+    ///
+    /// Redirects MSIs to the given addresses.
+    #[inline]
+    pub unsafe fn set_msi_address_config(&self, supervisor: bool, low: u32, high: u32) {
+        let (lo, hi) = if supervisor {
+            (SMSIADDRCFG, SMSIADDRCFGH)
+        } else {
+            (MMSIADDRCFG, MMSIADDRCFGH)
+        };
+        self.write(lo, low);
+        self.write(hi, high);
+    }
+
+    /// Returns true if `source` is pending
+    #[inline]
+    pub fn is_pending<I: InterruptNumber>(&self, source: I) -> bool {
+        let n = Self::source_number(source);
+        self.read(SETIP + 4 * (n / 32)).get_bit(n % 32)
+    }
+
+    /// Sets the pending bit of `source`
+    #[inline]
+    pub fn pend<I: InterruptNumber>(&self, source: I) {
+        unsafe { self.write(SETIPNUM, Self::source_number(source) as u32) };
+    }
+
+    /// Clears the pending bit of `source`
+    #[inline]
+    pub fn unpend<I: InterruptNumber>(&self, source: I) {
+        unsafe { self.write(CLRIPNUM, Self::source_number(source) as u32) };
+    }
+
+    /// Returns true if `source` is enabled
+    #[inline]
+    pub fn is_source_enabled<I: InterruptNumber>(&self, source: I) -> bool {
+        let n = Self::source_number(source);
+        self.read(SETIE + 4 * (n / 32)).get_bit(n % 32)
+    }
+
+    /// Enables `source`
+    ///
+    /// # Safety
+    ///
+    /// Enabling an interrupt can break mask-based critical sections.
+    #[inline]
+    pub unsafe fn enable<I: InterruptNumber>(&self, source: I) {
+        self.write(SETIENUM, Self::source_number(source) as u32);
+    }
+
+    /// Disables `source`
+    #[inline]
+    pub fn disable<I: InterruptNumber>(&self, source: I) {
+        unsafe { self.write(CLRIENUM, Self::source_number(source) as u32) };
+    }
+
+    /// Returns the raw `target` register of `source`
+    #[inline]
+    pub fn target<I: InterruptNumber>(&self, source: I) -> u32 {
+        self.read(Self::source_offset(TARGET, source))
+    }
+
+    /// Routes `source` to `hart` with `priority` (1 is the highest) in
+    /// direct delivery mode
+    ///
+    /// # Safety
+    ///
+    /// Moves the interrupt to another hart's handler.
+    #[inline]
+    pub unsafe fn set_target_direct<I: InterruptNumber>(&self, source: I, hart: u16, priority: u8) {
+        let mut target = 0u32;
+        target.set_bits(TARGET_HART, hart as u32);
+        target.set_bits(TARGET_IPRIO, priority as u32);
+        self.write(Self::source_offset(TARGET, source), target);
+    }
+
+    /// Routes `source` as external interrupt identity `eiid` to the
+    /// interrupt file of `hart` and `guest` (0 for the hart itself) in MSI
+    /// delivery mode
+    ///
+    /// # Safety
+    ///
+    /// Moves the interrupt to another hart's or guest's handler.
+    #[inline]
+    pub unsafe fn set_target_msi<I: InterruptNumber>(
+        &self,
+        source: I,
+        hart: u16,
+        guest: u8,
+        eiid: u16,
+    ) {
+        let mut target = 0u32;
+        target.set_bits(TARGET_HART, hart as u32);
+        target.set_bits(TARGET_GUEST, guest as u32);
+        target.set_bits(TARGET_EIID, eiid as u32);
+        self.write(Self::source_offset(TARGET, source), target);
+    }
+
+    /// Sends an MSI with identity `eiid` to the machine or supervisor
+    /// interrupt file of `hart`, e.g. to forward an interrupt or to flush
+    /// earlier MSIs of the domain, and waits until it has been sent
+    ///
+    /// Only available in MSI delivery mode.
+    #[inline]
+    pub fn generate_msi(&self, hart: u16, eiid: u16) {
+        let mut genmsi = 0u32;
+        genmsi.set_bits(TARGET_HART, hart as u32);
+        genmsi.set_bits(TARGET_EIID, eiid as u32);
+        unsafe { self.write(GENMSI, genmsi) };
+        while self.read(GENMSI).get_bit(GENMSI_BUSY) {}
+    }
+
+    /// Returns the interrupt delivery control of `hart` (direct mode only)
+    #[inline]
+    pub fn idc(&self, hart: usize) -> Idc {
+        Idc {
+            base: self.base + IDC + IDC_STRIDE * hart,
+        }
+    }
+}
+
+/// Interrupt delivery control of one hart in direct delivery mode
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Idc {
+    base: usize,
+}
+
+impl Idc {
+    #[inline]
+    fn read(&self, offset: usize) -> u32 {
+        unsafe { ptr::read_volatile((self.base + offset) as *const u32) }
+    }
+
+    #[inline]
+    unsafe fn write(&self, offset: usize, value: u32) {
+        ptr::write_volatile((self.base + offset) as *mut u32, value);
+    }
+
+    /// Enables or disables interrupt delivery to the hart
+    ///
+    /// # Safety
+    ///
+    /// Enabling delivery can break mask-based critical sections.
+    #[inline]
+    pub unsafe fn set_delivery(&self, enabled: bool) {
+        self.write(IDELIVERY, enabled as u32);
+    }
+
+    /// Forces (or stops forcing) a spurious interrupt, e.g. to test the
+    /// handler
+    ///
+    /// # Safety
+    ///
+    /// The forced interrupt is taken like a real one.
+    #[inline]
+    pub unsafe fn set_force(&self, force: bool) {
+        self.write(IFORCE, force as u32);
+    }
+
+    /// Returns the priority threshold; 0 means no threshold
+    #[inline]
+    pub fn threshold(&self) -> u32 {
+        self.read(ITHRESHOLD)
+    }
+
+    /// Sets the priority threshold; only sources with a higher priority
+    /// (lower number) are delivered
+    ///
+    /// # Safety
+    ///
+    /// Raising the threshold can break priority-based critical sections.
+    #[inline]
+    pub unsafe fn set_threshold(&self, threshold: u32) {
+        self.write(ITHRESHOLD, threshold);
+    }
+
+    #[inline]
+    fn decode(topi: u32) -> Option<Claimed> {
+        match topi.get_bits(16..26) {
+            0 => None,
+            n => Some(Claimed {
+                source: InterruptSource(n as u16),
+                priority: topi.get_bits(0..8) as u8,
+            }),
+        }
+    }
+
+    /// Returns the highest-priority pending and enabled interrupt without
+    /// claiming it
+    #[inline]
+    pub fn top(&self) -> Option<Claimed> {
+        Self::decode(self.read(TOPI))
+    }
+
+    /// Claims the highest-priority pending and enabled interrupt
+    ///
+    /// Claiming clears the pending bit of edge-triggered sources; a forced
+    /// interrupt is returned as `None` and clears the force bit.
+    #[inline]
+    pub fn claim(&self) -> Option<Claimed> {
+        Self::decode(self.read(CLAIMI))
+    }
+}
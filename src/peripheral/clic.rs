@@ -0,0 +1,250 @@
+//! Core-local interrupt controller (CLIC)
+//!
+//! Memory-mapped registers of a CLIC as defined by the RISC-V CLIC
+//! specification: the global `cliccfg` byte and, for every interrupt, the
+//! `clicintip`, `clicintie`, `clicintattr` and `clicintctl` bytes. The trap
+//! vector table base lives in the `mtvt`/`stvt` CSRs, and `mtvec`/`stvec`
+//! must be written with `TrapMode::Clic` to enable CLIC mode.
+//!
+//! Functions taking an interrupt panic if it is outside of [`INTERRUPTS`].
+
+use bit_field::BitField;
+use core::ptr;
+
+use interrupt::{Interrupt, InterruptNumber};
+use register::mstatus::MPP;
+
+/// Number of interrupts addressable by a CLIC
+pub const INTERRUPTS: usize = 4096;
+
+const CLICCFG_OFFSET: usize = 0;
+const INT_OFFSET: usize = 0x1000;
+const INT_STRIDE: usize = 4;
+const INTIP: usize = 0;
+const INTIE: usize = 1;
+const INTATTR: usize = 2;
+const INTCTL: usize = 3;
+
+const ATTR_SHV: usize = 0;
+const ATTR_TRIG: core::ops::Range<usize> = 1..3;
+const ATTR_MODE: core::ops::Range<usize> = 6..8;
+
+/// Trigger type of an interrupt
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Trigger {
+    PositiveLevel = 0,
+    PositiveEdge = 1,
+    NegativeLevel = 2,
+    NegativeEdge = 3,
+}
+
+/// Interrupt id of a CLIC
+///
+/// Ids 0-15 are the core-local interrupts, numbered by their cause codes,
+/// and higher ids are platform interrupts.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct ClicIrq(pub u16);
+
+impl ClicIrq {
+    /// Returns the id of a core-local interrupt, or `None` for
+    /// `Interrupt::Unknown`
+    #[inline]
+    pub fn local(interrupt: Interrupt) -> Option<Self> {
+        interrupt.to_code().map(|code| ClicIrq(code as u16))
+    }
+}
+
+unsafe impl InterruptNumber for ClicIrq {
+    #[inline]
+    fn number(self) -> u16 {
+        self.0
+    }
+}
+
+/// A CLIC instance
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Clic {
+    base: usize,
+}
+
+impl Clic {
+    /// Creates a handle to the CLIC mapped at `base`
+    ///
+    /// # Safety
+    ///
+    /// `base` must be the address of a CLIC, and the handle must only be
+    /// used where the CLIC is accessible.
+    #[inline]
+    pub const unsafe fn new(base: usize) -> Self {
+        Clic { base }
+    }
+
+    /// Returns the base address
+    #[inline]
+    pub fn base(&self) -> usize {
+        self.base
+    }
+
+    #[inline]
+    fn reg<I: InterruptNumber>(&self, interrupt: I, offset: usize) -> *mut u8 {
+        let n = interrupt.number() as usize;
+        assert!(n < INTERRUPTS, "CLIC interrupt {} out of range", n);
+        (self.base + INT_OFFSET + INT_STRIDE * n + offset) as *mut u8
+    }
+
+    #[inline]
+    fn attr<I: InterruptNumber>(&self, interrupt: I) -> u8 {
+        unsafe { ptr::read_volatile(self.reg(interrupt, INTATTR)) }
+    }
+
+    #[inline]
+    unsafe fn modify_attr<I: InterruptNumber, F: FnOnce(&mut u8)>(&self, interrupt: I, f: F) {
+        let mut attr = self.attr(interrupt);
+        f(&mut attr);
+        ptr::write_volatile(self.reg(interrupt, INTATTR), attr);
+    }
+
+    /// Returns the raw `cliccfg` register
+    #[inline]
+    pub fn cliccfg(&self) -> u8 {
+        unsafe { ptr::read_volatile((self.base + CLICCFG_OFFSET) as *const u8) }
+    }
+
+    /// Writes the raw `cliccfg` register
+    ///
+    /// # Safety
+    ///
+    /// Changes how `clicintctl` is split into level and priority, and which
+    /// privilege modes interrupts can be assigned to.
+    #[inline]
+    pub unsafe fn set_cliccfg(&self, cfg: u8) {
+        ptr::write_volatile((self.base + CLICCFG_OFFSET) as *mut u8, cfg);
+    }
+
+    /// Returns true if `interrupt` is pending
+    #[inline]
+    pub fn is_pending<I: InterruptNumber>(&self, interrupt: I) -> bool {
+        unsafe { ptr::read_volatile(self.reg(interrupt, INTIP)) & 1 != 0 }
+    }
+
+    /// Sets the pending bit of an edge-triggered `interrupt`
+    #[inline]
+    pub fn pend<I: InterruptNumber>(&self, interrupt: I) {
+        unsafe { ptr::write_volatile(self.reg(interrupt, INTIP), 1) };
+    }
+
+    /// Clears the pending bit of an edge-triggered `interrupt`
+    #[inline]
+    pub fn unpend<I: InterruptNumber>(&self, interrupt: I) {
+        unsafe { ptr::write_volatile(self.reg(interrupt, INTIP), 0) };
+    }
+
+    /// Returns true if `interrupt` is enabled
+    #[inline]
+    pub fn is_enabled<I: InterruptNumber>(&self, interrupt: I) -> bool {
+        unsafe { ptr::read_volatile(self.reg(interrupt, INTIE)) & 1 != 0 }
+    }
+
+    /// Enables `interrupt`
+    ///
+    /// # Safety
+    ///
+    /// Enabling an interrupt can break mask-based critical sections.
+    #[inline]
+    pub unsafe fn enable<I: InterruptNumber>(&self, interrupt: I) {
+        ptr::write_volatile(self.reg(interrupt, INTIE), 1);
+    }
+
+    /// Disables `interrupt`
+    #[inline]
+    pub fn disable<I: InterruptNumber>(&self, interrupt: I) {
+        unsafe { ptr::write_volatile(self.reg(interrupt, INTIE), 0) };
+    }
+
+    /// Returns true if `interrupt` uses selective hardware vectoring, i.e.
+    /// jumps to its entry of the `mtvt`/`stvt` table
+    #[inline]
+    pub fn is_vectored<I: InterruptNumber>(&self, interrupt: I) -> bool {
+        self.attr(interrupt).get_bit(ATTR_SHV)
+    }
+
+    /// Enables or disables selective hardware vectoring for `interrupt`
+    ///
+    /// # Safety
+    ///
+    /// A vectored interrupt jumps to the address in its table entry, which
+    /// must point to a suitable handler.
+    #[inline]
+    pub unsafe fn set_vectored<I: InterruptNumber>(&self, interrupt: I, vectored: bool) {
+        self.modify_attr(interrupt, |attr| {
+            attr.set_bit(ATTR_SHV, vectored);
+        });
+    }
+
+    /// Returns the trigger type of `interrupt`
+    #[inline]
+    pub fn trigger<I: InterruptNumber>(&self, interrupt: I) -> Trigger {
+        match self.attr(interrupt).get_bits(ATTR_TRIG) {
+            0 => Trigger::PositiveLevel,
+            1 => Trigger::PositiveEdge,
+            2 => Trigger::NegativeLevel,
+            _ => Trigger::NegativeEdge,
+        }
+    }
+
+    /// Sets the trigger type of `interrupt`
+    ///
+    /// Implementations may hardwire the trigger type.
+    ///
+    /// # Safety
+    ///
+    /// A wrong trigger type can leave the interrupt pending forever.
+    #[inline]
+    pub unsafe fn set_trigger<I: InterruptNumber>(&self, interrupt: I, trigger: Trigger) {
+        self.modify_attr(interrupt, |attr| {
+            attr.set_bits(ATTR_TRIG, trigger as u8);
+        });
+    }
+
+    /// Returns the privilege mode `interrupt` is taken in
+    #[inline]
+    pub fn mode<I: InterruptNumber>(&self, interrupt: I) -> Option<MPP> {
+        match self.attr(interrupt).get_bits(ATTR_MODE) {
+            0 => Some(MPP::User),
+            1 => Some(MPP::Supervisor),
+            3 => Some(MPP::Machine),
+            _ => None,
+        }
+    }
+
+    /// Sets the privilege mode `interrupt` is taken in
+    ///
+    /// # Safety
+    ///
+    /// Hands the interrupt over to the handler of another privilege mode.
+    #[inline]
+    pub unsafe fn set_mode<I: InterruptNumber>(&self, interrupt: I, mode: MPP) {
+        self.modify_attr(interrupt, |attr| {
+            attr.set_bits(ATTR_MODE, mode as u8);
+        });
+    }
+
+    /// Returns the raw `clicintctl` byte (level and priority) of `interrupt`
+    #[inline]
+    pub fn control<I: InterruptNumber>(&self, interrupt: I) -> u8 {
+        unsafe { ptr::read_volatile(self.reg(interrupt, INTCTL)) }
+    }
+
+    /// Sets the raw `clicintctl` byte of `interrupt`
+    ///
+    /// How the byte is split into level and priority is configured in
+    /// `cliccfg`; unimplemented low bits read as one.
+    ///
+    /// # Safety
+    ///
+    /// Changing levels can break level-based critical sections.
+    #[inline]
+    pub unsafe fn set_control<I: InterruptNumber>(&self, interrupt: I, control: u8) {
+        ptr::write_volatile(self.reg(interrupt, INTCTL), control);
+    }
+}
@@ -0,0 +1,123 @@
+//! Core-local interruptor (CLINT)
+//!
+//! The SiFive-compatible CLINT layout: one `msip` word per hart for machine
+//! software interrupts, one 64-bit `mtimecmp` per hart and the shared 64-bit
+//! `mtime` counter.
+//!
+//! Functions taking a hart panic if it is not below [`HARTS`].
+
+use core::ptr;
+
+use delay::{read_mtime, Delay};
+use time::Monotonic;
+
+/// Number of harts a CLINT can serve, limited by the `mtimecmp` array
+/// ending at `mtime`
+pub const HARTS: usize = 4095;
+
+const MSIP_OFFSET: usize = 0;
+const MTIMECMP_OFFSET: usize = 0x4000;
+const MTIME_OFFSET: usize = 0xBFF8;
+
+#[inline]
+fn check_hart(hart: usize) {
+    assert!(hart < HARTS, "CLINT hart {} out of range", hart);
+}
+
+/// A CLINT instance
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Clint {
+    base: usize,
+}
+
+impl Clint {
+    /// Creates a handle to the CLINT mapped at `base`
+    ///
+    /// # Safety
+    ///
+    /// `base` must be the address of a CLINT, and the handle must only be
+    /// used where the CLINT is accessible.
+    #[inline]
+    pub const unsafe fn new(base: usize) -> Self {
+        Clint { base }
+    }
+
+    /// Returns the base address
+    #[inline]
+    pub fn base(&self) -> usize {
+        self.base
+    }
+
+    /// Returns true if a machine software interrupt is pending for `hart`
+    #[inline]
+    pub fn msip(&self, hart: usize) -> bool {
+        check_hart(hart);
+        unsafe { ptr::read_volatile((self.base + MSIP_OFFSET + 4 * hart) as *const u32) & 1 != 0 }
+    }
+
+    /// Raises or clears the machine software interrupt of `hart`
+    #[inline]
+    pub fn set_msip(&self, hart: usize, pending: bool) {
+        check_hart(hart);
+        unsafe {
+            ptr::write_volatile(
+                (self.base + MSIP_OFFSET + 4 * hart) as *mut u32,
+                pending as u32,
+            )
+        };
+    }
+
+    /// Returns the address of the `mtime` register
+    #[inline]
+    pub fn mtime_address(&self) -> usize {
+        self.base + MTIME_OFFSET
+    }
+
+    /// Reads `mtime`
+    #[inline]
+    pub fn mtime(&self) -> u64 {
+        unsafe { read_mtime(self.mtime_address()) }
+    }
+
+    /// Reads the `mtimecmp` register of `hart`
+    #[inline]
+    pub fn mtimecmp(&self, hart: usize) -> u64 {
+        check_hart(hart);
+        unsafe { read_mtime(self.base + MTIMECMP_OFFSET + 8 * hart) }
+    }
+
+    /// Writes the `mtimecmp` register of `hart`
+    ///
+    /// On RV32 the low half is first set to all ones, so that no spurious
+    /// timer interrupt is raised while the two halves are updated.
+    #[inline]
+    pub fn set_mtimecmp(&self, hart: usize, value: u64) {
+        check_hart(hart);
+        let addr = self.base + MTIMECMP_OFFSET + 8 * hart;
+        unsafe {
+            match () {
+                #[cfg(riscv32)]
+                () => {
+                    ptr::write_volatile(addr as *mut u32, u32::MAX);
+                    ptr::write_volatile((addr + 4) as *mut u32, (value >> 32) as u32);
+                    ptr::write_volatile(addr as *mut u32, value as u32);
+                }
+
+                #[cfg(not(riscv32))]
+                () => ptr::write_volatile(addr as *mut u64, value),
+            }
+        }
+    }
+
+    /// Returns a delay driven by `mtime`, which ticks at `frequency` Hz
+    #[inline]
+    pub fn delay(&self, frequency: u32) -> Delay {
+        unsafe { Delay::new(::delay::Counter::Mtime(self.mtime_address()), frequency) }
+    }
+
+    /// Returns a monotonic clock reading `mtime`, which ticks at `frequency` Hz
+    #[inline]
+    pub fn monotonic(&self, frequency: u32) -> Monotonic {
+        unsafe { Monotonic::new(::delay::Counter::Mtime(self.mtime_address()), frequency) }
+    }
+}
@@ -0,0 +1,102 @@
+//! Memory-mapped peripherals
+//!
+//! Drivers for the standard interrupt controllers and timers found next to
+//! RISC-V harts. They only hold the base address of the device, which the
+//! platform has to provide (e.g. from the device tree).
+
+pub mod aplic;
+pub mod clic;
+pub mod clint;
+pub mod plic;
+
+use self::clint::Clint;
+use self::plic::Plic;
+
+/// The standard memory-mapped peripherals of the platform
+///
+/// Their base addresses are platform specific, so unlike on Cortex-M they
+/// are passed to `take`; a platform crate usually wraps it with its own
+/// constants.
+#[derive(Debug)]
+pub struct Peripherals {
+    /// Core-local interruptor
+    pub clint: Clint,
+    /// Platform-level interrupt controller
+    pub plic: Plic,
+}
+
+// Set once `take` handed out the peripherals
+static mut TAKEN: bool = false;
+
+impl Peripherals {
+    /// Returns the peripherals the first time it is called, `None` afterwards
+    ///
+    /// Like `interrupt::free`, the check only excludes other code on the
+    /// current hart; call it from one hart, e.g. the boot hart.
+    ///
+    /// # Safety
+    ///
+    /// `clint_base` and `plic_base` must be the addresses of the CLINT and the
+    /// PLIC of the platform.
+    #[inline]
+    pub unsafe fn take(clint_base: usize, plic_base: usize) -> Option<Self> {
+        ::interrupt::free(|_| {
+            if TAKEN {
+                None
+            } else {
+                Some(Peripherals::steal(clint_base, plic_base))
+            }
+        })
+    }
+
+    /// Returns the peripherals, even if they have already been taken
+    ///
+    /// # Safety
+    ///
+    /// Like `take`; additionally, the returned handles alias any that were
+    /// handed out before.
+    #[inline]
+    pub unsafe fn steal(clint_base: usize, plic_base: usize) -> Self {
+        TAKEN = true;
+        Peripherals {
+            clint: Clint::new(clint_base),
+            plic: Plic::new(plic_base),
+        }
+    }
+}
+
+#[doc(hidden)]
+pub mod export {
+    pub use core::option::Option;
+    pub use core::ptr::addr_of_mut;
+}
+
+/// Returns a `&'static mut` reference to a value the first time the
+/// expansion is executed, `None` afterwards
+///
+/// The value is placed in a static, so it can be large without using
+/// stack space. The check runs in `interrupt::free` and therefore only
+/// excludes other code on the current hart.
+///
+/// ```ignore
+/// let buffer: &'static mut [u8; 1024] = singleton!(: [u8; 1024] = [0; 1024]).unwrap();
+/// ```
+#[macro_export]
+macro_rules! singleton {
+    ($name:ident: $ty:ty = $expr:expr) => {
+        $crate::interrupt::free(|_| {
+            static mut $name: $crate::peripheral::export::Option<$ty> =
+                $crate::peripheral::export::Option::None;
+            let slot = unsafe { &mut *$crate::peripheral::export::addr_of_mut!($name) };
+            if slot.is_some() {
+                $crate::peripheral::export::Option::None
+            } else {
+                *slot = $crate::peripheral::export::Option::Some($expr);
+                slot.as_mut()
+            }
+        })
+    };
+    (: $ty:ty = $expr:expr) => {
+        $crate::singleton!(VAR: $ty = $expr)
+    };
+}
@@ -0,0 +1,203 @@
+//! Platform-level interrupt controller (PLIC)
+//!
+//! Register layout as defined by the RISC-V PLIC specification: per-source
+//! priorities and pending bits, and per-context enable bits, priority
+//! threshold and claim/complete register. A context is usually one privilege
+//! mode of one hart.
+//!
+//! Functions taking a source or context panic if it is outside of
+//! [`SOURCES`] or [`CONTEXTS`].
+
+use core::ptr;
+
+use interrupt::InterruptNumber;
+pub use interrupt::PlicIrq as InterruptSource;
+
+/// Number of interrupt sources, including the reserved source 0
+pub const SOURCES: usize = 1024;
+/// Number of contexts
+pub const CONTEXTS: usize = 15872;
+
+const PRIORITY_OFFSET: usize = 0;
+const PENDING_OFFSET: usize = 0x1000;
+const ENABLE_OFFSET: usize = 0x2000;
+const ENABLE_STRIDE: usize = 0x80;
+const CONTEXT_OFFSET: usize = 0x20_0000;
+const CONTEXT_STRIDE: usize = 0x1000;
+
+/// A PLIC context
+///
+/// The mapping from harts and privilege modes to contexts is platform
+/// specific.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Context(pub u16);
+
+/// Returns the number of `source`, checked against `SOURCES`
+#[inline]
+fn source_number<I: InterruptNumber>(source: I) -> usize {
+    let n = source.number() as usize;
+    assert!(n < SOURCES, "PLIC source {} out of range", n);
+    n
+}
+
+/// A PLIC instance
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Plic {
+    base: usize,
+}
+
+impl Plic {
+    /// Creates a handle to the PLIC mapped at `base`
+    ///
+    /// # Safety
+    ///
+    /// `base` must be the address of a PLIC, and the handle must only be
+    /// used where the PLIC is accessible.
+    #[inline]
+    pub const unsafe fn new(base: usize) -> Self {
+        Plic { base }
+    }
+
+    /// Returns the base address
+    #[inline]
+    pub fn base(&self) -> usize {
+        self.base
+    }
+
+    #[inline]
+    fn reg(&self, offset: usize) -> *mut u32 {
+        (self.base + offset) as *mut u32
+    }
+
+    /// Returns the priority of `source` (0 means never interrupt)
+    #[inline]
+    pub fn priority<I: InterruptNumber>(&self, source: I) -> u32 {
+        unsafe { ptr::read_volatile(self.reg(PRIORITY_OFFSET + 4 * source_number(source))) }
+    }
+
+    /// Sets the priority of `source`
+    ///
+    /// The number of supported priority levels is platform specific; unused
+    /// high bits are ignored.
+    ///
+    /// # Safety
+    ///
+    /// Changing priorities can break priority-based critical sections.
+    #[inline]
+    pub unsafe fn set_priority<I: InterruptNumber>(&self, source: I, priority: u32) {
+        ptr::write_volatile(
+            self.reg(PRIORITY_OFFSET + 4 * source_number(source)),
+            priority,
+        );
+    }
+
+    /// Returns true if `source` is pending
+    #[inline]
+    pub fn is_pending<I: InterruptNumber>(&self, source: I) -> bool {
+        let n = source_number(source);
+        let word = unsafe { ptr::read_volatile(self.reg(PENDING_OFFSET + 4 * (n / 32))) };
+        word & (1 << (n % 32)) != 0
+    }
+
+    /// Returns the registers of `context`
+    #[inline]
+    pub fn context(&self, context: Context) -> PlicContext {
+        assert!(
+            (context.0 as usize) < CONTEXTS,
+            "PLIC context {} out of range",
+            context.0
+        );
+        PlicContext {
+            plic: *self,
+            context: context.0 as usize,
+        }
+    }
+}
+
+/// The enable bits, threshold and claim/complete register of one context
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct PlicContext {
+    plic: Plic,
+    context: usize,
+}
+
+impl PlicContext {
+    #[inline]
+    fn enable_reg(&self, n: usize) -> *mut u32 {
+        self.plic
+            .reg(ENABLE_OFFSET + ENABLE_STRIDE * self.context + 4 * (n / 32))
+    }
+
+    #[inline]
+    fn context_reg(&self, offset: usize) -> *mut u32 {
+        self.plic
+            .reg(CONTEXT_OFFSET + CONTEXT_STRIDE * self.context + offset)
+    }
+
+    /// Returns true if `source` is enabled for this context
+    #[inline]
+    pub fn is_enabled<I: InterruptNumber>(&self, source: I) -> bool {
+        let n = source_number(source);
+        unsafe { ptr::read_volatile(self.enable_reg(n)) & (1 << (n % 32)) != 0 }
+    }
+
+    /// Enables `source` for this context
+    ///
+    /// The enable word is updated with a read-modify-write, which must not
+    /// race with other updates of the same context.
+    ///
+    /// # Safety
+    ///
+    /// Enabling an interrupt can break mask-based critical sections.
+    #[inline]
+    pub unsafe fn enable<I: InterruptNumber>(&self, source: I) {
+        let n = source_number(source);
+        let reg = self.enable_reg(n);
+        ptr::write_volatile(reg, ptr::read_volatile(reg) | (1 << (n % 32)));
+    }
+
+    /// Disables `source` for this context
+    ///
+    /// See `enable` for concurrent updates.
+    #[inline]
+    pub fn disable<I: InterruptNumber>(&self, source: I) {
+        let n = source_number(source);
+        let reg = self.enable_reg(n);
+        unsafe { ptr::write_volatile(reg, ptr::read_volatile(reg) & !(1 << (n % 32))) };
+    }
+
+    /// Returns the priority threshold; only sources with a higher priority
+    /// interrupt this context
+    #[inline]
+    pub fn threshold(&self) -> u32 {
+        unsafe { ptr::read_volatile(self.context_reg(0)) }
+    }
+
+    /// Sets the priority threshold
+    ///
+    /// # Safety
+    ///
+    /// Lowering the threshold can break priority-based critical sections.
+    #[inline]
+    pub unsafe fn set_threshold(&self, threshold: u32) {
+        ptr::write_volatile(self.context_reg(0), threshold);
+    }
+
+    /// Claims the highest-priority pending interrupt of this context
+    ///
+    /// Returns `None` if no interrupt is pending. The claimed source is not
+    /// signalled again until it is passed to `complete`.
+    #[inline]
+    pub fn claim(&self) -> Option<InterruptSource> {
+        match unsafe { ptr::read_volatile(self.context_reg(4)) } {
+            0 => None,
+            n => Some(InterruptSource(n as u16)),
+        }
+    }
+
+    /// Signals that the handler of the claimed `source` has finished
+    #[inline]
+    pub fn complete<I: InterruptNumber>(&self, source: I) {
+        unsafe { ptr::write_volatile(self.context_reg(4), source.number() as u32) };
+    }
+}
@@ -0,0 +1,92 @@
+//! Access to arbitrary CSRs by number
+//!
+//! Intended for vendor-specific CSRs that have no dedicated module. On RISC-V
+//! targets this module only exists with the `inline-asm` feature, as the CSR
+//! number is encoded into the instruction and the prebuilt blobs cannot cover
+//! every number. CSR numbers are 12 bits wide; `NUM` values of `0x1000` and
+//! above are rejected at compile time.
+
+#[cfg(all(not(riscv), feature = "emulation"))]
+use super::emulation;
+
+/// Compile-time check of the CSR number `NUM`
+struct Csr<const NUM: u16>;
+
+impl<const NUM: u16> Csr<NUM> {
+    const VALID: () = assert!(NUM < 0x1000, "CSR numbers are 12 bits wide");
+}
+
+/// Reads CSR `NUM`
+#[inline]
+pub fn read<const NUM: u16>() -> usize {
+    let () = Csr::<NUM>::VALID;
+    match () {
+        #[cfg(riscv)]
+        () => {
+            let r: usize;
+            unsafe { core::arch::asm!("csrrs {0}, {1}, x0", out(reg) r, const NUM) };
+            r
+        }
+
+        #[cfg(all(not(riscv), feature = "emulation"))]
+        () => emulation::read(NUM as usize),
+
+        #[cfg(all(not(riscv), not(feature = "emulation")))]
+        () => unimplemented!(),
+    }
+}
+
+/// Writes `bits` to CSR `NUM`
+#[inline]
+#[allow(unused_variables)]
+pub unsafe fn write<const NUM: u16>(bits: usize) {
+    let () = Csr::<NUM>::VALID;
+    match () {
+        #[cfg(riscv)]
+        () => core::arch::asm!("csrrw x0, {1}, {0}", in(reg) bits, const NUM),
+
+        #[cfg(all(not(riscv), feature = "emulation"))]
+        () => emulation::write(NUM as usize, bits),
+
+        #[cfg(all(not(riscv), not(feature = "emulation")))]
+        () => unimplemented!(),
+    }
+}
+
+/// Sets the bits of CSR `NUM` that are set in `bits`
+#[inline]
+#[allow(unused_variables)]
+pub unsafe fn set_bits<const NUM: u16>(bits: usize) {
+    let () = Csr::<NUM>::VALID;
+    match () {
+        #[cfg(riscv)]
+        () => core::arch::asm!("csrrs x0, {1}, {0}", in(reg) bits, const NUM),
+
+        #[cfg(all(not(riscv), feature = "emulation"))]
+        () => {
+            emulation::read_set(NUM as usize, bits);
+        }
+
+        #[cfg(all(not(riscv), not(feature = "emulation")))]
+        () => unimplemented!(),
+    }
+}
+
+/// Clears the bits of CSR `NUM` that are set in `bits`
+#[inline]
+#[allow(unused_variables)]
+pub unsafe fn clear_bits<const NUM: u16>(bits: usize) {
+    let () = Csr::<NUM>::VALID;
+    match () {
+        #[cfg(riscv)]
+        () => core::arch::asm!("csrrc x0, {1}, {0}", in(reg) bits, const NUM),
+
+        #[cfg(all(not(riscv), feature = "emulation"))]
+        () => {
+            emulation::read_clear(NUM as usize, bits);
+        }
+
+        #[cfg(all(not(riscv), not(feature = "emulation")))]
+        () => unimplemented!(),
+    }
+}
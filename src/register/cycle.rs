@@ -0,0 +1,4 @@
+//! cycle register
+
+read_csr_as_usize!(0xC00, __read_cycle);
+read_composite_csr!(super::cycleh::read(), read());
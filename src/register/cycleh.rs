@@ -0,0 +1,3 @@
+//! cycleh register
+
+read_csr_as_usize_rv32!(0xC80, __read_cycleh);
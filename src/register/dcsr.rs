@@ -0,0 +1,195 @@
+//! dcsr register
+
+use bit_field::BitField;
+
+/// Debug control and status register
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Dcsr {
+    bits: usize,
+}
+
+/// Reason for entering Debug Mode
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Cause {
+    /// An `ebreak` instruction was executed
+    Ebreak = 1,
+    /// A trigger module fired with action set to enter Debug Mode
+    Trigger = 2,
+    /// The debugger requested entry to Debug Mode using `haltreq`
+    HaltRequest = 3,
+    /// The hart single stepped because `step` was set
+    Step = 4,
+    /// The hart halted directly out of reset due to `resethaltreq`
+    ResetHaltRequest = 5,
+    /// The hart halted because it is part of a halt group
+    HaltGroup = 6,
+}
+
+/// Privilege level the hart was operating in when Debug Mode was entered
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Prv {
+    User = 0,
+    Supervisor = 1,
+    Machine = 3,
+}
+
+impl Dcsr {
+    /// Returns the contents of the register as raw bits
+    #[inline]
+    pub fn bits(&self) -> usize {
+        self.bits
+    }
+
+    /// Debug support version (0 = none, 4 = external debug support)
+    #[inline]
+    pub fn xdebugver(&self) -> usize {
+        self.bits.get_bits(28..32)
+    }
+
+    /// `ebreak` in VS-mode enters Debug Mode
+    #[inline]
+    pub fn ebreakvs(&self) -> bool {
+        self.bits.get_bit(17)
+    }
+
+    /// `ebreak` in VU-mode enters Debug Mode
+    #[inline]
+    pub fn ebreakvu(&self) -> bool {
+        self.bits.get_bit(16)
+    }
+
+    /// `ebreak` in M-mode enters Debug Mode
+    #[inline]
+    pub fn ebreakm(&self) -> bool {
+        self.bits.get_bit(15)
+    }
+
+    /// `ebreak` in S-mode enters Debug Mode
+    #[inline]
+    pub fn ebreaks(&self) -> bool {
+        self.bits.get_bit(13)
+    }
+
+    /// `ebreak` in U-mode enters Debug Mode
+    #[inline]
+    pub fn ebreaku(&self) -> bool {
+        self.bits.get_bit(12)
+    }
+
+    /// Interrupts are enabled during single stepping
+    #[inline]
+    pub fn stepie(&self) -> bool {
+        self.bits.get_bit(11)
+    }
+
+    /// Counters stop while in Debug Mode
+    #[inline]
+    pub fn stopcount(&self) -> bool {
+        self.bits.get_bit(10)
+    }
+
+    /// Timers stop while in Debug Mode
+    #[inline]
+    pub fn stoptime(&self) -> bool {
+        self.bits.get_bit(9)
+    }
+
+    /// Reason for entering Debug Mode, or `None` if reserved
+    #[inline]
+    pub fn cause(&self) -> Option<Cause> {
+        match self.bits.get_bits(6..9) {
+            1 => Some(Cause::Ebreak),
+            2 => Some(Cause::Trigger),
+            3 => Some(Cause::HaltRequest),
+            4 => Some(Cause::Step),
+            5 => Some(Cause::ResetHaltRequest),
+            6 => Some(Cause::HaltGroup),
+            _ => None,
+        }
+    }
+
+    /// Virtualization mode the hart was operating in when Debug Mode was entered
+    #[inline]
+    pub fn v(&self) -> bool {
+        self.bits.get_bit(5)
+    }
+
+    /// `mprv` in `mstatus` takes effect in Debug Mode
+    #[inline]
+    pub fn mprven(&self) -> bool {
+        self.bits.get_bit(4)
+    }
+
+    /// A non-maskable interrupt is pending
+    #[inline]
+    pub fn nmip(&self) -> bool {
+        self.bits.get_bit(3)
+    }
+
+    /// Single step is enabled
+    #[inline]
+    pub fn step(&self) -> bool {
+        self.bits.get_bit(2)
+    }
+
+    /// Privilege level the hart was operating in when Debug Mode was entered
+    #[inline]
+    pub fn prv(&self) -> Prv {
+        match self.bits.get_bits(0..2) {
+            0 => Prv::User,
+            1 => Prv::Supervisor,
+            3 => Prv::Machine,
+            _ => unreachable!(),
+        }
+    }
+}
+
+read_csr_as!(Dcsr, 0x7B0, __read_dcsr);
+write_csr!(0x7B0, __write_dcsr);
+set!(0x7B0, __set_dcsr);
+clear!(0x7B0, __clear_dcsr);
+
+set_clear_csr!(
+    /// `ebreak` in VS-mode enters Debug Mode
+    , set_ebreakvs, clear_ebreakvs, 1 << 17);
+set_clear_csr!(
+    /// `ebreak` in VU-mode enters Debug Mode
+    , set_ebreakvu, clear_ebreakvu, 1 << 16);
+set_clear_csr!(
+    /// `ebreak` in M-mode enters Debug Mode
+    , set_ebreakm, clear_ebreakm, 1 << 15);
+set_clear_csr!(
+    /// `ebreak` in S-mode enters Debug Mode
+    , set_ebreaks, clear_ebreaks, 1 << 13);
+set_clear_csr!(
+    /// `ebreak` in U-mode enters Debug Mode
+    , set_ebreaku, clear_ebreaku, 1 << 12);
+set_clear_csr!(
+    /// Interrupts are enabled during single stepping
+    , set_stepie, clear_stepie, 1 << 11);
+set_clear_csr!(
+    /// Counters stop while in Debug Mode
+    , set_stopcount, clear_stopcount, 1 << 10);
+set_clear_csr!(
+    /// Timers stop while in Debug Mode
+    , set_stoptime, clear_stoptime, 1 << 9);
+set_clear_csr!(
+    /// Virtualization mode to return to on `dret`
+    , set_v, clear_v, 1 << 5);
+set_clear_csr!(
+    /// `mprv` in `mstatus` takes effect in Debug Mode
+    , set_mprven, clear_mprven, 1 << 4);
+set_clear_csr!(
+    /// Single step is enabled
+    , set_step, clear_step, 1 << 2);
+
+/// Privilege level to return to on `dret`
+#[inline]
+pub unsafe fn set_prv(prv: Prv) {
+    let mut value = _read();
+    value.set_bits(0..2, prv as usize);
+    _write(value);
+}
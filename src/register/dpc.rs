@@ -0,0 +1,4 @@
+//! dpc register
+
+read_csr_as_usize!(0x7B1, __read_dpc);
+write_csr_as_usize!(0x7B1, __write_dpc);
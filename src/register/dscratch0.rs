@@ -0,0 +1,4 @@
+//! dscratch0 register
+
+read_csr_as_usize!(0x7B2, __read_dscratch0);
+write_csr_as_usize!(0x7B2, __write_dscratch0);
@@ -0,0 +1,4 @@
+//! dscratch1 register
+
+read_csr_as_usize!(0x7B3, __read_dscratch1);
+write_csr_as_usize!(0x7B3, __write_dscratch1);
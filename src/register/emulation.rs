@@ -0,0 +1,66 @@
+//! Emulated register file for host-side testing
+//!
+//! With the `emulation` feature enabled on a non-RISC-V target, every CSR
+//! access made through the `register` modules (and `register::csr`) is
+//! served from a per-thread array of 4096 registers instead of panicking.
+//! All registers start out as zero and behave as plain read/write storage:
+//! there are no read-only, WARL or side-effect semantics, and the RV32-only
+//! high halves stay unavailable because the host emulates an RV64 hart.
+//!
+//! Tests can seed values that hardware would provide (e.g. `mcause` or
+//! `mhartid`) with [`write`] and inspect what the code under test wrote with
+//! [`read`]. Each test thread sees its own register file.
+
+use core::cell::RefCell;
+
+/// Number of CSRs in the emulated register file
+pub const CSR_COUNT: usize = 4096;
+
+::std::thread_local! {
+    static CSRS: RefCell<[usize; CSR_COUNT]> = const { RefCell::new([0; CSR_COUNT]) };
+}
+
+#[inline]
+fn update<F: FnOnce(usize) -> usize>(csr: usize, f: F) -> usize {
+    CSRS.with(|csrs| {
+        let reg = &mut csrs.borrow_mut()[csr];
+        let old = *reg;
+        *reg = f(old);
+        old
+    })
+}
+
+/// Reads CSR number `csr`
+#[inline]
+pub fn read(csr: usize) -> usize {
+    CSRS.with(|csrs| csrs.borrow()[csr])
+}
+
+/// Writes `bits` to CSR number `csr`
+#[inline]
+pub fn write(csr: usize, bits: usize) {
+    update(csr, |_| bits);
+}
+
+/// Writes `bits` to CSR number `csr`, returning its previous value
+#[inline]
+pub fn swap(csr: usize, bits: usize) -> usize {
+    update(csr, |_| bits)
+}
+
+/// Sets `bits` in CSR number `csr`, returning its previous value
+#[inline]
+pub fn read_set(csr: usize, bits: usize) -> usize {
+    update(csr, |old| old | bits)
+}
+
+/// Clears `bits` in CSR number `csr`, returning its previous value
+#[inline]
+pub fn read_clear(csr: usize, bits: usize) -> usize {
+    update(csr, |old| old & !bits)
+}
+
+/// Resets every CSR of the current thread to zero
+pub fn reset() {
+    CSRS.with(|csrs| *csrs.borrow_mut() = [0; CSR_COUNT]);
+}
@@ -4,16 +4,19 @@ use bit_field::BitField;
 
 /// Floating-point control and status register
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct FCSR {
     bits: u32,
 }
 
 /// Accrued Exception Flags
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Flags(u32);
 
 /// Accrued Exception Flag
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Flag {
     /// Inexact
     NX = 0b00001,
@@ -32,6 +35,24 @@ pub enum Flag {
 }
 
 impl Flags {
+    /// Creates the flags from raw bits, ignoring bits outside the `fflags` field
+    #[inline]
+    pub fn from_bits(bits: u32) -> Flags {
+        Flags(bits & 0b11111)
+    }
+
+    /// Returns the flags as raw bits
+    #[inline]
+    pub fn bits(&self) -> u32 {
+        self.0
+    }
+
+    /// Returns `true` if `flag` is set
+    #[inline]
+    pub fn contains(&self, flag: Flag) -> bool {
+        self.0 & flag as u32 != 0
+    }
+
     /// Inexact
     #[inline]
     pub fn nx(&self) -> bool {
@@ -65,6 +86,7 @@ impl Flags {
 
 /// Rounding Mode
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum RoundingMode {
     RoundToNearestEven = 0b000,
     RoundTowardsZero = 0b001,
@@ -74,6 +96,20 @@ pub enum RoundingMode {
     Invalid = 0b111,
 }
 
+impl From<u32> for RoundingMode {
+    #[inline]
+    fn from(bits: u32) -> Self {
+        match bits & 0b111 {
+            0b000 => RoundingMode::RoundToNearestEven,
+            0b001 => RoundingMode::RoundTowardsZero,
+            0b010 => RoundingMode::RoundDown,
+            0b011 => RoundingMode::RoundUp,
+            0b100 => RoundingMode::RoundToNearestMaxMagnitude,
+            _ => RoundingMode::Invalid,
+        }
+    }
+}
+
 impl FCSR {
     /// Returns the contents of the register as raw bits
     pub fn bits(&self) -> u32 {
@@ -89,19 +125,13 @@ impl FCSR {
     /// Rounding Mode
     #[inline]
     pub fn frm(&self) -> RoundingMode {
-        match self.bits.get_bits(5..8) {
-            0b000 => RoundingMode::RoundToNearestEven,
-            0b001 => RoundingMode::RoundTowardsZero,
-            0b010 => RoundingMode::RoundDown,
-            0b011 => RoundingMode::RoundUp,
-            0b100 => RoundingMode::RoundToNearestMaxMagnitude,
-            _ => RoundingMode::Invalid,
-        }
+        RoundingMode::from(self.bits.get_bits(5..8))
     }
 }
 
 read_csr!(0x003, __read_fcsr);
 write_csr!(0x003, __write_fcsr);
+set!(0x003, __set_fcsr);
 clear!(0x003, __clear_fcsr);
 
 /// Reads the CSR
@@ -114,12 +144,24 @@ pub fn read() -> FCSR {
 
 /// Writes the CSR
 #[inline]
+pub unsafe fn write(frm: RoundingMode, fflags: Flags) {
+    _write((((frm as u32) << 5) | fflags.bits()) as usize);
+}
+
+/// Sets the `frm` field, preserving `fflags`
+#[inline]
 pub unsafe fn set_rounding_mode(frm: RoundingMode) {
     let old = read();
     let bits = ((frm as u32) << 5) | old.fflags().0;
     _write(bits as usize);
 }
 
+/// Sets `fflags` field bit
+#[inline]
+pub unsafe fn set_flag(flag: Flag) {
+    _set(flag as usize);
+}
+
 /// Resets `fflags` field bits
 #[inline]
 pub unsafe fn clear_flags() {
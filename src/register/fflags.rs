@@ -0,0 +1,38 @@
+//! Floating-point accrued exceptions register
+
+pub use super::fcsr::{Flag, Flags};
+
+read_csr!(0x001, __read_fflags);
+write_csr!(0x001, __write_fflags);
+set!(0x001, __set_fflags);
+clear!(0x001, __clear_fflags);
+
+/// Reads the CSR
+#[inline]
+pub fn read() -> Flags {
+    Flags::from_bits(unsafe { _read() } as u32)
+}
+
+/// Writes the CSR
+#[inline]
+pub unsafe fn write(flags: Flags) {
+    _write(flags.bits() as usize);
+}
+
+/// Sets `fflags` field bit
+#[inline]
+pub unsafe fn set_flag(flag: Flag) {
+    _set(flag as usize);
+}
+
+/// Resets `fflags` field bit
+#[inline]
+pub unsafe fn clear_flag(flag: Flag) {
+    _clear(flag as usize);
+}
+
+/// Resets all `fflags` field bits
+#[inline]
+pub unsafe fn clear() {
+    _clear(0b11111);
+}
@@ -0,0 +1,18 @@
+//! Floating-point dynamic rounding mode register
+
+pub use super::fcsr::RoundingMode;
+
+read_csr!(0x002, __read_frm);
+write_csr!(0x002, __write_frm);
+
+/// Reads the CSR
+#[inline]
+pub fn read() -> RoundingMode {
+    RoundingMode::from(unsafe { _read() } as u32)
+}
+
+/// Writes the CSR
+#[inline]
+pub unsafe fn write(frm: RoundingMode) {
+    _write(frm as usize);
+}
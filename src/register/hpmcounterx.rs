@@ -80,3 +80,68 @@ regh!(0xC9C, hpmcounter28h, __read_hpmcounter28h, __write_hpmcounter28h);
 regh!(0xC9D, hpmcounter29h, __read_hpmcounter29h, __write_hpmcounter29h);
 regh!(0xC9E, hpmcounter30h, __read_hpmcounter30h, __write_hpmcounter30h);
 regh!(0xC9F, hpmcounter31h, __read_hpmcounter31h, __write_hpmcounter31h);
+
+macro_rules! indexed {
+    ($($index:expr => $csr:ident),+) => {
+        /// Runtime-indexed access to the performance-monitoring counters
+        pub mod hpmcounter {
+            /// Reads `hpmcounter<index>`
+            ///
+            /// # Panics
+            ///
+            /// Panics if `index` is not in the range 3-31.
+            #[inline]
+            pub fn read(index: usize) -> usize {
+                match index {
+                    $($index => super::$csr::read(),)+
+                    _ => panic!("invalid hpmcounter index {}", index),
+                }
+            }
+
+            /// Reads `hpmcounter<index>` as a 64-bit value
+            ///
+            /// # Panics
+            ///
+            /// Panics if `index` is not in the range 3-31.
+            #[inline]
+            pub fn read64(index: usize) -> u64 {
+                match index {
+                    $($index => super::$csr::read64(),)+
+                    _ => panic!("invalid hpmcounter index {}", index),
+                }
+            }
+        }
+    }
+}
+
+indexed!(
+    3 => hpmcounter3,
+    4 => hpmcounter4,
+    5 => hpmcounter5,
+    6 => hpmcounter6,
+    7 => hpmcounter7,
+    8 => hpmcounter8,
+    9 => hpmcounter9,
+    10 => hpmcounter10,
+    11 => hpmcounter11,
+    12 => hpmcounter12,
+    13 => hpmcounter13,
+    14 => hpmcounter14,
+    15 => hpmcounter15,
+    16 => hpmcounter16,
+    17 => hpmcounter17,
+    18 => hpmcounter18,
+    19 => hpmcounter19,
+    20 => hpmcounter20,
+    21 => hpmcounter21,
+    22 => hpmcounter22,
+    23 => hpmcounter23,
+    24 => hpmcounter24,
+    25 => hpmcounter25,
+    26 => hpmcounter26,
+    27 => hpmcounter27,
+    28 => hpmcounter28,
+    29 => hpmcounter29,
+    30 => hpmcounter30,
+    31 => hpmcounter31
+);
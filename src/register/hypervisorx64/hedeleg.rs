@@ -3,6 +3,7 @@
 use bit_field::BitField;
 
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Hedeleg {
     bits: usize,
 }
@@ -132,6 +133,7 @@ read_csr_as!(Hedeleg, 1538, __read_hedeleg);
 write_csr!(1538, __write_hedeleg);
 set!(1538, __set_hedeleg);
 clear!(1538, __clear_hedeleg);
+modify_csr!(Hedeleg);
 // bit ops
 set_clear_csr!(
     ///Instruction address misaligned
@@ -170,4 +172,42 @@ set_clear_csr!(
     ///Store/AMO page fault 
     , set_ex15, clear_ex15, 1 << 15);
 
+// named bit ops
+set_clear_csr!(
+    ///Instruction address misaligned
+    , set_instruction_misaligned, clear_instruction_misaligned, 1 << 0);
+set_clear_csr!(
+    ///Instruction access fault
+    , set_instruction_fault, clear_instruction_fault, 1 << 1);
+set_clear_csr!(
+    ///Illegal instruction
+    , set_illegal_instruction, clear_illegal_instruction, 1 << 2);
+set_clear_csr!(
+    ///Breakpoint
+    , set_breakpoint, clear_breakpoint, 1 << 3);
+set_clear_csr!(
+    ///Load address misaligned
+    , set_load_misaligned, clear_load_misaligned, 1 << 4);
+set_clear_csr!(
+    ///Load access fault
+    , set_load_fault, clear_load_fault, 1 << 5);
+set_clear_csr!(
+    ///Store/AMO address misaligned
+    , set_store_misaligned, clear_store_misaligned, 1 << 6);
+set_clear_csr!(
+    ///Store/AMO access fault
+    , set_store_fault, clear_store_fault, 1 << 7);
+set_clear_csr!(
+    ///Environment call from U-mode or VU-mode
+    , set_user_env_call, clear_user_env_call, 1 << 8);
+set_clear_csr!(
+    ///Instruction page fault
+    , set_instruction_page_fault, clear_instruction_page_fault, 1 << 12);
+set_clear_csr!(
+    ///Load page fault
+    , set_load_page_fault, clear_load_page_fault, 1 << 13);
+set_clear_csr!(
+    ///Store/AMO page fault
+    , set_store_page_fault, clear_store_page_fault, 1 << 15);
+
 // enums
@@ -0,0 +1,119 @@
+//! Hypervisor Environment Configuration Register.
+
+use bit_field::BitField;
+pub use register::menvcfg::CBIE;
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Henvcfg {
+    bits: usize,
+}
+impl Henvcfg {
+    #[inline]
+    pub fn bits(&self) -> usize {
+        return self.bits;
+    }
+    #[inline]
+    pub fn from_bits(x: usize) -> Self {
+        return Henvcfg { bits: x };
+    }
+    #[inline]
+    pub unsafe fn write(&self) {
+        _write(self.bits);
+    }
+    /// Fence of I/O implies Memory.
+    #[inline]
+    pub fn fiom(&self) -> bool {
+        self.bits.get_bit(0)
+    }
+    #[inline]
+    pub fn set_fiom(&mut self, val: bool) {
+        self.bits.set_bit(0, val);
+    }
+    /// Cache block invalidate instruction enable, or `None` if reserved.
+    #[inline]
+    pub fn cbie(&self) -> Option<CBIE> {
+        match self.bits.get_bits(4..6) {
+            0 => Some(CBIE::IllegalInstruction),
+            1 => Some(CBIE::Flush),
+            3 => Some(CBIE::Invalidate),
+            _ => None,
+        }
+    }
+    #[inline]
+    pub fn set_cbie(&mut self, val: CBIE) {
+        self.bits.set_bits(4..6, val as usize);
+    }
+    /// Cache block clean and flush instruction enable.
+    #[inline]
+    pub fn cbcfe(&self) -> bool {
+        self.bits.get_bit(6)
+    }
+    #[inline]
+    pub fn set_cbcfe(&mut self, val: bool) {
+        self.bits.set_bit(6, val);
+    }
+    /// Cache block zero instruction enable.
+    #[inline]
+    pub fn cbze(&self) -> bool {
+        self.bits.get_bit(7)
+    }
+    #[inline]
+    pub fn set_cbze(&mut self, val: bool) {
+        self.bits.set_bit(7, val);
+    }
+    /// Page-based memory types enable (see `henvcfgh` on RV32).
+    #[inline]
+    #[cfg(target_pointer_width = "64")]
+    pub fn pbmte(&self) -> bool {
+        self.bits.get_bit(62)
+    }
+    #[inline]
+    #[cfg(target_pointer_width = "64")]
+    pub fn set_pbmte(&mut self, val: bool) {
+        self.bits.set_bit(62, val);
+    }
+    /// VS-level timer counter enable (see `henvcfgh` on RV32).
+    #[inline]
+    #[cfg(target_pointer_width = "64")]
+    pub fn stce(&self) -> bool {
+        self.bits.get_bit(63)
+    }
+    #[inline]
+    #[cfg(target_pointer_width = "64")]
+    pub fn set_stce(&mut self, val: bool) {
+        self.bits.set_bit(63, val);
+    }
+}
+/// Sets the cache block invalidate instruction enable.
+#[inline]
+pub unsafe fn set_cbie(cbie: CBIE) {
+    let mut value = _read();
+    value.set_bits(4..6, cbie as usize);
+    _write(value);
+}
+read_csr_as!(Henvcfg, 1546, __read_henvcfg);
+write_csr!(1546, __write_henvcfg);
+set!(1546, __set_henvcfg);
+clear!(1546, __clear_henvcfg);
+modify_csr!(Henvcfg);
+// bit ops
+set_clear_csr!(
+    ///Fence of I/O implies Memory.
+    , set_fiom, clear_fiom, 1 << 0);
+set_clear_csr!(
+    ///Cache block clean and flush instruction enable.
+    , set_cbcfe, clear_cbcfe, 1 << 6);
+set_clear_csr!(
+    ///Cache block zero instruction enable.
+    , set_cbze, clear_cbze, 1 << 7);
+set_clear_csr!(
+    ///Page-based memory types enable (see `henvcfgh` on RV32).
+    #[cfg(target_pointer_width = "64")]
+    , set_pbmte, clear_pbmte, 1 << 62);
+set_clear_csr!(
+    ///VS-level timer counter enable (see `henvcfgh` on RV32).
+    #[cfg(target_pointer_width = "64")]
+    , set_stce, clear_stce, 1 << 63);
+
+// enums
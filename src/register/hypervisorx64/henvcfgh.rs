@@ -0,0 +1,55 @@
+//! Upper 32 bits of Hypervisor Environment Configuration Register, RV32 only.
+
+use bit_field::BitField;
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Henvcfgh {
+    bits: usize,
+}
+impl Henvcfgh {
+    #[inline]
+    pub fn bits(&self) -> usize {
+        return self.bits;
+    }
+    #[inline]
+    pub fn from_bits(x: usize) -> Self {
+        return Henvcfgh { bits: x };
+    }
+    #[inline]
+    pub unsafe fn write(&self) {
+        _write(self.bits);
+    }
+    /// Page-based memory types enable.
+    #[inline]
+    pub fn pbmte(&self) -> bool {
+        self.bits.get_bit(30)
+    }
+    #[inline]
+    pub fn set_pbmte(&mut self, val: bool) {
+        self.bits.set_bit(30, val);
+    }
+    /// VS-level timer counter enable.
+    #[inline]
+    pub fn stce(&self) -> bool {
+        self.bits.get_bit(31)
+    }
+    #[inline]
+    pub fn set_stce(&mut self, val: bool) {
+        self.bits.set_bit(31, val);
+    }
+}
+read_csr_as_rv32!(Henvcfgh, 1562, __read_henvcfgh);
+write_csr_rv32!(1562, __write_henvcfgh);
+set_rv32!(1562, __set_henvcfgh);
+clear_rv32!(1562, __clear_henvcfgh);
+modify_csr!(Henvcfgh);
+// bit ops
+set_clear_csr!(
+    ///Page-based memory types enable.
+    , set_pbmte, clear_pbmte, 1 << 30);
+set_clear_csr!(
+    ///VS-level timer counter enable.
+    , set_stce, clear_stce, 1 << 31);
+
+// enums
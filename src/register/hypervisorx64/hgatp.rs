@@ -1,8 +1,24 @@
 //! Hypervisor Guest Address Translation and Protection Register.
 
+use addr::{FrameWith, PhysicalAddress};
 use bit_field::BitField;
+use core::ops::Range;
+
+#[cfg(target_pointer_width = "32")]
+const MODE: Range<usize> = 31..32;
+#[cfg(target_pointer_width = "32")]
+const VMID: Range<usize> = 22..29;
+#[cfg(target_pointer_width = "32")]
+const PPN: Range<usize> = 0..22;
+#[cfg(target_pointer_width = "64")]
+const MODE: Range<usize> = 60..64;
+#[cfg(target_pointer_width = "64")]
+const VMID: Range<usize> = 44..58;
+#[cfg(target_pointer_width = "64")]
+const PPN: Range<usize> = 0..44;
 
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Hgatp {
     bits: usize,
 }
@@ -22,51 +38,80 @@ impl Hgatp {
     /// Guest address translation mode.
     #[inline]
     pub fn mode(&self) -> HgatpValues {
-        HgatpValues::from(self.bits.get_bits(60..64))
+        HgatpValues::from(self.bits.get_bits(MODE))
     }
     #[inline]
     pub fn set_mode(&mut self, val: HgatpValues) {
-        self.bits.set_bits(60..64, val as usize);
+        self.bits.set_bits(MODE, val as usize);
     }
     /// Virtual machine ID.
     #[inline]
     pub fn vmid(&self) -> usize {
-        self.bits.get_bits(44..58)
+        self.bits.get_bits(VMID)
     }
     #[inline]
     pub fn set_vmid(&mut self, val: usize) {
-        self.bits.set_bits(44..58, val);
+        self.bits.set_bits(VMID, val);
     }
     /// Physical Page Number for root page table.
     #[inline]
     pub fn ppn(&self) -> usize {
-        self.bits.get_bits(0..44)
+        self.bits.get_bits(PPN)
     }
     #[inline]
     pub fn set_ppn(&mut self, val: usize) {
-        self.bits.set_bits(0..44, val);
+        self.bits.set_bits(PPN, val);
+    }
+    /// Root page table frame of the G-stage translation.
+    #[inline]
+    pub fn frame<P: PhysicalAddress>(&self) -> FrameWith<P> {
+        FrameWith::of_ppn(self.ppn())
     }
 }
+/// Sets the G-stage root page table frame, VMID and translation mode.
+///
+/// # Panics
+///
+/// Panics if `frame` is not 16 KiB aligned, as the G-stage root page table
+/// spans four pages.
+#[inline]
+pub unsafe fn set_frame<P: PhysicalAddress>(frame: FrameWith<P>, vmid: usize, mode: HgatpValues) {
+    assert!(
+        frame.number() & 0b11 == 0,
+        "G-stage root page table must be 16 KiB aligned"
+    );
+    let mut hgatp = Hgatp::from_bits(0);
+    hgatp.set_mode(mode);
+    hgatp.set_vmid(vmid);
+    hgatp.set_ppn(frame.number());
+    hgatp.write();
+}
 read_csr_as!(Hgatp, 1664, __read_hgatp);
 write_csr!(1664, __write_hgatp);
 set!(1664, __set_hgatp);
 clear!(1664, __clear_hgatp);
+modify_csr!(Hgatp);
 // bit ops
 
 // enums
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[repr(usize)]
 pub enum HgatpValues {
     Bare = 0,
+    Sv32x4 = 1,
     Sv39x4 = 8,
     Sv48x4 = 9,
+    Sv57x4 = 10,
 }
 impl HgatpValues {
     fn from(x: usize) -> Self {
         match x {
             0 => Self::Bare,
+            1 => Self::Sv32x4,
             8 => Self::Sv39x4,
             9 => Self::Sv48x4,
+            10 => Self::Sv57x4,
             _ => unreachable!(),
         }
     }
@@ -3,6 +3,7 @@
 use bit_field::BitField;
 
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Hideleg {
     bits: usize,
 }
@@ -51,6 +52,7 @@ read_csr_as!(Hideleg, 1539, __read_hideleg);
 write_csr!(1539, __write_hideleg);
 set!(1539, __set_hideleg);
 clear!(1539, __clear_hideleg);
+modify_csr!(Hideleg);
 // bit ops
 set_clear_csr!(
     ///Software Interrupt
@@ -62,4 +64,15 @@ set_clear_csr!(
     ///External Interrupt 
     , set_eip, clear_eip, 1 << 10);
 
+// named bit ops
+set_clear_csr!(
+    ///Virtual Supervisor Software Interrupt
+    , set_vssoft, clear_vssoft, 1 << 2);
+set_clear_csr!(
+    ///Virtual Supervisor Timer Interrupt
+    , set_vstimer, clear_vstimer, 1 << 6);
+set_clear_csr!(
+    ///Virtual Supervisor External Interrupt
+    , set_vsext, clear_vsext, 1 << 10);
+
 // enums
@@ -3,6 +3,7 @@
 use bit_field::BitField;
 
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Hie {
     bits: usize,
 }
@@ -60,6 +61,7 @@ read_csr_as!(Hie, 1540, __read_hie);
 write_csr!(1540, __write_hie);
 set!(1540, __set_hie);
 clear!(1540, __clear_hie);
+modify_csr!(Hie);
 // bit ops
 set_clear_csr!(
     ///Software Interrupt
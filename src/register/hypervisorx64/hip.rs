@@ -3,6 +3,7 @@
 use bit_field::BitField;
 
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Hip {
     bits: usize,
 }
@@ -60,6 +61,7 @@ read_csr_as!(Hip, 1604, __read_hip);
 write_csr!(1604, __write_hip);
 set!(1604, __set_hip);
 clear!(1604, __clear_hip);
+modify_csr!(Hip);
 // bit ops
 set_clear_csr!(
     ///Software Interrupt
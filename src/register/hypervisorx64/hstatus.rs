@@ -3,6 +3,7 @@
 use bit_field::BitField;
 
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Hstatus {
     bits: usize,
 }
@@ -21,10 +22,12 @@ impl Hstatus {
     }
     /// Effective XLEN for VM.
     #[inline]
+    #[cfg(target_pointer_width = "64")]
     pub fn vsxl(&self) -> VsxlValues {
         VsxlValues::from(self.bits.get_bits(32..34))
     }
     #[inline]
+    #[cfg(target_pointer_width = "64")]
     pub fn set_vsxl(&mut self, val: VsxlValues) {
         self.bits.set_bits(32..34, val as usize);
     }
@@ -114,6 +117,7 @@ read_csr_as!(Hstatus, 1536, __read_hstatus);
 write_csr!(1536, __write_hstatus);
 set!(1536, __set_hstatus);
 clear!(1536, __clear_hstatus);
+modify_csr!(Hstatus);
 // bit ops
 set_clear_csr!(
     ///TSR for VM.
@@ -141,13 +145,15 @@ set_clear_csr!(
     , set_vsbe, clear_vsbe, 1 << 5);
 
 // enums
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[repr(usize)]
 pub enum VsxlValues {
     Vsxl32 = 1,
     Vsxl64 = 2,
     Vsxl128 = 3,
 }
+#[cfg(target_pointer_width = "64")]
 impl VsxlValues {
     fn from(x: usize) -> Self {
         match x {
@@ -2,3 +2,17 @@
 read_composite_csr!(super::htimedeltah::read(), read());
 read_csr_as_usize!(1541, __read_htimedelta);
 write_csr_as_usize!(1541, __write_htimedelta);
+
+/// Sets the offset added to `time` when read in VS/VU-mode.
+#[inline]
+pub fn set_guest_time_offset(offset: i64) {
+    match () {
+        #[cfg(target_pointer_width = "32")]
+        () => {
+            super::htimedeltah::write((offset as u64 >> 32) as usize);
+            write(offset as usize);
+        }
+        #[cfg(not(target_pointer_width = "32"))]
+        () => write(offset as usize),
+    }
+}
@@ -1,3 +1,3 @@
-//! Hypervisor Time Delta Register.
-read_csr_as_usize!(1557, __read_htimedeltah);
-write_csr_as_usize!(1557, __write_htimedeltah);
+//! Upper 32 bits of Hypervisor Time Delta Register, RV32 only.
+read_csr_as_usize_rv32!(1557, __read_htimedeltah);
+write_csr_as_usize_rv32!(1557, __write_htimedeltah);
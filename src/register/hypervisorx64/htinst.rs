@@ -1,3 +1,39 @@
 //! Hypervisor Trap Instruction Register.
 read_csr_as_usize!(1610, __read_htinst);
 write_csr_as_usize!(1610, __write_htinst);
+
+/// Decoded htinst value.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TrapInstruction {
+    /// No information is provided about the trapping instruction.
+    None,
+    /// Transformed standard instruction; `compressed` is set if the trapping
+    /// instruction was a 16-bit compressed instruction.
+    Transformed { insn: u32, compressed: bool },
+    /// Custom value or pseudoinstruction for an implicit guest page table access.
+    Pseudo(u32),
+}
+
+impl TrapInstruction {
+    /// Decodes a raw htinst or mtinst value.
+    #[inline]
+    pub fn from_bits(bits: u32) -> Self {
+        if bits == 0 {
+            TrapInstruction::None
+        } else if bits & 1 == 1 {
+            TrapInstruction::Transformed {
+                insn: bits | 0b10,
+                compressed: bits & 0b10 == 0,
+            }
+        } else {
+            TrapInstruction::Pseudo(bits)
+        }
+    }
+}
+
+/// Reads and decodes htinst.
+#[inline]
+pub fn read_instruction() -> TrapInstruction {
+    TrapInstruction::from_bits(read() as u32)
+}
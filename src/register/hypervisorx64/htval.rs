@@ -1,3 +1,14 @@
 //! Hypervisor Trap Value Register.
 read_csr_as_usize!(1603, __read_htval);
 write_csr_as_usize!(1603, __write_htval);
+
+/// Reads the faulting guest physical address
+///
+/// htval holds the guest physical address shifted right by 2 bits; the low
+/// two bits of the address are taken from stval, which must hold the faulting
+/// guest virtual address of the same trap. Guest physical addresses are
+/// wider than XLEN on RV32 (34 bits), hence the `u64`.
+#[inline]
+pub fn read_gpa() -> u64 {
+    ((read() as u64) << 2) | (::register::stval::read() as u64 & 0b11)
+}
@@ -3,6 +3,7 @@
 use bit_field::BitField;
 
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Hvip {
     bits: usize,
 }
@@ -51,6 +52,7 @@ read_csr_as!(Hvip, 1605, __read_hvip);
 write_csr!(1605, __write_hvip);
 set!(1605, __set_hvip);
 clear!(1605, __clear_hvip);
+modify_csr!(Hvip);
 // bit ops
 set_clear_csr!(
     ///Software Interrupt
@@ -1,5 +1,10 @@
+/// Width of the CSRs in bits
+const XLEN: usize = core::mem::size_of::<usize>() * 8;
+
 pub mod hcounteren;
 pub mod hedeleg;
+pub mod henvcfg;
+pub mod henvcfgh;
 pub mod hgatp;
 pub mod hgeie;
 pub mod hgeip;
@@ -1,8 +1,23 @@
 //! Virtual Supervisor Guest Address Translation and Protection Register.
 
 use bit_field::BitField;
+use core::ops::Range;
+
+#[cfg(target_pointer_width = "32")]
+const MODE: Range<usize> = 31..32;
+#[cfg(target_pointer_width = "32")]
+const ASID: Range<usize> = 22..31;
+#[cfg(target_pointer_width = "32")]
+const PPN: Range<usize> = 0..22;
+#[cfg(target_pointer_width = "64")]
+const MODE: Range<usize> = 60..64;
+#[cfg(target_pointer_width = "64")]
+const ASID: Range<usize> = 44..60;
+#[cfg(target_pointer_width = "64")]
+const PPN: Range<usize> = 0..44;
 
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Vsatp {
     bits: usize,
 }
@@ -19,54 +34,60 @@ impl Vsatp {
     pub unsafe fn write(&self) {
         _write(self.bits);
     }
-    /// Guest address translation mode.
+    /// Guest virtual address translation mode.
     #[inline]
-    pub fn mode(&self) -> HgatpValues {
-        HgatpValues::from(self.bits.get_bits(60..64))
+    pub fn mode(&self) -> VsatpValues {
+        VsatpValues::from(self.bits.get_bits(MODE))
     }
     #[inline]
-    pub fn set_mode(&mut self, val: HgatpValues) {
-        self.bits.set_bits(60..64, val as usize);
+    pub fn set_mode(&mut self, val: VsatpValues) {
+        self.bits.set_bits(MODE, val as usize);
     }
     /// ASID.
     #[inline]
     pub fn asid(&self) -> usize {
-        self.bits.get_bits(44..60)
+        self.bits.get_bits(ASID)
     }
     #[inline]
     pub fn set_asid(&mut self, val: usize) {
-        self.bits.set_bits(44..60, val);
+        self.bits.set_bits(ASID, val);
     }
     /// Physical Page Number for root page table.
     #[inline]
     pub fn ppn(&self) -> usize {
-        self.bits.get_bits(0..44)
+        self.bits.get_bits(PPN)
     }
     #[inline]
     pub fn set_ppn(&mut self, val: usize) {
-        self.bits.set_bits(0..44, val);
+        self.bits.set_bits(PPN, val);
     }
 }
 read_csr_as!(Vsatp, 640, __read_vsatp);
 write_csr!(640, __write_vsatp);
 set!(640, __set_vsatp);
 clear!(640, __clear_vsatp);
+modify_csr!(Vsatp);
 // bit ops
 
 // enums
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[repr(usize)]
-pub enum HgatpValues {
+pub enum VsatpValues {
     Bare = 0,
-    Sv39x4 = 8,
-    Sv48x4 = 9,
+    Sv32 = 1,
+    Sv39 = 8,
+    Sv48 = 9,
+    Sv57 = 10,
 }
-impl HgatpValues {
+impl VsatpValues {
     fn from(x: usize) -> Self {
         match x {
             0 => Self::Bare,
-            8 => Self::Sv39x4,
-            9 => Self::Sv48x4,
+            1 => Self::Sv32,
+            8 => Self::Sv39,
+            9 => Self::Sv48,
+            10 => Self::Sv57,
             _ => unreachable!(),
         }
     }
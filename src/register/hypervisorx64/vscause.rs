@@ -1,8 +1,11 @@
 //! Virtual Supervisor Cause Register.
 
+use super::XLEN;
 use bit_field::BitField;
+pub use register::scause::{Exception, Interrupt, Trap};
 
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Vscause {
     bits: usize,
 }
@@ -22,29 +25,35 @@ impl Vscause {
     /// Is cause interrupt.
     #[inline]
     pub fn interrupt(&self) -> bool {
-        self.bits.get_bit(63)
+        self.bits.get_bit(XLEN - 1)
     }
     #[inline]
     pub fn set_interrupt(&mut self, val: bool) {
-        self.bits.set_bit(63, val);
+        self.bits.set_bit(XLEN - 1, val);
     }
     /// Exception code
     #[inline]
     pub fn code(&self) -> usize {
-        self.bits.get_bits(0..63)
+        self.bits.get_bits(0..XLEN - 1)
     }
     #[inline]
     pub fn set_code(&mut self, val: usize) {
-        self.bits.set_bits(0..63, val);
+        self.bits.set_bits(0..XLEN - 1, val);
+    }
+    /// Trap cause.
+    #[inline]
+    pub fn cause(&self) -> Trap {
+        Trap::from_code(self.interrupt(), self.code())
     }
 }
 read_csr_as!(Vscause, 578, __read_vscause);
 write_csr!(578, __write_vscause);
 set!(578, __set_vscause);
 clear!(578, __clear_vscause);
+modify_csr!(Vscause);
 // bit ops
 set_clear_csr!(
     ///Is cause interrupt.
-    , set_interrupt, clear_interrupt, 1 << 63);
+    , set_interrupt, clear_interrupt, 1 << (XLEN - 1));
 
 // enums
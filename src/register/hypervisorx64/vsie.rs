@@ -3,6 +3,7 @@
 use bit_field::BitField;
 
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Vsie {
     bits: usize,
 }
@@ -51,6 +52,7 @@ read_csr_as!(Vsie, 516, __read_vsie);
 write_csr!(516, __write_vsie);
 set!(516, __set_vsie);
 clear!(516, __clear_vsie);
+modify_csr!(Vsie);
 // bit ops
 set_clear_csr!(
     ///Software Interrupt
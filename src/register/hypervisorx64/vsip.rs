@@ -3,6 +3,7 @@
 use bit_field::BitField;
 
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Vsip {
     bits: usize,
 }
@@ -51,6 +52,7 @@ read_csr_as!(Vsip, 580, __read_vsip);
 write_csr!(580, __write_vsip);
 set!(580, __set_vsip);
 clear!(580, __clear_vsip);
+modify_csr!(Vsip);
 // bit ops
 set_clear_csr!(
     ///Software Interrupt
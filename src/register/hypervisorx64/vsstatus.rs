@@ -1,8 +1,11 @@
-//! Hypervisor Guest External Interrupt Pending Register.
+//! Virtual Supervisor Status Register.
 
+use super::XLEN;
 use bit_field::BitField;
+pub use register::sstatus::{FS, SPP};
 
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Vsstatus {
     bits: usize,
 }
@@ -19,25 +22,27 @@ impl Vsstatus {
     pub unsafe fn write(&self) {
         _write(self.bits);
     }
-    ///
+    /// Whether either the FS field or XS field signals the presence of some dirty state.
     #[inline]
-    pub fn sd(&self) -> usize {
-        self.bits.get_bits(60..64)
+    pub fn sd(&self) -> bool {
+        self.bits.get_bit(XLEN - 1)
     }
     #[inline]
-    pub fn set_sd(&mut self, val: usize) {
-        self.bits.set_bits(60..64, val);
+    pub fn set_sd(&mut self, val: bool) {
+        self.bits.set_bit(XLEN - 1, val);
     }
     /// Effective User XLEN.
     #[inline]
+    #[cfg(target_pointer_width = "64")]
     pub fn uxl(&self) -> UxlValues {
         UxlValues::from(self.bits.get_bits(32..34))
     }
     #[inline]
+    #[cfg(target_pointer_width = "64")]
     pub fn set_uxl(&mut self, val: UxlValues) {
         self.bits.set_bits(32..34, val as usize);
     }
-    ///
+    /// Make eXecutable Readable.
     #[inline]
     pub fn mxr(&self) -> bool {
         self.bits.get_bit(19)
@@ -46,7 +51,7 @@ impl Vsstatus {
     pub fn set_mxr(&mut self, val: bool) {
         self.bits.set_bit(19, val);
     }
-    ///
+    /// Permit Supervisor User Memory access.
     #[inline]
     pub fn sum(&self) -> bool {
         self.bits.get_bit(18)
@@ -55,34 +60,49 @@ impl Vsstatus {
     pub fn set_sum(&mut self, val: bool) {
         self.bits.set_bit(18, val);
     }
-    ///
+    /// Status of additional user-mode extensions and associated state.
     #[inline]
-    pub fn xs(&self) -> usize {
-        self.bits.get_bits(15..17)
+    pub fn xs(&self) -> FS {
+        match self.bits.get_bits(15..17) {
+            0 => FS::Off,
+            1 => FS::Initial,
+            2 => FS::Clean,
+            3 => FS::Dirty,
+            _ => unreachable!(),
+        }
     }
     #[inline]
-    pub fn set_xs(&mut self, val: usize) {
-        self.bits.set_bits(15..17, val);
+    pub fn set_xs(&mut self, val: FS) {
+        self.bits.set_bits(15..17, val as usize);
     }
-    ///
+    /// Status of the floating-point unit.
     #[inline]
-    pub fn fs(&self) -> usize {
-        self.bits.get_bits(13..15)
+    pub fn fs(&self) -> FS {
+        match self.bits.get_bits(13..15) {
+            0 => FS::Off,
+            1 => FS::Initial,
+            2 => FS::Clean,
+            3 => FS::Dirty,
+            _ => unreachable!(),
+        }
     }
     #[inline]
-    pub fn set_fs(&mut self, val: usize) {
-        self.bits.set_bits(13..15, val);
+    pub fn set_fs(&mut self, val: FS) {
+        self.bits.set_bits(13..15, val as usize);
     }
-    ///
+    /// Supervisor Previous Privilege mode.
     #[inline]
-    pub fn spp(&self) -> bool {
-        self.bits.get_bit(8)
+    pub fn spp(&self) -> SPP {
+        match self.bits.get_bit(8) {
+            true => SPP::Supervisor,
+            false => SPP::User,
+        }
     }
     #[inline]
-    pub fn set_spp(&mut self, val: bool) {
-        self.bits.set_bit(8, val);
+    pub fn set_spp(&mut self, val: SPP) {
+        self.bits.set_bit(8, val == SPP::Supervisor);
     }
-    ///
+    /// VU-mode access endianness.
     #[inline]
     pub fn ube(&self) -> bool {
         self.bits.get_bit(6)
@@ -91,7 +111,7 @@ impl Vsstatus {
     pub fn set_ube(&mut self, val: bool) {
         self.bits.set_bit(6, val);
     }
-    ///
+    /// Supervisor Previous Interrupt Enable.
     #[inline]
     pub fn spie(&self) -> bool {
         self.bits.get_bit(5)
@@ -100,7 +120,7 @@ impl Vsstatus {
     pub fn set_spie(&mut self, val: bool) {
         self.bits.set_bit(5, val);
     }
-    ///
+    /// Supervisor Interrupt Enable.
     #[inline]
     pub fn sie(&self) -> bool {
         self.bits.get_bit(1)
@@ -114,34 +134,37 @@ read_csr_as!(Vsstatus, 512, __read_vsstatus);
 write_csr!(512, __write_vsstatus);
 set!(512, __set_vsstatus);
 clear!(512, __clear_vsstatus);
+modify_csr!(Vsstatus);
 // bit ops
 set_clear_csr!(
-    ///
+    ///Make eXecutable Readable.
     , set_mxr, clear_mxr, 1 << 19);
 set_clear_csr!(
-    ///
+    ///Permit Supervisor User Memory access.
     , set_sum, clear_sum, 1 << 18);
 set_clear_csr!(
-    ///
+    ///Supervisor Previous Privilege mode.
     , set_spp, clear_spp, 1 << 8);
 set_clear_csr!(
-    ///
+    ///VU-mode access endianness.
     , set_ube, clear_ube, 1 << 6);
 set_clear_csr!(
-    ///
+    ///Supervisor Previous Interrupt Enable.
     , set_spie, clear_spie, 1 << 5);
 set_clear_csr!(
-    ///
+    ///Supervisor Interrupt Enable.
     , set_sie, clear_sie, 1 << 1);
 
 // enums
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[repr(usize)]
 pub enum UxlValues {
     Uxl32 = 1,
     Uxl64 = 2,
     Uxl128 = 3,
 }
+#[cfg(target_pointer_width = "64")]
 impl UxlValues {
     fn from(x: usize) -> Self {
         match x {
@@ -1,8 +1,18 @@
 //! Virtual Supervisor Trap Vector Base Address Register.
 
+use super::XLEN;
 use bit_field::BitField;
 
+/// Trap mode of `vstvec`, which has no CLIC mode
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TrapMode {
+    Direct = 0,
+    Vectored = 1,
+}
+
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Vstvec {
     bits: usize,
 }
@@ -19,16 +29,16 @@ impl Vstvec {
     pub unsafe fn write(&self) {
         _write(self.bits);
     }
-    ///
+    /// Trap vector base address, shifted right by 2 bits.
     #[inline]
     pub fn base(&self) -> usize {
-        self.bits.get_bits(2..64)
+        self.bits.get_bits(2..XLEN)
     }
     #[inline]
     pub fn set_base(&mut self, val: usize) {
-        self.bits.set_bits(2..64, val);
+        self.bits.set_bits(2..XLEN, val);
     }
-    ///
+    /// Raw trap vector mode.
     #[inline]
     pub fn mode(&self) -> usize {
         self.bits.get_bits(0..2)
@@ -37,11 +47,31 @@ impl Vstvec {
     pub fn set_mode(&mut self, val: usize) {
         self.bits.set_bits(0..2, val);
     }
+    /// Trap vector base address.
+    #[inline]
+    pub fn address(&self) -> usize {
+        self.bits & !0b11
+    }
+    /// Trap vector mode, or `None` if reserved.
+    #[inline]
+    pub fn trap_mode(&self) -> Option<TrapMode> {
+        match self.mode() {
+            0 => Some(TrapMode::Direct),
+            1 => Some(TrapMode::Vectored),
+            _ => None,
+        }
+    }
+}
+/// Sets the trap vector base address and mode.
+#[inline]
+pub unsafe fn set(addr: usize, mode: TrapMode) {
+    _write(addr + mode as usize);
 }
 read_csr_as!(Vstvec, 517, __read_vstvec);
 write_csr!(517, __write_vstvec);
 set!(517, __set_vstvec);
 clear!(517, __clear_vstvec);
+modify_csr!(Vstvec);
 // bit ops
 
 // enums
@@ -0,0 +1,4 @@
+//! instret register
+
+read_csr_as_usize!(0xC02, __read_instret);
+read_composite_csr!(super::instreth::read(), read());
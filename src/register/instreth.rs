@@ -0,0 +1,3 @@
+//! instreth register
+
+read_csr_as_usize_rv32!(0xC82, __read_instreth);
@@ -20,7 +20,10 @@ macro_rules! read_csr {
                     $asm_fn()
                 }
 
-                #[cfg(not(riscv))]
+                #[cfg(all(not(riscv), feature = "emulation"))]
+                () => $crate::register::emulation::read($csr_number),
+
+                #[cfg(all(not(riscv), not(feature = "emulation")))]
                 () => unimplemented!(),
             }
         }
@@ -70,6 +73,20 @@ macro_rules! read_csr_as {
     };
 }
 
+macro_rules! read_csr_as_rv32 {
+    ($register:ident, $csr_number:expr, $asm_fn: ident) => {
+        read_csr_rv32!($csr_number, $asm_fn);
+
+        /// Reads the CSR
+        #[inline]
+        pub fn read() -> $register {
+            $register {
+                bits: unsafe { _read() },
+            }
+        }
+    };
+}
+
 macro_rules! read_csr_as_usize {
     ($csr_number:expr, $asm_fn: ident) => {
         read_csr!($csr_number, $asm_fn);
@@ -113,7 +130,10 @@ macro_rules! write_csr {
                     $asm_fn(bits);
                 }
 
-                #[cfg(not(riscv))]
+                #[cfg(all(not(riscv), feature = "emulation"))]
+                () => $crate::register::emulation::write($csr_number, bits),
+
+                #[cfg(all(not(riscv), not(feature = "emulation")))]
                 () => unimplemented!(),
             }
         }
@@ -189,7 +209,12 @@ macro_rules! set {
                     $asm_fn(bits);
                 }
 
-                #[cfg(not(riscv))]
+                #[cfg(all(not(riscv), feature = "emulation"))]
+                () => {
+                    $crate::register::emulation::read_set($csr_number, bits);
+                }
+
+                #[cfg(all(not(riscv), not(feature = "emulation")))]
                 () => unimplemented!(),
             }
         }
@@ -215,13 +240,228 @@ macro_rules! clear {
                     $asm_fn(bits);
                 }
 
-                #[cfg(not(riscv))]
+                #[cfg(all(not(riscv), feature = "emulation"))]
+                () => {
+                    $crate::register::emulation::read_clear($csr_number, bits);
+                }
+
+                #[cfg(all(not(riscv), not(feature = "emulation")))]
+                () => unimplemented!(),
+            }
+        }
+    };
+}
+
+macro_rules! set_rv32 {
+    ($csr_number:expr, $asm_fn: ident) => {
+        /// Set the CSR
+        #[inline]
+        #[allow(unused_variables)]
+        unsafe fn _set(bits: usize) {
+            match () {
+                #[cfg(all(riscv32, feature = "inline-asm"))]
+                () => core::arch::asm!("csrrs x0, {1}, {0}", in(reg) bits, const $csr_number),
+
+                #[cfg(all(riscv32, not(feature = "inline-asm")))]
+                () => {
+                    extern "C" {
+                        fn $asm_fn(bits: usize);
+                    }
+
+                    $asm_fn(bits);
+                }
+
+                #[cfg(not(riscv32))]
+                () => unimplemented!(),
+            }
+        }
+    };
+}
+
+macro_rules! clear_rv32 {
+    ($csr_number:expr, $asm_fn: ident) => {
+        /// Clear the CSR
+        #[inline]
+        #[allow(unused_variables)]
+        unsafe fn _clear(bits: usize) {
+            match () {
+                #[cfg(all(riscv32, feature = "inline-asm"))]
+                () => core::arch::asm!("csrrc x0, {1}, {0}", in(reg) bits, const $csr_number),
+
+                #[cfg(all(riscv32, not(feature = "inline-asm")))]
+                () => {
+                    extern "C" {
+                        fn $asm_fn(bits: usize);
+                    }
+
+                    $asm_fn(bits);
+                }
+
+                #[cfg(not(riscv32))]
                 () => unimplemented!(),
             }
         }
     };
 }
 
+macro_rules! swap {
+    ($csr_number:expr, $asm_fn: ident) => {
+        /// Atomically swaps the CSR, returning its previous value
+        #[inline]
+        #[allow(unused_variables)]
+        unsafe fn _swap(bits: usize) -> usize {
+            match () {
+                #[cfg(all(riscv, feature = "inline-asm"))]
+                () => {
+                    let r: usize;
+                    core::arch::asm!("csrrw {0}, {2}, {1}", out(reg) r, in(reg) bits, const $csr_number);
+                    r
+                }
+
+                #[cfg(all(riscv, not(feature = "inline-asm")))]
+                () => {
+                    extern "C" {
+                        fn $asm_fn(bits: usize) -> usize;
+                    }
+
+                    $asm_fn(bits)
+                }
+
+                #[cfg(all(not(riscv), feature = "emulation"))]
+                () => $crate::register::emulation::swap($csr_number, bits),
+
+                #[cfg(all(not(riscv), not(feature = "emulation")))]
+                () => unimplemented!(),
+            }
+        }
+    };
+}
+
+macro_rules! read_set {
+    ($csr_number:expr, $asm_fn: ident) => {
+        /// Atomically sets bits in the CSR, returning its previous value
+        #[inline]
+        #[allow(unused_variables)]
+        unsafe fn _read_set(bits: usize) -> usize {
+            match () {
+                #[cfg(all(riscv, feature = "inline-asm"))]
+                () => {
+                    let r: usize;
+                    core::arch::asm!("csrrs {0}, {2}, {1}", out(reg) r, in(reg) bits, const $csr_number);
+                    r
+                }
+
+                #[cfg(all(riscv, not(feature = "inline-asm")))]
+                () => {
+                    extern "C" {
+                        fn $asm_fn(bits: usize) -> usize;
+                    }
+
+                    $asm_fn(bits)
+                }
+
+                #[cfg(all(not(riscv), feature = "emulation"))]
+                () => $crate::register::emulation::read_set($csr_number, bits),
+
+                #[cfg(all(not(riscv), not(feature = "emulation")))]
+                () => unimplemented!(),
+            }
+        }
+    };
+}
+
+macro_rules! read_clear {
+    ($csr_number:expr, $asm_fn: ident) => {
+        /// Atomically clears bits in the CSR, returning its previous value
+        #[inline]
+        #[allow(unused_variables)]
+        unsafe fn _read_clear(bits: usize) -> usize {
+            match () {
+                #[cfg(all(riscv, feature = "inline-asm"))]
+                () => {
+                    let r: usize;
+                    core::arch::asm!("csrrc {0}, {2}, {1}", out(reg) r, in(reg) bits, const $csr_number);
+                    r
+                }
+
+                #[cfg(all(riscv, not(feature = "inline-asm")))]
+                () => {
+                    extern "C" {
+                        fn $asm_fn(bits: usize) -> usize;
+                    }
+
+                    $asm_fn(bits)
+                }
+
+                #[cfg(all(not(riscv), feature = "emulation"))]
+                () => $crate::register::emulation::read_clear($csr_number, bits),
+
+                #[cfg(all(not(riscv), not(feature = "emulation")))]
+                () => unimplemented!(),
+            }
+        }
+    };
+}
+
+macro_rules! swap_csr_as_usize {
+    ($csr_number:expr, $asm_fn: ident) => {
+        swap!($csr_number, $asm_fn);
+
+        /// Atomically swaps the CSR, returning its previous value
+        #[inline]
+        pub fn swap(bits: usize) -> usize {
+            unsafe { _swap(bits) }
+        }
+    };
+}
+
+macro_rules! swap_csr_as {
+    ($register:ident, $csr_number:expr, $asm_fn: ident) => {
+        swap!($csr_number, $asm_fn);
+
+        /// Atomically swaps the CSR, returning its previous value
+        #[inline]
+        pub unsafe fn swap(bits: usize) -> $register {
+            $register { bits: _swap(bits) }
+        }
+    };
+}
+
+macro_rules! read_set_clear_csr_as {
+    ($register:ident, $csr_number:expr, $set_fn: ident, $clear_fn: ident) => {
+        read_set!($csr_number, $set_fn);
+        read_clear!($csr_number, $clear_fn);
+
+        /// Atomically sets `bits` in the CSR, returning its previous value
+        #[inline]
+        pub unsafe fn read_and_set_bits(bits: usize) -> $register {
+            $register {
+                bits: _read_set(bits),
+            }
+        }
+
+        /// Atomically clears `bits` in the CSR, returning its previous value
+        #[inline]
+        pub unsafe fn read_and_clear_bits(bits: usize) -> $register {
+            $register {
+                bits: _read_clear(bits),
+            }
+        }
+    };
+}
+
+macro_rules! modify_csr {
+    ($register:ident) => {
+        /// Reads the CSR, lets `f` modify the value and writes it back
+        #[inline]
+        pub unsafe fn modify<F: FnOnce(&mut $register)>(f: F) {
+            let mut register = read();
+            f(&mut register);
+            _write(register.bits);
+        }
+    };
+}
+
 macro_rules! set_csr {
     ($(#[$attr:meta])*, $set_field:ident, $e:expr) => {
         $(#[$attr])*
@@ -4,6 +4,7 @@ use core::num::NonZeroUsize;
 
 /// marchid register
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Marchid {
     bits: NonZeroUsize,
 }
@@ -15,7 +16,7 @@ impl Marchid {
     }
 }
 
-read_csr!(0xF11, __read_marchid);
+read_csr!(0xF12, __read_marchid);
 
 /// Reads the CSR
 #[inline]
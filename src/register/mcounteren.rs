@@ -0,0 +1,71 @@
+//! mcounteren register
+
+use bit_field::BitField;
+
+/// mcounteren register
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Mcounteren {
+    bits: usize,
+}
+
+impl Mcounteren {
+    /// Returns the contents of the register as raw bits
+    #[inline]
+    pub fn bits(&self) -> usize {
+        self.bits
+    }
+
+    /// Supervisor "cycle\[h\]" Enable
+    #[inline]
+    pub fn cy(&self) -> bool {
+        self.bits.get_bit(0)
+    }
+
+    /// Supervisor "time\[h\]" Enable
+    #[inline]
+    pub fn tm(&self) -> bool {
+        self.bits.get_bit(1)
+    }
+
+    /// Supervisor "instret\[h\]" Enable
+    #[inline]
+    pub fn ir(&self) -> bool {
+        self.bits.get_bit(2)
+    }
+
+    /// Supervisor "hpm\[x\]" Enable (bits 3-31)
+    #[inline]
+    pub fn hpm(&self, index: usize) -> bool {
+        assert!((3..32).contains(&index));
+        self.bits.get_bit(index)
+    }
+}
+
+read_csr_as!(Mcounteren, 0x306, __read_mcounteren);
+set!(0x306, __set_mcounteren);
+clear!(0x306, __clear_mcounteren);
+
+set_clear_csr!(
+    /// Supervisor "cycle\[h\]" Enable
+    , set_cy, clear_cy, 1 << 0);
+set_clear_csr!(
+    /// Supervisor "time\[h\]" Enable
+    , set_tm, clear_tm, 1 << 1);
+set_clear_csr!(
+    /// Supervisor "instret\[h\]" Enable
+    , set_ir, clear_ir, 1 << 2);
+
+/// Supervisor "hpm\[x\]" Enable (bits 3-31)
+#[inline]
+pub unsafe fn set_hpm(index: usize) {
+    assert!((3..32).contains(&index));
+    _set(1 << index);
+}
+
+/// Supervisor "hpm\[x\]" Disable (bits 3-31)
+#[inline]
+pub unsafe fn clear_hpm(index: usize) {
+    assert!((3..32).contains(&index));
+    _clear(1 << index);
+}
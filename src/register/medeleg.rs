@@ -2,8 +2,11 @@
 
 use bit_field::BitField;
 
+pub use trap::Exception;
+
 /// medeleg register
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Medeleg {
     bits: usize,
 }
@@ -15,6 +18,15 @@ impl Medeleg {
         self.bits
     }
 
+    /// Returns whether `exception` is delegated (`false` for `Exception::Unknown`)
+    #[inline]
+    pub fn is_delegated(&self, exception: Exception) -> bool {
+        match exception.to_code() {
+            Some(code) => self.bits.get_bit(code),
+            None => false,
+        }
+    }
+
     /// Instruction Address Misaligned Delegate
     #[inline]
     pub fn instruction_misaligned(&self) -> bool {
@@ -75,6 +87,12 @@ impl Medeleg {
         self.bits.get_bit(9)
     }
 
+    /// Environment Call from VS-mode Delegate
+    #[inline]
+    pub fn virtual_supervisor_env_call(&self) -> bool {
+        self.bits.get_bit(10)
+    }
+
     /// Environment Call from M-mode Delegate
     #[inline]
     pub fn machine_env_call(&self) -> bool {
@@ -98,12 +116,52 @@ impl Medeleg {
     pub fn store_page_fault(&self) -> bool {
         self.bits.get_bit(15)
     }
+
+    /// Instruction Guest-Page Fault Delegate
+    #[inline]
+    pub fn instruction_guest_page_fault(&self) -> bool {
+        self.bits.get_bit(20)
+    }
+
+    /// Load Guest-Page Fault Delegate
+    #[inline]
+    pub fn load_guest_page_fault(&self) -> bool {
+        self.bits.get_bit(21)
+    }
+
+    /// Virtual Instruction Delegate
+    #[inline]
+    pub fn virtual_instruction(&self) -> bool {
+        self.bits.get_bit(22)
+    }
+
+    /// Store/AMO Guest-Page Fault Delegate
+    #[inline]
+    pub fn store_guest_page_fault(&self) -> bool {
+        self.bits.get_bit(23)
+    }
 }
 
 read_csr_as!(Medeleg, 0x302, __read_medeleg);
 set!(0x302, __set_medeleg);
 clear!(0x302, __clear_medeleg);
 
+/// Delegates `exception` (ignored for `Exception::Unknown`)
+#[inline]
+pub unsafe fn delegate(exception: Exception) {
+    if let Some(code) = exception.to_code() {
+        _set(1 << code);
+    }
+}
+
+/// Stops delegating `exception` (ignored for `Exception::Unknown`)
+#[inline]
+pub unsafe fn undelegate(exception: Exception) {
+    if let Some(code) = exception.to_code() {
+        _clear(1 << code);
+    }
+}
+
 set_clear_csr!(
     /// Instruction Address Misaligned Delegate
     , set_instruction_misaligned, clear_instruction_misaligned, 1 << 0);
@@ -134,6 +192,9 @@ set_clear_csr!(
 set_clear_csr!(
     /// Environment Call from S-mode Delegate
     , set_supervisor_env_call, clear_supervisor_env_call, 1 << 9);
+set_clear_csr!(
+    /// Environment Call from VS-mode Delegate
+    , set_virtual_supervisor_env_call, clear_virtual_supervisor_env_call, 1 << 10);
 set_clear_csr!(
     /// Environment Call from M-mode Delegate
     , set_machine_env_call, clear_machine_env_call, 1 << 11);
@@ -146,3 +207,15 @@ set_clear_csr!(
 set_clear_csr!(
     /// Store/AMO Page Fault Delegate
     , set_store_page_fault, clear_store_page_fault, 1 << 15);
+set_clear_csr!(
+    /// Instruction Guest-Page Fault Delegate
+    , set_instruction_guest_page_fault, clear_instruction_guest_page_fault, 1 << 20);
+set_clear_csr!(
+    /// Load Guest-Page Fault Delegate
+    , set_load_guest_page_fault, clear_load_guest_page_fault, 1 << 21);
+set_clear_csr!(
+    /// Virtual Instruction Delegate
+    , set_virtual_instruction, clear_virtual_instruction, 1 << 22);
+set_clear_csr!(
+    /// Store/AMO Guest-Page Fault Delegate
+    , set_store_guest_page_fault, clear_store_guest_page_fault, 1 << 23);
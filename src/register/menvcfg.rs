@@ -0,0 +1,122 @@
+//! menvcfg register
+
+use bit_field::BitField;
+
+/// Machine environment configuration register
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Menvcfg {
+    bits: usize,
+}
+
+/// Cache block invalidate instruction enable
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CBIE {
+    /// `cbo.inval` raises an illegal instruction exception
+    IllegalInstruction = 0b00,
+    /// `cbo.inval` performs a flush operation
+    Flush = 0b01,
+    /// `cbo.inval` performs an invalidate operation
+    Invalidate = 0b11,
+}
+
+impl CBIE {
+    #[inline]
+    pub(crate) fn from(bits: usize) -> Option<CBIE> {
+        match bits {
+            0b00 => Some(CBIE::IllegalInstruction),
+            0b01 => Some(CBIE::Flush),
+            0b11 => Some(CBIE::Invalidate),
+            _ => None,
+        }
+    }
+}
+
+impl Menvcfg {
+    /// Returns the contents of the register as raw bits
+    #[inline]
+    pub fn bits(&self) -> usize {
+        self.bits
+    }
+
+    /// Fence of I/O implies Memory
+    #[inline]
+    pub fn fiom(&self) -> bool {
+        self.bits.get_bit(0)
+    }
+
+    /// Cache block invalidate instruction enable, or `None` if reserved
+    #[inline]
+    pub fn cbie(&self) -> Option<CBIE> {
+        CBIE::from(self.bits.get_bits(4..6))
+    }
+
+    /// Cache block clean and flush instruction enable
+    #[inline]
+    pub fn cbcfe(&self) -> bool {
+        self.bits.get_bit(6)
+    }
+
+    /// Cache block zero instruction enable
+    #[inline]
+    pub fn cbze(&self) -> bool {
+        self.bits.get_bit(7)
+    }
+
+    /// Double trap enable for S-mode (see `menvcfgh` on RV32)
+    #[inline]
+    #[cfg(target_pointer_width = "64")]
+    pub fn dte(&self) -> bool {
+        self.bits.get_bit(59)
+    }
+
+    /// Page-based memory types enable (see `menvcfgh` on RV32)
+    #[inline]
+    #[cfg(target_pointer_width = "64")]
+    pub fn pbmte(&self) -> bool {
+        self.bits.get_bit(62)
+    }
+
+    /// Supervisor timer counter enable (see `menvcfgh` on RV32)
+    #[inline]
+    #[cfg(target_pointer_width = "64")]
+    pub fn stce(&self) -> bool {
+        self.bits.get_bit(63)
+    }
+}
+
+read_csr_as!(Menvcfg, 0x30A, __read_menvcfg);
+write_csr!(0x30A, __write_menvcfg);
+set!(0x30A, __set_menvcfg);
+clear!(0x30A, __clear_menvcfg);
+
+set_clear_csr!(
+    /// Fence of I/O implies Memory
+    , set_fiom, clear_fiom, 1 << 0);
+set_clear_csr!(
+    /// Cache block clean and flush instruction enable
+    , set_cbcfe, clear_cbcfe, 1 << 6);
+set_clear_csr!(
+    /// Cache block zero instruction enable
+    , set_cbze, clear_cbze, 1 << 7);
+set_clear_csr!(
+    /// Double trap enable for S-mode
+    #[cfg(target_pointer_width = "64")]
+    , set_dte, clear_dte, 1 << 59);
+set_clear_csr!(
+    /// Page-based memory types enable
+    #[cfg(target_pointer_width = "64")]
+    , set_pbmte, clear_pbmte, 1 << 62);
+set_clear_csr!(
+    /// Supervisor timer counter enable
+    #[cfg(target_pointer_width = "64")]
+    , set_stce, clear_stce, 1 << 63);
+
+/// Cache block invalidate instruction enable
+#[inline]
+pub unsafe fn set_cbie(cbie: CBIE) {
+    let mut value = _read();
+    value.set_bits(4..6, cbie as usize);
+    _write(value);
+}
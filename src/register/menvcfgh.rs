@@ -0,0 +1,51 @@
+//! menvcfgh register
+
+use bit_field::BitField;
+
+/// Upper 32 bits of machine environment configuration register (RV32 only)
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Menvcfgh {
+    bits: usize,
+}
+
+impl Menvcfgh {
+    /// Returns the contents of the register as raw bits
+    #[inline]
+    pub fn bits(&self) -> usize {
+        self.bits
+    }
+
+    /// Double trap enable for S-mode
+    #[inline]
+    pub fn dte(&self) -> bool {
+        self.bits.get_bit(27)
+    }
+
+    /// Page-based memory types enable
+    #[inline]
+    pub fn pbmte(&self) -> bool {
+        self.bits.get_bit(30)
+    }
+
+    /// Supervisor timer counter enable
+    #[inline]
+    pub fn stce(&self) -> bool {
+        self.bits.get_bit(31)
+    }
+}
+
+read_csr_as_rv32!(Menvcfgh, 0x31A, __read_menvcfgh);
+write_csr_rv32!(0x31A, __write_menvcfgh);
+set_rv32!(0x31A, __set_menvcfgh);
+clear_rv32!(0x31A, __clear_menvcfgh);
+
+set_clear_csr!(
+    /// Double trap enable for S-mode
+    , set_dte, clear_dte, 1 << 27);
+set_clear_csr!(
+    /// Page-based memory types enable
+    , set_pbmte, clear_pbmte, 1 << 30);
+set_clear_csr!(
+    /// Supervisor timer counter enable
+    , set_stce, clear_stce, 1 << 31);
@@ -1,3 +1,26 @@
 //! mhartid register
 
 read_csr_as_usize!(0xf14, __read_mhartid);
+
+/// Stores the hart id in the `tp` register as the hart index
+///
+/// `mhartid` is only accessible from M-mode, so firmware conventionally hands the
+/// hart id over to S-mode software in `tp` before dropping privilege. This is
+/// `sync::init_hart` with the hart id as the index, see the `sync` module docs
+/// for how `tp` is used.
+///
+/// # Safety
+///
+/// Overwrites `tp`, see [`asm::write_tp`](crate::asm::write_tp).
+#[inline]
+pub unsafe fn save_to_tp() {
+    crate::sync::init_hart(read());
+}
+
+/// Returns the hart id previously stored in `tp` by [`save_to_tp`]
+///
+/// This is the hart index returned by `sync::hart_index`.
+#[inline]
+pub fn read_from_tp() -> usize {
+    crate::sync::hart_index()
+}
@@ -82,3 +82,81 @@ regh!(0xB9C, mhpmcounter28h, __read_mhpmcounter28h, __write_mhpmcounter28h);
 regh!(0xB9D, mhpmcounter29h, __read_mhpmcounter29h, __write_mhpmcounter29h);
 regh!(0xB9E, mhpmcounter30h, __read_mhpmcounter30h, __write_mhpmcounter30h);
 regh!(0xB9F, mhpmcounter31h, __read_mhpmcounter31h, __write_mhpmcounter31h);
+
+macro_rules! indexed {
+    ($($index:expr => $csr:ident),+) => {
+        /// Runtime-indexed access to the machine performance-monitoring counters
+        pub mod mhpmcounter {
+            /// Reads `mhpmcounter<index>`
+            ///
+            /// # Panics
+            ///
+            /// Panics if `index` is not in the range 3-31.
+            #[inline]
+            pub fn read(index: usize) -> usize {
+                match index {
+                    $($index => super::$csr::read(),)+
+                    _ => panic!("invalid mhpmcounter index {}", index),
+                }
+            }
+
+            /// Reads `mhpmcounter<index>` as a 64-bit value
+            ///
+            /// # Panics
+            ///
+            /// Panics if `index` is not in the range 3-31.
+            #[inline]
+            pub fn read64(index: usize) -> u64 {
+                match index {
+                    $($index => super::$csr::read64(),)+
+                    _ => panic!("invalid mhpmcounter index {}", index),
+                }
+            }
+
+            /// Writes `mhpmcounter<index>`
+            ///
+            /// # Panics
+            ///
+            /// Panics if `index` is not in the range 3-31.
+            #[inline]
+            pub fn write(index: usize, bits: usize) {
+                match index {
+                    $($index => super::$csr::write(bits),)+
+                    _ => panic!("invalid mhpmcounter index {}", index),
+                }
+            }
+        }
+    }
+}
+
+indexed!(
+    3 => mhpmcounter3,
+    4 => mhpmcounter4,
+    5 => mhpmcounter5,
+    6 => mhpmcounter6,
+    7 => mhpmcounter7,
+    8 => mhpmcounter8,
+    9 => mhpmcounter9,
+    10 => mhpmcounter10,
+    11 => mhpmcounter11,
+    12 => mhpmcounter12,
+    13 => mhpmcounter13,
+    14 => mhpmcounter14,
+    15 => mhpmcounter15,
+    16 => mhpmcounter16,
+    17 => mhpmcounter17,
+    18 => mhpmcounter18,
+    19 => mhpmcounter19,
+    20 => mhpmcounter20,
+    21 => mhpmcounter21,
+    22 => mhpmcounter22,
+    23 => mhpmcounter23,
+    24 => mhpmcounter24,
+    25 => mhpmcounter25,
+    26 => mhpmcounter26,
+    27 => mhpmcounter27,
+    28 => mhpmcounter28,
+    29 => mhpmcounter29,
+    30 => mhpmcounter30,
+    31 => mhpmcounter31
+);
@@ -1,41 +1,347 @@
 macro_rules! reg {
     (
-        $addr:expr, $csr:ident, $readf:ident, $writef:ident
+        $addr:expr, $csr:ident, $csrh:ident, $readf:ident, $writef:ident
     ) => {
         /// Machine performance-monitoring event selector
         pub mod $csr {
+            use super::mhpmevent::PerfEvent;
+
             read_csr_as_usize!($addr, $readf);
             write_csr_as_usize!($addr, $writef);
+
+            /// Reads the selected event
+            #[inline]
+            pub fn read_event() -> PerfEvent {
+                match () {
+                    #[cfg(riscv32)]
+                    () => {
+                        PerfEvent::from_bits(((super::$csrh::read() as u64) << 32) | read() as u64)
+                    }
+
+                    #[cfg(not(riscv32))]
+                    () => PerfEvent::from_bits(read() as u64),
+                }
+            }
+
+            /// Selects the event to be counted
+            #[inline]
+            pub fn write_event(event: PerfEvent) {
+                match () {
+                    #[cfg(riscv32)]
+                    () => {
+                        super::$csrh::write((event.bits() >> 32) as usize);
+                        write(event.bits() as usize);
+                    }
+
+                    #[cfg(not(riscv32))]
+                    () => write(event.bits() as usize),
+                }
+            }
+        }
+    };
+}
+
+macro_rules! regh {
+    (
+        $addr:expr, $csrh:ident, $readf:ident, $writef:ident
+    ) => {
+        /// Upper 32 bits of machine performance-monitoring event selector (RV32 only)
+        pub mod $csrh {
+            read_csr_as_usize_rv32!($addr, $readf);
+            write_csr_as_usize_rv32!($addr, $writef);
         }
     };
 }
 
-reg!(0x323, mhpmevent3, __read_mhpmevent3, __write_mhpmevent3);
-reg!(0x324, mhpmevent4, __read_mhpmevent4, __write_mhpmevent4);
-reg!(0x325, mhpmevent5, __read_mhpmevent5, __write_mhpmevent5);
-reg!(0x326, mhpmevent6, __read_mhpmevent6, __write_mhpmevent6);
-reg!(0x327, mhpmevent7, __read_mhpmevent7, __write_mhpmevent7);
-reg!(0x328, mhpmevent8, __read_mhpmevent8, __write_mhpmevent8);
-reg!(0x329, mhpmevent9, __read_mhpmevent9, __write_mhpmevent9);
-reg!(0x32A, mhpmevent10, __read_mhpmevent10, __write_mhpmevent10);
-reg!(0x32B, mhpmevent11, __read_mhpmevent11, __write_mhpmevent11);
-reg!(0x32C, mhpmevent12, __read_mhpmevent12, __write_mhpmevent12);
-reg!(0x32D, mhpmevent13, __read_mhpmevent13, __write_mhpmevent13);
-reg!(0x32E, mhpmevent14, __read_mhpmevent14, __write_mhpmevent14);
-reg!(0x32F, mhpmevent15, __read_mhpmevent15, __write_mhpmevent15);
-reg!(0x330, mhpmevent16, __read_mhpmevent16, __write_mhpmevent16);
-reg!(0x331, mhpmevent17, __read_mhpmevent17, __write_mhpmevent17);
-reg!(0x332, mhpmevent18, __read_mhpmevent18, __write_mhpmevent18);
-reg!(0x333, mhpmevent19, __read_mhpmevent19, __write_mhpmevent19);
-reg!(0x334, mhpmevent20, __read_mhpmevent20, __write_mhpmevent20);
-reg!(0x335, mhpmevent21, __read_mhpmevent21, __write_mhpmevent21);
-reg!(0x336, mhpmevent22, __read_mhpmevent22, __write_mhpmevent22);
-reg!(0x337, mhpmevent23, __read_mhpmevent23, __write_mhpmevent23);
-reg!(0x338, mhpmevent24, __read_mhpmevent24, __write_mhpmevent24);
-reg!(0x339, mhpmevent25, __read_mhpmevent25, __write_mhpmevent25);
-reg!(0x33A, mhpmevent26, __read_mhpmevent26, __write_mhpmevent26);
-reg!(0x33B, mhpmevent27, __read_mhpmevent27, __write_mhpmevent27);
-reg!(0x33C, mhpmevent28, __read_mhpmevent28, __write_mhpmevent28);
-reg!(0x33D, mhpmevent29, __read_mhpmevent29, __write_mhpmevent29);
-reg!(0x33E, mhpmevent30, __read_mhpmevent30, __write_mhpmevent30);
-reg!(0x33F, mhpmevent31, __read_mhpmevent31, __write_mhpmevent31);
+macro_rules! indexed {
+    ($($index:expr => $csr:ident),+) => {
+        /// Reads `mhpmevent<index>`
+        ///
+        /// # Panics
+        ///
+        /// Panics if `index` is not in the range 3-31.
+        #[inline]
+        pub fn read(index: usize) -> usize {
+            match index {
+                $($index => super::$csr::read(),)+
+                _ => panic!("invalid mhpmevent index {}", index),
+            }
+        }
+
+        /// Writes `mhpmevent<index>`
+        ///
+        /// # Panics
+        ///
+        /// Panics if `index` is not in the range 3-31.
+        #[inline]
+        pub fn write(index: usize, bits: usize) {
+            match index {
+                $($index => super::$csr::write(bits),)+
+                _ => panic!("invalid mhpmevent index {}", index),
+            }
+        }
+
+        /// Reads the event selected by `mhpmevent<index>`
+        ///
+        /// # Panics
+        ///
+        /// Panics if `index` is not in the range 3-31.
+        #[inline]
+        pub fn read_event(index: usize) -> PerfEvent {
+            match index {
+                $($index => super::$csr::read_event(),)+
+                _ => panic!("invalid mhpmevent index {}", index),
+            }
+        }
+
+        /// Selects the event counted by `mhpmcounter<index>`
+        ///
+        /// # Panics
+        ///
+        /// Panics if `index` is not in the range 3-31.
+        #[inline]
+        pub fn write_event(index: usize, event: PerfEvent) {
+            match index {
+                $($index => super::$csr::write_event(event),)+
+                _ => panic!("invalid mhpmevent index {}", index),
+            }
+        }
+
+        /// Clears the OF bit of `mhpmevent<index>`, re-arming its overflow interrupt
+        ///
+        /// # Panics
+        ///
+        /// Panics if `index` is not in the range 3-31.
+        #[inline]
+        pub fn clear_overflow(index: usize) {
+            write_event(index, read_event(index).with_of(false));
+        }
+
+        /// Sets or clears the MINH bit of `mhpmevent<index>`
+        ///
+        /// # Panics
+        ///
+        /// Panics if `index` is not in the range 3-31.
+        #[inline]
+        pub fn set_minh(index: usize, val: bool) {
+            write_event(index, read_event(index).with_minh(val));
+        }
+    };
+}
+
+reg!(0x323, mhpmevent3, mhpmevent3h, __read_mhpmevent3, __write_mhpmevent3);
+reg!(0x324, mhpmevent4, mhpmevent4h, __read_mhpmevent4, __write_mhpmevent4);
+reg!(0x325, mhpmevent5, mhpmevent5h, __read_mhpmevent5, __write_mhpmevent5);
+reg!(0x326, mhpmevent6, mhpmevent6h, __read_mhpmevent6, __write_mhpmevent6);
+reg!(0x327, mhpmevent7, mhpmevent7h, __read_mhpmevent7, __write_mhpmevent7);
+reg!(0x328, mhpmevent8, mhpmevent8h, __read_mhpmevent8, __write_mhpmevent8);
+reg!(0x329, mhpmevent9, mhpmevent9h, __read_mhpmevent9, __write_mhpmevent9);
+reg!(0x32A, mhpmevent10, mhpmevent10h, __read_mhpmevent10, __write_mhpmevent10);
+reg!(0x32B, mhpmevent11, mhpmevent11h, __read_mhpmevent11, __write_mhpmevent11);
+reg!(0x32C, mhpmevent12, mhpmevent12h, __read_mhpmevent12, __write_mhpmevent12);
+reg!(0x32D, mhpmevent13, mhpmevent13h, __read_mhpmevent13, __write_mhpmevent13);
+reg!(0x32E, mhpmevent14, mhpmevent14h, __read_mhpmevent14, __write_mhpmevent14);
+reg!(0x32F, mhpmevent15, mhpmevent15h, __read_mhpmevent15, __write_mhpmevent15);
+reg!(0x330, mhpmevent16, mhpmevent16h, __read_mhpmevent16, __write_mhpmevent16);
+reg!(0x331, mhpmevent17, mhpmevent17h, __read_mhpmevent17, __write_mhpmevent17);
+reg!(0x332, mhpmevent18, mhpmevent18h, __read_mhpmevent18, __write_mhpmevent18);
+reg!(0x333, mhpmevent19, mhpmevent19h, __read_mhpmevent19, __write_mhpmevent19);
+reg!(0x334, mhpmevent20, mhpmevent20h, __read_mhpmevent20, __write_mhpmevent20);
+reg!(0x335, mhpmevent21, mhpmevent21h, __read_mhpmevent21, __write_mhpmevent21);
+reg!(0x336, mhpmevent22, mhpmevent22h, __read_mhpmevent22, __write_mhpmevent22);
+reg!(0x337, mhpmevent23, mhpmevent23h, __read_mhpmevent23, __write_mhpmevent23);
+reg!(0x338, mhpmevent24, mhpmevent24h, __read_mhpmevent24, __write_mhpmevent24);
+reg!(0x339, mhpmevent25, mhpmevent25h, __read_mhpmevent25, __write_mhpmevent25);
+reg!(0x33A, mhpmevent26, mhpmevent26h, __read_mhpmevent26, __write_mhpmevent26);
+reg!(0x33B, mhpmevent27, mhpmevent27h, __read_mhpmevent27, __write_mhpmevent27);
+reg!(0x33C, mhpmevent28, mhpmevent28h, __read_mhpmevent28, __write_mhpmevent28);
+reg!(0x33D, mhpmevent29, mhpmevent29h, __read_mhpmevent29, __write_mhpmevent29);
+reg!(0x33E, mhpmevent30, mhpmevent30h, __read_mhpmevent30, __write_mhpmevent30);
+reg!(0x33F, mhpmevent31, mhpmevent31h, __read_mhpmevent31, __write_mhpmevent31);
+
+regh!(0x723, mhpmevent3h, __read_mhpmevent3h, __write_mhpmevent3h);
+regh!(0x724, mhpmevent4h, __read_mhpmevent4h, __write_mhpmevent4h);
+regh!(0x725, mhpmevent5h, __read_mhpmevent5h, __write_mhpmevent5h);
+regh!(0x726, mhpmevent6h, __read_mhpmevent6h, __write_mhpmevent6h);
+regh!(0x727, mhpmevent7h, __read_mhpmevent7h, __write_mhpmevent7h);
+regh!(0x728, mhpmevent8h, __read_mhpmevent8h, __write_mhpmevent8h);
+regh!(0x729, mhpmevent9h, __read_mhpmevent9h, __write_mhpmevent9h);
+regh!(0x72A, mhpmevent10h, __read_mhpmevent10h, __write_mhpmevent10h);
+regh!(0x72B, mhpmevent11h, __read_mhpmevent11h, __write_mhpmevent11h);
+regh!(0x72C, mhpmevent12h, __read_mhpmevent12h, __write_mhpmevent12h);
+regh!(0x72D, mhpmevent13h, __read_mhpmevent13h, __write_mhpmevent13h);
+regh!(0x72E, mhpmevent14h, __read_mhpmevent14h, __write_mhpmevent14h);
+regh!(0x72F, mhpmevent15h, __read_mhpmevent15h, __write_mhpmevent15h);
+regh!(0x730, mhpmevent16h, __read_mhpmevent16h, __write_mhpmevent16h);
+regh!(0x731, mhpmevent17h, __read_mhpmevent17h, __write_mhpmevent17h);
+regh!(0x732, mhpmevent18h, __read_mhpmevent18h, __write_mhpmevent18h);
+regh!(0x733, mhpmevent19h, __read_mhpmevent19h, __write_mhpmevent19h);
+regh!(0x734, mhpmevent20h, __read_mhpmevent20h, __write_mhpmevent20h);
+regh!(0x735, mhpmevent21h, __read_mhpmevent21h, __write_mhpmevent21h);
+regh!(0x736, mhpmevent22h, __read_mhpmevent22h, __write_mhpmevent22h);
+regh!(0x737, mhpmevent23h, __read_mhpmevent23h, __write_mhpmevent23h);
+regh!(0x738, mhpmevent24h, __read_mhpmevent24h, __write_mhpmevent24h);
+regh!(0x739, mhpmevent25h, __read_mhpmevent25h, __write_mhpmevent25h);
+regh!(0x73A, mhpmevent26h, __read_mhpmevent26h, __write_mhpmevent26h);
+regh!(0x73B, mhpmevent27h, __read_mhpmevent27h, __write_mhpmevent27h);
+regh!(0x73C, mhpmevent28h, __read_mhpmevent28h, __write_mhpmevent28h);
+regh!(0x73D, mhpmevent29h, __read_mhpmevent29h, __write_mhpmevent29h);
+regh!(0x73E, mhpmevent30h, __read_mhpmevent30h, __write_mhpmevent30h);
+regh!(0x73F, mhpmevent31h, __read_mhpmevent31h, __write_mhpmevent31h);
+
+/// Runtime-indexed access to the machine performance-monitoring event selectors
+pub mod mhpmevent {
+    use bit_field::BitField;
+
+    /// Hardware performance-monitoring event selection
+    ///
+    /// Holds the implementation-defined event code along with the privilege-mode
+    /// filtering and overflow bits defined by the Sscofpmf extension.
+    #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+    pub struct PerfEvent {
+        bits: u64,
+    }
+
+    impl PerfEvent {
+        /// Creates an event selection from a raw, implementation-defined event code
+        ///
+        /// Only the low 56 bits of `event` are used.
+        #[inline]
+        pub fn new(event: u64) -> Self {
+            PerfEvent {
+                bits: event.get_bits(0..56),
+            }
+        }
+
+        /// Creates an event selection from the full 64-bit `mhpmevent` value
+        #[inline]
+        pub fn from_bits(bits: u64) -> Self {
+            PerfEvent { bits }
+        }
+
+        /// Returns the full 64-bit `mhpmevent` value
+        #[inline]
+        pub fn bits(&self) -> u64 {
+            self.bits
+        }
+
+        /// Implementation-defined event code
+        #[inline]
+        pub fn event(&self) -> u64 {
+            self.bits.get_bits(0..56)
+        }
+
+        /// Overflow status and interrupt disable (OF)
+        #[inline]
+        pub fn of(&self) -> bool {
+            self.bits.get_bit(63)
+        }
+
+        /// Inhibit counting in M-mode (MINH)
+        #[inline]
+        pub fn minh(&self) -> bool {
+            self.bits.get_bit(62)
+        }
+
+        /// Inhibit counting in S-mode (SINH)
+        #[inline]
+        pub fn sinh(&self) -> bool {
+            self.bits.get_bit(61)
+        }
+
+        /// Inhibit counting in U-mode (UINH)
+        #[inline]
+        pub fn uinh(&self) -> bool {
+            self.bits.get_bit(60)
+        }
+
+        /// Inhibit counting in VS-mode (VSINH)
+        #[inline]
+        pub fn vsinh(&self) -> bool {
+            self.bits.get_bit(59)
+        }
+
+        /// Inhibit counting in VU-mode (VUINH)
+        #[inline]
+        pub fn vuinh(&self) -> bool {
+            self.bits.get_bit(58)
+        }
+
+        /// Sets the overflow status and interrupt disable bit (OF)
+        ///
+        /// The counter only raises an overflow interrupt while this bit is clear.
+        #[inline]
+        pub fn with_of(mut self, val: bool) -> Self {
+            self.bits.set_bit(63, val);
+            self
+        }
+
+        /// Sets the inhibit counting in M-mode bit (MINH)
+        #[inline]
+        pub fn with_minh(mut self, val: bool) -> Self {
+            self.bits.set_bit(62, val);
+            self
+        }
+
+        /// Sets the inhibit counting in S-mode bit (SINH)
+        #[inline]
+        pub fn with_sinh(mut self, val: bool) -> Self {
+            self.bits.set_bit(61, val);
+            self
+        }
+
+        /// Sets the inhibit counting in U-mode bit (UINH)
+        #[inline]
+        pub fn with_uinh(mut self, val: bool) -> Self {
+            self.bits.set_bit(60, val);
+            self
+        }
+
+        /// Sets the inhibit counting in VS-mode bit (VSINH)
+        #[inline]
+        pub fn with_vsinh(mut self, val: bool) -> Self {
+            self.bits.set_bit(59, val);
+            self
+        }
+
+        /// Sets the inhibit counting in VU-mode bit (VUINH)
+        #[inline]
+        pub fn with_vuinh(mut self, val: bool) -> Self {
+            self.bits.set_bit(58, val);
+            self
+        }
+    }
+
+    indexed!(
+        3 => mhpmevent3,
+        4 => mhpmevent4,
+        5 => mhpmevent5,
+        6 => mhpmevent6,
+        7 => mhpmevent7,
+        8 => mhpmevent8,
+        9 => mhpmevent9,
+        10 => mhpmevent10,
+        11 => mhpmevent11,
+        12 => mhpmevent12,
+        13 => mhpmevent13,
+        14 => mhpmevent14,
+        15 => mhpmevent15,
+        16 => mhpmevent16,
+        17 => mhpmevent17,
+        18 => mhpmevent18,
+        19 => mhpmevent19,
+        20 => mhpmevent20,
+        21 => mhpmevent21,
+        22 => mhpmevent22,
+        23 => mhpmevent23,
+        24 => mhpmevent24,
+        25 => mhpmevent25,
+        26 => mhpmevent26,
+        27 => mhpmevent27,
+        28 => mhpmevent28,
+        29 => mhpmevent29,
+        30 => mhpmevent30,
+        31 => mhpmevent31
+    );
+}
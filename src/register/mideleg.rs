@@ -2,8 +2,11 @@
 
 use bit_field::BitField;
 
+pub use trap::Interrupt;
+
 /// mideleg register
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Mideleg {
     bits: usize,
 }
@@ -15,6 +18,15 @@ impl Mideleg {
         self.bits
     }
 
+    /// Returns whether `interrupt` is delegated (`false` for `Interrupt::Unknown`)
+    #[inline]
+    pub fn is_delegated(&self, interrupt: Interrupt) -> bool {
+        match interrupt.to_code() {
+            Some(code) => self.bits.get_bit(code),
+            None => false,
+        }
+    }
+
     /// User Software Interrupt Delegate
     #[inline]
     pub fn usoft(&self) -> bool {
@@ -56,6 +68,22 @@ read_csr_as!(Mideleg, 0x303, __read_mideleg);
 set!(0x303, __set_mideleg);
 clear!(0x303, __clear_mideleg);
 
+/// Delegates `interrupt` (ignored for `Interrupt::Unknown`)
+#[inline]
+pub unsafe fn delegate(interrupt: Interrupt) {
+    if let Some(code) = interrupt.to_code() {
+        _set(1 << code);
+    }
+}
+
+/// Stops delegating `interrupt` (ignored for `Interrupt::Unknown`)
+#[inline]
+pub unsafe fn undelegate(interrupt: Interrupt) {
+    if let Some(code) = interrupt.to_code() {
+        _clear(1 << code);
+    }
+}
+
 set_clear_csr!(
     /// User Software Interrupt Delegate
     , set_usoft, clear_usoft, 1 << 0);
@@ -4,6 +4,7 @@ use bit_field::BitField;
 
 /// mie register
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Mie {
     bits: usize,
 }
@@ -73,6 +74,8 @@ impl Mie {
 read_csr_as!(Mie, 0x304, __read_mie);
 set!(0x304, __set_mie);
 clear!(0x304, __clear_mie);
+swap_csr_as!(Mie, 0x304, __swap_mie);
+read_set_clear_csr_as!(Mie, 0x304, __read_set_mie, __read_clear_mie);
 
 set_clear_csr!(
     /// User Software Interrupt Enable
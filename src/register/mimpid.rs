@@ -4,6 +4,7 @@ use core::num::NonZeroUsize;
 
 /// mimpid register
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Mimpid {
     bits: NonZeroUsize,
 }
@@ -15,7 +16,7 @@ impl Mimpid {
     }
 }
 
-read_csr!(0xF11, __read_mimpid);
+read_csr!(0xF13, __read_mimpid);
 
 /// Reads the CSR
 #[inline]
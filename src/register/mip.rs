@@ -4,6 +4,7 @@ use bit_field::BitField;
 
 /// mip register
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Mip {
     bits: usize,
 }
@@ -73,6 +74,8 @@ impl Mip {
 read_csr_as!(Mip, 0x344, __read_mip);
 set!(0x344, __set_mip);
 clear!(0x344, __clear_mip);
+swap_csr_as!(Mip, 0x344, __swap_mip);
+read_set_clear_csr_as!(Mip, 0x344, __read_set_mip, __read_clear_mip);
 
 set_clear_csr!(
     /// User Software Interrupt Pending
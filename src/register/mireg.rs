@@ -0,0 +1,22 @@
+//! mireg register
+
+read_csr_as_usize!(0x351, __read_mireg);
+write_csr_as_usize!(0x351, __write_mireg);
+
+/// Reads the indirectly accessed register selected by `select`
+///
+/// Writes `miselect`, so it must not race with other users of the window.
+#[inline]
+pub fn read_indirect(select: usize) -> usize {
+    super::miselect::write(select);
+    read()
+}
+
+/// Writes the indirectly accessed register selected by `select`
+///
+/// Writes `miselect`, so it must not race with other users of the window.
+#[inline]
+pub fn write_indirect(select: usize, bits: usize) {
+    super::miselect::write(select);
+    write(bits);
+}
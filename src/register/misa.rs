@@ -1,15 +1,18 @@
 //! misa register
 
+use bit_field::BitField;
 use core::num::NonZeroUsize;
 
 /// misa register
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Misa {
     bits: NonZeroUsize,
 }
 
 /// Machine XLEN
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum MXL {
     XLEN32,
     XLEN64,
@@ -38,13 +41,47 @@ impl Misa {
         }
     }
 
-    /// Returns true when the atomic extension is implemented.
+    /// Returns true when the given extension is implemented.
+    ///
+    /// `extension` is the single-letter name of the extension, e.g. `'A'` or `'c'`.
     pub fn has_extension(&self, extension: char) -> bool {
-        let bit = extension as u8 - 65;
-        if bit > 25 {
-            return false;
+        match extension.to_ascii_uppercase() {
+            c @ 'A'..='Z' => self.bits().get_bit(c as usize - 'A' as usize),
+            _ => false,
         }
-        self.bits() & (1 << bit) == (1 << bit)
+    }
+
+    /// Returns an iterator over the letters of the implemented extensions
+    pub fn extensions(&self) -> Extensions {
+        Extensions {
+            bits: self.bits().get_bits(0..26),
+            next: 0,
+        }
+    }
+}
+
+/// Iterator over the extensions implemented according to `misa`
+///
+/// Yields the single-letter extension names in alphabetical order.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Extensions {
+    bits: usize,
+    next: u8,
+}
+
+impl Iterator for Extensions {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        while self.next < 26 {
+            let bit = self.next;
+            self.next += 1;
+            if self.bits.get_bit(bit as usize) {
+                return Some((b'A' + bit) as char);
+            }
+        }
+        None
     }
 }
 
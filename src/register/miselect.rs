@@ -0,0 +1,4 @@
+//! miselect register
+
+read_csr_as_usize!(0x350, __read_miselect);
+write_csr_as_usize!(0x350, __write_miselect);
@@ -0,0 +1,34 @@
+//! mncause register
+
+use bit_field::BitField;
+use core::mem::size_of;
+
+/// Resumable NMI cause register
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Mncause {
+    bits: usize,
+}
+
+impl Mncause {
+    /// Returns the contents of the register as raw bits
+    #[inline]
+    pub fn bits(&self) -> usize {
+        self.bits
+    }
+
+    /// Implementation-defined NMI cause code
+    #[inline]
+    pub fn code(&self) -> usize {
+        self.bits.get_bits(0..size_of::<usize>() * 8 - 1)
+    }
+
+    /// Is the NMI an interrupt (always set by hardware)
+    #[inline]
+    pub fn is_interrupt(&self) -> bool {
+        self.bits.get_bit(size_of::<usize>() * 8 - 1)
+    }
+}
+
+read_csr_as!(Mncause, 0x742, __read_mncause);
+write_csr!(0x742, __write_mncause);
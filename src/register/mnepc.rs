@@ -0,0 +1,4 @@
+//! mnepc register
+
+read_csr_as_usize!(0x741, __read_mnepc);
+write_csr_as_usize!(0x741, __write_mnepc);
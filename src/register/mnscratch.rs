@@ -0,0 +1,5 @@
+//! mnscratch register
+
+read_csr_as_usize!(0x740, __read_mnscratch);
+write_csr_as_usize!(0x740, __write_mnscratch);
+swap_csr_as_usize!(0x740, __swap_mnscratch);
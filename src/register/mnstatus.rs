@@ -0,0 +1,65 @@
+//! mnstatus register
+
+pub use super::mstatus::MPP;
+use bit_field::BitField;
+
+/// Resumable NMI status register
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Mnstatus {
+    bits: usize,
+}
+
+impl Mnstatus {
+    /// Returns the contents of the register as raw bits
+    #[inline]
+    pub fn bits(&self) -> usize {
+        self.bits
+    }
+
+    /// Non-maskable interrupts are enabled
+    #[inline]
+    pub fn nmie(&self) -> bool {
+        self.bits.get_bit(3)
+    }
+
+    /// Virtualization mode at the time of the NMI
+    #[inline]
+    pub fn mnpv(&self) -> bool {
+        self.bits.get_bit(7)
+    }
+
+    /// Privilege mode at the time of the NMI
+    #[inline]
+    pub fn mnpp(&self) -> MPP {
+        match self.bits.get_bits(11..13) {
+            0b00 => MPP::User,
+            0b01 => MPP::Supervisor,
+            0b11 => MPP::Machine,
+            _ => unreachable!(),
+        }
+    }
+}
+
+read_csr_as!(Mnstatus, 0x744, __read_mnstatus);
+write_csr!(0x744, __write_mnstatus);
+set!(0x744, __set_mnstatus);
+clear!(0x744, __clear_mnstatus);
+
+set_csr!(
+    /// Non-maskable interrupts are enabled
+    ///
+    /// Software can set but not clear NMIE; it is cleared by hardware on NMI entry.
+    , set_nmie, 1 << 3);
+
+set_clear_csr!(
+    /// Virtualization mode to return to on `mnret`
+    , set_mnpv, clear_mnpv, 1 << 7);
+
+/// Privilege mode to return to on `mnret`
+#[inline]
+pub unsafe fn set_mnpp(mnpp: MPP) {
+    let mut value = _read();
+    value.set_bits(11..13, mnpp as usize);
+    _write(value);
+}
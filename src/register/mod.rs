@@ -9,40 +9,83 @@
 //! - mcycleh
 //! - minstreth
 //! - mhpmcounter[3-31]h
+//! - mhpmevent[3-31]h
+//! - pmpcfg1, pmpcfg3, ..., pmpcfg15
+//! - mstatush
+//! - menvcfgh
+//! - stimecmph
+//! - henvcfgh
+//! - htimedeltah
 
 #[macro_use]
 mod macros;
 
-// User Trap Setup
+// Host-side CSR emulation
+#[cfg(all(not(riscv), feature = "emulation"))]
+pub mod emulation;
+
+// Generic CSR access
+#[cfg(any(feature = "inline-asm", not(riscv)))]
+pub mod csr;
+
+// User Trap Setup (N extension)
+#[cfg(feature = "n-extension")]
 pub mod uie;
+#[cfg(feature = "n-extension")]
 pub mod ustatus;
+#[cfg(feature = "n-extension")]
 pub mod utvec;
 
-// User Trap Handling
+// User Trap Handling (N extension)
+#[cfg(feature = "n-extension")]
 pub mod ucause;
+#[cfg(feature = "n-extension")]
 pub mod uepc;
+#[cfg(feature = "n-extension")]
 pub mod uip;
+#[cfg(feature = "n-extension")]
 pub mod uscratch;
+#[cfg(feature = "n-extension")]
 pub mod utval;
 
 // User Floating-Point CSRs
-// TODO: frm, fflags
 pub mod fcsr;
+pub mod fflags;
+pub mod frm;
+
+// User Vector CSRs
+pub mod vcsr;
+pub mod vl;
+pub mod vlenb;
+pub mod vstart;
+pub mod vtype;
+pub mod vxrm;
+pub mod vxsat;
+
+// Unprivileged Entropy Source
+pub mod seed;
 
 // User Counter/Timers
-// TODO: cycle[h], instret[h]
+pub mod cycle;
+pub mod instret;
 pub mod time;
 #[rustfmt::skip] // long macro use
 mod hpmcounterx;
 pub use self::hpmcounterx::*;
+pub mod cycleh;
+pub mod instreth;
 pub mod timeh;
 
 // Supervisor Trap Setup
 // TODO: sedeleg, sideleg
+pub mod scounteren;
 pub mod sie;
 pub mod sstatus;
 pub mod stvec;
-// TODO: scounteren
+pub mod stvt;
+
+// Supervisor Configuration
+pub mod senvcfg;
 
 // Supervisor Trap Handling
 pub mod scause;
@@ -51,6 +94,19 @@ pub mod sip;
 pub mod sscratch;
 pub mod stval;
 
+// Supervisor Advanced Interrupt Architecture
+pub mod sireg;
+pub mod siselect;
+pub mod stopei;
+pub mod stopi;
+
+// Supervisor Count Overflow
+pub mod scountovf;
+
+// Supervisor Timer Compare
+pub mod stimecmp;
+pub mod stimecmph;
+
 // Supervisor Protection and Translation
 pub mod satp;
 
@@ -61,24 +117,47 @@ pub mod mimpid;
 pub mod mvendorid;
 
 // Machine Trap Setup
+pub mod mcounteren;
 pub mod medeleg;
 pub mod mideleg;
 pub mod mie;
 pub mod misa;
 pub mod mstatus;
+pub mod mstatush;
 pub mod mtvec;
-// TODO: mcounteren
+pub mod mtvt;
 
 // Machine Trap Handling
 pub mod mcause;
 pub mod mepc;
 pub mod mip;
 pub mod mscratch;
+pub mod mtinst;
 pub mod mtval;
+pub mod mtval2;
+
+// Machine Resumable NMI
+pub mod mncause;
+pub mod mnepc;
+pub mod mnscratch;
+pub mod mnstatus;
+
+// Machine Advanced Interrupt Architecture
+pub mod mireg;
+pub mod miselect;
+pub mod mtopei;
+pub mod mtopi;
+
+// Machine Configuration
+pub mod menvcfg;
+pub mod menvcfgh;
 
 // Machine Protection and Translation
+pub mod pmp;
+#[rustfmt::skip] // long macro use
 mod pmpcfgx;
 pub use self::pmpcfgx::*;
+#[rustfmt::skip] // long macro use
 mod pmpaddrx;
 pub use self::pmpaddrx::*;
 
@@ -92,12 +171,24 @@ pub mod mcycleh;
 pub mod minstreth;
 
 // Machine Counter Setup
+#[rustfmt::skip] // long macro use
 mod mhpmeventx;
 pub use self::mhpmeventx::*;
 
-// TODO: Debug/Trace Registers (shared with Debug Mode)
-
-// TODO: Debug Mode Registers
+// Debug/Trace Registers (shared with Debug Mode)
+pub mod tcontrol;
+pub mod tdata1;
+pub mod tdata2;
+pub mod tdata3;
+pub mod tinfo;
+pub mod trigger;
+pub mod tselect;
+
+// Debug Mode Registers
+pub mod dcsr;
+pub mod dpc;
+pub mod dscratch0;
+pub mod dscratch1;
 
 // Hypervisor Extension Registers
 mod hypervisorx64;
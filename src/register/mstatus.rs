@@ -1,17 +1,18 @@
 //! mstatus register
-// TODO: Virtualization, Memory Privilege and Extension Context Fields
 
 use bit_field::BitField;
 use core::mem::size_of;
 
 /// mstatus register
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Mstatus {
     bits: usize,
 }
 
 /// Additional extension state
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum XS {
     /// All off
     AllOff = 0,
@@ -28,6 +29,7 @@ pub enum XS {
 
 /// Floating-point extension state
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum FS {
     Off = 0,
     Initial = 1,
@@ -37,14 +39,37 @@ pub enum FS {
 
 /// Machine Previous Privilege Mode
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum MPP {
     Machine = 3,
     Supervisor = 1,
     User = 0,
 }
 
+/// Effective XLEN of a lower privilege mode
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum XLEN {
+    XLEN32 = 1,
+    XLEN64 = 2,
+    XLEN128 = 3,
+}
+
+impl XLEN {
+    #[inline]
+    fn from(bits: usize) -> Option<XLEN> {
+        match bits {
+            1 => Some(XLEN::XLEN32),
+            2 => Some(XLEN::XLEN64),
+            3 => Some(XLEN::XLEN128),
+            _ => None,
+        }
+    }
+}
+
 /// Supervisor Previous Privilege Mode
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum SPP {
     Supervisor = 1,
     User = 0,
@@ -107,6 +132,21 @@ impl Mstatus {
         }
     }
 
+    #[inline]
+    pub fn set_sie(&mut self, val: bool) {
+        self.bits.set_bit(1, val);
+    }
+
+    #[inline]
+    pub fn set_spie(&mut self, val: bool) {
+        self.bits.set_bit(5, val);
+    }
+
+    #[inline]
+    pub fn set_spp(&mut self, val: SPP) {
+        self.bits.set_bit(8, val == SPP::Supervisor);
+    }
+
     #[inline]
     pub fn set_mpie(&mut self, val: bool) {
         self.bits.set_bit(7, val);
@@ -137,6 +177,31 @@ impl Mstatus {
         }
     }
 
+    #[inline]
+    pub fn set_fs(&mut self, val: FS) {
+        self.bits.set_bits(13..15, val as usize);
+    }
+
+    /// Vector extension state
+    ///
+    /// Encodes the status of the vector unit, including the vector CSRs and
+    /// vector registers `v0–v31`.
+    #[inline]
+    pub fn vs(&self) -> FS {
+        match self.bits.get_bits(9..11) {
+            0b00 => FS::Off,
+            0b01 => FS::Initial,
+            0b10 => FS::Clean,
+            0b11 => FS::Dirty,
+            _ => unreachable!(),
+        }
+    }
+
+    #[inline]
+    pub fn set_vs(&mut self, val: FS) {
+        self.bits.set_bits(9..11, val as usize);
+    }
+
     /// Additional extension state
     ///
     /// Encodes the status of additional user-mode extensions and associated state.
@@ -151,6 +216,102 @@ impl Mstatus {
         }
     }
 
+    /// Modify PRiVilege
+    ///
+    /// Loads and stores execute with the privilege mode in MPP.
+    #[inline]
+    pub fn mprv(&self) -> bool {
+        self.bits.get_bit(17)
+    }
+
+    /// Permit Supervisor User Memory access
+    #[inline]
+    pub fn sum(&self) -> bool {
+        self.bits.get_bit(18)
+    }
+
+    /// Make eXecutable Readable
+    #[inline]
+    pub fn mxr(&self) -> bool {
+        self.bits.get_bit(19)
+    }
+
+    /// Trap Virtual Memory
+    ///
+    /// Accesses to `satp` and `sfence.vma` in S-mode raise an illegal instruction exception.
+    #[inline]
+    pub fn tvm(&self) -> bool {
+        self.bits.get_bit(20)
+    }
+
+    /// Timeout Wait
+    ///
+    /// `wfi` in modes below M raises an illegal instruction exception after a timeout.
+    #[inline]
+    pub fn tw(&self) -> bool {
+        self.bits.get_bit(21)
+    }
+
+    /// Trap SRET
+    ///
+    /// `sret` in S-mode raises an illegal instruction exception.
+    #[inline]
+    pub fn tsr(&self) -> bool {
+        self.bits.get_bit(22)
+    }
+
+    /// Effective XLEN of U-mode, or `None` if the field is hardwired to zero
+    #[inline]
+    #[cfg(target_pointer_width = "64")]
+    pub fn uxl(&self) -> Option<XLEN> {
+        XLEN::from(self.bits.get_bits(32..34))
+    }
+
+    /// Effective XLEN of S-mode, or `None` if the field is hardwired to zero
+    #[inline]
+    #[cfg(target_pointer_width = "64")]
+    pub fn sxl(&self) -> Option<XLEN> {
+        XLEN::from(self.bits.get_bits(34..36))
+    }
+
+    /// S-mode explicit memory accesses are big endian (in `mstatush` on RV32)
+    #[inline]
+    #[cfg(target_pointer_width = "64")]
+    pub fn sbe(&self) -> bool {
+        self.bits.get_bit(36)
+    }
+
+    /// M-mode explicit memory accesses are big endian (in `mstatush` on RV32)
+    #[inline]
+    #[cfg(target_pointer_width = "64")]
+    pub fn mbe(&self) -> bool {
+        self.bits.get_bit(37)
+    }
+
+    /// Guest Virtual Address was written to `mtval` on the last trap (in `mstatush` on RV32)
+    #[inline]
+    #[cfg(target_pointer_width = "64")]
+    pub fn gva(&self) -> bool {
+        self.bits.get_bit(38)
+    }
+
+    /// Machine Previous Virtualization mode (in `mstatush` on RV32)
+    #[inline]
+    #[cfg(target_pointer_width = "64")]
+    pub fn mpv(&self) -> bool {
+        self.bits.get_bit(39)
+    }
+
+    /// Machine Double Trap (in `mstatush` on RV32)
+    ///
+    /// Set on trap entry into M-mode; a trap taken while it is set is a
+    /// double trap and is reported to the RNMI handler.
+    #[inline]
+    #[cfg(target_pointer_width = "64")]
+    pub fn mdt(&self) -> bool {
+        self.bits.get_bit(42)
+    }
+
     /// Whether either the FS field or XS field
     /// signals the presence of some dirty state
     #[inline]
@@ -163,6 +324,9 @@ read_csr_as!(Mstatus, 0x300, __read_mstatus);
 write_csr!(0x300, __write_mstatus);
 set!(0x300, __set_mstatus);
 clear!(0x300, __clear_mstatus);
+swap_csr_as!(Mstatus, 0x300, __swap_mstatus);
+read_set_clear_csr_as!(Mstatus, 0x300, __read_set_mstatus, __read_clear_mstatus);
+modify_csr!(Mstatus);
 
 set_clear_csr!(
     /// User Interrupt Enable
@@ -188,6 +352,35 @@ set_csr!(
     /// Machine Previous Interrupt Enable
     , set_mpie, 1 << 7);
 
+set_clear_csr!(
+    /// Modify PRiVilege
+    , set_mprv, clear_mprv, 1 << 17);
+
+set_clear_csr!(
+    /// Permit Supervisor User Memory access
+    , set_sum, clear_sum, 1 << 18);
+
+set_clear_csr!(
+    /// Make eXecutable Readable
+    , set_mxr, clear_mxr, 1 << 19);
+
+set_clear_csr!(
+    /// Trap Virtual Memory
+    , set_tvm, clear_tvm, 1 << 20);
+
+set_clear_csr!(
+    /// Timeout Wait
+    , set_tw, clear_tw, 1 << 21);
+
+set_clear_csr!(
+    /// Trap SRET
+    , set_tsr, clear_tsr, 1 << 22);
+
+set_clear_csr!(
+    /// Machine Double Trap
+    #[cfg(target_pointer_width = "64")]
+    , set_mdt, clear_mdt, 1 << 42);
+
 /// Supervisor Previous Privilege Mode
 #[inline]
 pub unsafe fn set_spp(spp: SPP) {
@@ -212,3 +405,11 @@ pub unsafe fn set_fs(fs: FS) {
     value.set_bits(13..15, fs as usize);
     _write(value);
 }
+
+/// Vector extension state
+#[inline]
+pub unsafe fn set_vs(vs: FS) {
+    let mut value = _read();
+    value.set_bits(9..11, vs as usize);
+    _write(value);
+}
@@ -0,0 +1,69 @@
+//! mstatush register
+
+use bit_field::BitField;
+
+/// Upper 32 bits of machine status register (RV32 only)
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Mstatush {
+    bits: usize,
+}
+
+impl Mstatush {
+    /// Returns the contents of the register as raw bits
+    #[inline]
+    pub fn bits(&self) -> usize {
+        self.bits
+    }
+
+    /// S-mode explicit memory accesses are big endian
+    #[inline]
+    pub fn sbe(&self) -> bool {
+        self.bits.get_bit(4)
+    }
+
+    /// M-mode explicit memory accesses are big endian
+    #[inline]
+    pub fn mbe(&self) -> bool {
+        self.bits.get_bit(5)
+    }
+
+    /// Guest Virtual Address was written to `mtval` on the last trap
+    #[inline]
+    pub fn gva(&self) -> bool {
+        self.bits.get_bit(6)
+    }
+
+    /// Machine Previous Virtualization mode
+    #[inline]
+    pub fn mpv(&self) -> bool {
+        self.bits.get_bit(7)
+    }
+
+    /// Machine Double Trap
+    #[inline]
+    pub fn mdt(&self) -> bool {
+        self.bits.get_bit(10)
+    }
+}
+
+read_csr_as_rv32!(Mstatush, 0x310, __read_mstatush);
+write_csr_rv32!(0x310, __write_mstatush);
+set_rv32!(0x310, __set_mstatush);
+clear_rv32!(0x310, __clear_mstatush);
+
+set_clear_csr!(
+    /// S-mode explicit memory accesses are big endian
+    , set_sbe, clear_sbe, 1 << 4);
+set_clear_csr!(
+    /// M-mode explicit memory accesses are big endian
+    , set_mbe, clear_mbe, 1 << 5);
+set_clear_csr!(
+    /// Guest Virtual Address was written to `mtval` on the last trap
+    , set_gva, clear_gva, 1 << 6);
+set_clear_csr!(
+    /// Machine Previous Virtualization mode
+    , set_mpv, clear_mpv, 1 << 7);
+set_clear_csr!(
+    /// Machine Double Trap
+    , set_mdt, clear_mdt, 1 << 10);
@@ -0,0 +1,12 @@
+//! mtinst register
+
+pub use super::htinst::TrapInstruction;
+
+read_csr_as_usize!(0x34A, __read_mtinst);
+write_csr_as_usize!(0x34A, __write_mtinst);
+
+/// Reads and decodes mtinst
+#[inline]
+pub fn read_instruction() -> TrapInstruction {
+    TrapInstruction::from_bits(read() as u32)
+}
@@ -0,0 +1,49 @@
+//! mtopei register
+
+use bit_field::BitField;
+
+/// Machine top external interrupt register
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Mtopei {
+    bits: usize,
+}
+
+impl Mtopei {
+    /// Returns the contents of the register as raw bits
+    #[inline]
+    pub fn bits(&self) -> usize {
+        self.bits
+    }
+
+    /// Identity of the highest-priority pending and enabled external interrupt,
+    /// or 0 if there is none
+    #[inline]
+    pub fn identity(&self) -> usize {
+        self.bits.get_bits(16..27)
+    }
+
+    /// Priority of the interrupt, equal to its identity
+    #[inline]
+    pub fn priority(&self) -> usize {
+        self.bits.get_bits(0..11)
+    }
+}
+
+read_csr_as!(Mtopei, 0x35C, __read_mtopei);
+swap!(0x35C, __swap_mtopei);
+
+/// Claims the highest-priority pending and enabled external interrupt
+///
+/// Atomically reads the register and clears the pending bit of the reported
+/// interrupt. Returns `None` if no interrupt was pending.
+#[inline]
+pub fn claim() -> Option<Mtopei> {
+    let topei = Mtopei {
+        bits: unsafe { _swap(0) },
+    };
+    match topei.identity() {
+        0 => None,
+        _ => Some(topei),
+    }
+}
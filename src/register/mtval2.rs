@@ -0,0 +1,15 @@
+//! mtval2 register
+
+read_csr_as_usize!(0x34B, __read_mtval2);
+write_csr_as_usize!(0x34B, __write_mtval2);
+
+/// Reads the faulting guest physical address
+///
+/// mtval2 holds the guest physical address shifted right by 2 bits; the low
+/// two bits of the address are taken from mtval, which must hold the faulting
+/// guest virtual address of the same trap. Guest physical addresses are
+/// wider than XLEN on RV32 (34 bits), hence the `u64`.
+#[inline]
+pub fn read_gpa() -> u64 {
+    ((read() as u64) << 2) | (::register::mtval::read() as u64 & 0b11)
+}
@@ -1,16 +1,87 @@
 //! mtvec register
 
+use core::fmt;
+
 /// mtvec register
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Mtvec {
     bits: usize,
 }
 
 /// Trap mode
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum TrapMode {
     Direct = 0,
     Vectored = 1,
+    /// Core-local interrupt controller (CLIC) mode
+    ///
+    /// Exceptions and non-vectored interrupts trap to the base-address,
+    /// interrupts with selective hardware vectoring jump through the table
+    /// in `mtvt`/`stvt`.
+    Clic = 3,
+}
+
+/// Error returned when a trap-vector base-address is not suitably aligned
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Misaligned;
+
+impl fmt::Display for Misaligned {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("trap vector base address is misaligned for the trap mode")
+    }
+}
+
+#[cfg(feature = "core-error")]
+impl core::error::Error for Misaligned {}
+
+impl TrapMode {
+    /// Returns the base-address alignment required by this mode
+    ///
+    /// The privileged specification requires four bytes in `Direct` and
+    /// `Vectored` mode, but allows implementations to require more in
+    /// `Vectored` mode; such constraints are not known here and have to be
+    /// met by the caller. In `Clic` mode the CLIC specification takes
+    /// `xtvec[5:0]` for the mode, so the base-address is 64-byte aligned.
+    #[inline]
+    pub fn alignment(self) -> usize {
+        self.mask() + 1
+    }
+
+    /// Returns the mask of the low bits of the register holding the mode
+    #[inline]
+    pub(crate) fn mask(self) -> usize {
+        match self {
+            TrapMode::Clic => 0b11_1111,
+            _ => 0b11,
+        }
+    }
+
+    /// Decodes the mode field of a raw trap-vector register value
+    #[inline]
+    pub fn from_bits(bits: usize) -> Option<Self> {
+        match bits & 0b11 {
+            0 => Some(TrapMode::Direct),
+            1 => Some(TrapMode::Vectored),
+            3 if bits & 0b11_1100 == 0 => Some(TrapMode::Clic),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the address traps with interrupt `code` are routed to
+///
+/// Synchronous exceptions always trap to `base`. In `Clic` mode this is also
+/// the address of non-vectored interrupts; vectored ones are looked up in
+/// the `mtvt`/`stvt` table instead.
+#[inline]
+pub fn slot_address(base: usize, mode: TrapMode, code: usize) -> usize {
+    match mode {
+        TrapMode::Direct | TrapMode::Clic => base,
+        TrapMode::Vectored => base + 4 * code,
+    }
 }
 
 impl Mtvec {
@@ -21,27 +92,89 @@ impl Mtvec {
 
     /// Returns the trap-vector base-address
     pub fn address(&self) -> usize {
-        self.bits - (self.bits & 0b11)
+        match self.trap_mode() {
+            Some(TrapMode::Clic) => self.bits & !TrapMode::Clic.mask(),
+            _ => self.bits - (self.bits & 0b11),
+        }
     }
 
     /// Returns the trap-vector mode
     pub fn trap_mode(&self) -> Option<TrapMode> {
-        let mode = self.bits & 0b11;
-        match mode {
-            0 => Some(TrapMode::Direct),
-            1 => Some(TrapMode::Vectored),
-            _ => None,
-        }
+        TrapMode::from_bits(self.bits)
+    }
+
+    /// Returns the address interrupts with the given cause `code` trap to,
+    /// or `None` if the mode is reserved
+    pub fn slot_address(&self, code: usize) -> Option<usize> {
+        self.trap_mode()
+            .map(|mode| slot_address(self.address(), mode, code))
+    }
+
+    /// Sets the trap-vector base-address
+    ///
+    /// `addr` must be aligned for the current trap mode, see
+    /// [`TrapMode::alignment`].
+    pub fn set_address(&mut self, addr: usize) {
+        let mask = self.trap_mode().map_or(0b11, TrapMode::mask);
+        debug_assert!(
+            addr & mask == 0,
+            "mtvec base-address is not aligned for the trap mode"
+        );
+        self.bits = (addr & !mask) | (self.bits & mask);
+    }
+
+    /// Sets the trap-vector mode
+    ///
+    /// The current base-address must be aligned for `mode`, see
+    /// [`TrapMode::alignment`].
+    pub fn set_trap_mode(&mut self, mode: TrapMode) {
+        debug_assert!(
+            self.address() & mode.mask() == 0,
+            "mtvec base-address is not aligned for the trap mode"
+        );
+        self.bits = (self.bits & !mode.mask()) | mode as usize;
     }
 }
 
 read_csr_as!(Mtvec, 0x305, __read_mtvec);
 
 write_csr!(0x305, __write_mtvec);
+modify_csr!(Mtvec);
 
 /// Writes the CSR
 #[inline]
 pub unsafe fn write(addr: usize, mode: TrapMode) {
+    debug_assert!(
+        addr & mode.mask() == 0,
+        "mtvec base-address is not aligned for the trap mode"
+    );
     let bits = addr + mode as usize;
     _write(bits);
 }
+
+/// Writes the CSR if `addr` satisfies the alignment required by `mode`
+#[inline]
+pub unsafe fn try_write(addr: usize, mode: TrapMode) -> Result<(), Misaligned> {
+    if addr & (mode.alignment() - 1) != 0 {
+        return Err(Misaligned);
+    }
+    _write(addr + mode as usize);
+    Ok(())
+}
+
+/// Rounds `addr` up to the alignment required by `mode`, writes the CSR
+/// and returns the base-address that was written
+///
+/// # Panics
+///
+/// Panics if rounding up `addr` overflows.
+#[inline]
+pub unsafe fn write_aligned(addr: usize, mode: TrapMode) -> usize {
+    let align = mode.alignment();
+    let base = addr
+        .checked_add(align - 1)
+        .expect("mtvec base-address overflows when aligned")
+        & !(align - 1);
+    _write(base + mode as usize);
+    base
+}
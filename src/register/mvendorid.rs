@@ -4,6 +4,7 @@ use core::num::NonZeroUsize;
 
 /// mvendorid register
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Mvendorid {
     bits: NonZeroUsize,
 }
@@ -14,10 +15,23 @@ impl Mvendorid {
         self.bits.get()
     }
 
-    /// Returns the JEDEC manufacturer ID
+    /// Returns the JEDEC bank of the manufacturer
+    #[deprecated(note = "returns the bank, not the ID code; use `bank` and `offset`")]
     pub fn jedec_manufacturer(&self) -> usize {
+        self.bank()
+    }
+
+    /// Returns the JEDEC bank of the manufacturer, i.e. the number of `0x7f`
+    /// continuation codes preceding its ID code
+    pub fn bank(&self) -> usize {
         self.bits() >> 7
     }
+
+    /// Returns the JEDEC ID code of the manufacturer within its bank,
+    /// without the parity bit
+    pub fn offset(&self) -> usize {
+        self.bits() & 0x7f
+    }
 }
 
 read_csr!(0xF11, __read_mvendorid);
@@ -0,0 +1,238 @@
+//! Physical memory protection
+//!
+//! High-level access to the PMP entries backed by the `pmpcfg0`-`pmpcfg15`
+//! and `pmpaddr0`-`pmpaddr63` registers.
+
+use bit_field::BitField;
+use core::fmt;
+use core::mem::size_of;
+use core::ops::Range;
+
+use super::{pmpaddr, pmpcfg};
+
+/// Number of PMP entries
+pub const ENTRIES: usize = 64;
+
+/// Address-matching mode of a PMP entry
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Mode {
+    /// Null region (disabled)
+    Off = 0,
+    /// Top of range
+    Tor = 1,
+    /// Naturally aligned four-byte region
+    Na4 = 2,
+    /// Naturally aligned power-of-two region, at least eight bytes
+    Napot = 3,
+}
+
+bitflags! {
+    /// Access permissions of a PMP entry
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+    pub struct Permissions: u8 {
+        const READ =    1 << 0;
+        const WRITE =   1 << 1;
+        const EXECUTE = 1 << 2;
+    }
+}
+
+/// A region described by a PMP entry
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PmpRegion {
+    /// Physical address range covered by the entry
+    ///
+    /// Physical addresses are wider than XLEN (34 bits on RV32, 56 on RV64),
+    /// hence the `u64` bounds.
+    pub range: Range<u64>,
+    /// Access permissions
+    pub permissions: Permissions,
+    /// Address-matching mode
+    pub mode: Mode,
+    /// Entry is locked until the next reset and also applies to M-mode
+    pub locked: bool,
+}
+
+/// Error returned when a PMP entry cannot be programmed
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PmpError {
+    /// Entry index is not in the range 0-63
+    InvalidIndex,
+    /// Entry (or the entry holding a TOR base address) is locked
+    Locked,
+    /// Range bounds are not aligned as required by the mode
+    Misaligned,
+    /// Range cannot be encoded with the requested mode
+    InvalidRange,
+}
+
+impl fmt::Display for PmpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            PmpError::InvalidIndex => "PMP entry index out of range",
+            PmpError::Locked => "PMP entry is locked",
+            PmpError::Misaligned => "PMP range is misaligned",
+            PmpError::InvalidRange => "PMP range cannot be encoded",
+        })
+    }
+}
+
+#[cfg(feature = "core-error")]
+impl core::error::Error for PmpError {}
+
+const LOCKED: usize = 7;
+const MODE: Range<usize> = 3..5;
+const PERMISSIONS: Range<usize> = 0..3;
+
+/// Width of `pmpaddr`, which holds physical address bits 2 and up
+const ADDR_BITS: u32 = if size_of::<usize>() == 4 { 32 } else { 54 };
+const ADDR_MASK: u64 = (1 << ADDR_BITS) - 1;
+
+/// Returns the `pmpcfg` register holding the entry and the bit offset of its byte
+#[inline]
+fn cfg_location(index: usize) -> (usize, usize) {
+    let per_reg = size_of::<usize>();
+    (index / per_reg * (per_reg / 4), index % per_reg * 8)
+}
+
+#[inline]
+fn read_cfg(index: usize) -> usize {
+    let (reg, offset) = cfg_location(index);
+    pmpcfg::read(reg).get_bits(offset..offset + 8)
+}
+
+#[inline]
+fn write_cfg(index: usize, cfg: usize) {
+    let (reg, offset) = cfg_location(index);
+    let mut bits = pmpcfg::read(reg);
+    bits.set_bits(offset..offset + 8, cfg);
+    pmpcfg::write(reg, bits);
+}
+
+#[inline]
+fn read_addr(index: usize) -> u64 {
+    pmpaddr::read(index) as u64 & ADDR_MASK
+}
+
+/// Reads back and decodes PMP entry `index`
+///
+/// For `Off` entries the range is decoded as if the entry used TOR matching.
+pub fn read(index: usize) -> Result<PmpRegion, PmpError> {
+    if index >= ENTRIES {
+        return Err(PmpError::InvalidIndex);
+    }
+    let cfg = read_cfg(index);
+    let mode = match cfg.get_bits(MODE) {
+        0 => Mode::Off,
+        1 => Mode::Tor,
+        2 => Mode::Na4,
+        _ => Mode::Napot,
+    };
+    let addr = read_addr(index);
+    let range = match mode {
+        Mode::Off | Mode::Tor => {
+            let start = if index == 0 {
+                0
+            } else {
+                read_addr(index - 1) << 2
+            };
+            start..addr << 2
+        }
+        Mode::Na4 => (addr << 2)..(addr << 2) + 4,
+        Mode::Napot => {
+            // All ones cover twice the physical address space, which still fits
+            let ones = (!addr & ADDR_MASK).trailing_zeros().min(ADDR_BITS);
+            let start = (addr & !((1 << ones) - 1)) << 2;
+            start..start + (1 << (ones + 3))
+        }
+    };
+    Ok(PmpRegion {
+        range,
+        permissions: Permissions::from_bits_truncate(cfg.get_bits(PERMISSIONS) as u8),
+        mode,
+        locked: cfg.get_bit(LOCKED),
+    })
+}
+
+/// Encodes `region` into PMP entry `index`
+///
+/// A TOR region at a non-zero index also overwrites `pmpaddr<index - 1>` with
+/// the start of the range, so the previous entry should be `Off` or end
+/// where this region begins. An `Off` region only records `range.end`, so
+/// that it can serve as the base of a following TOR entry. The entry is
+/// turned off while its address is updated.
+///
+/// # Safety
+///
+/// Changing the PMP configuration can revoke access to memory that is still
+/// in use, and a locked entry also restricts M-mode until the next reset.
+pub unsafe fn write(index: usize, region: &PmpRegion) -> Result<(), PmpError> {
+    if index >= ENTRIES {
+        return Err(PmpError::InvalidIndex);
+    }
+    if read_cfg(index).get_bit(LOCKED) {
+        return Err(PmpError::Locked);
+    }
+    // A locked TOR entry also locks the `pmpaddr` holding its base
+    if index + 1 < ENTRIES {
+        let next = read_cfg(index + 1);
+        if next.get_bit(LOCKED) && next.get_bits(MODE) == Mode::Tor as usize {
+            return Err(PmpError::Locked);
+        }
+    }
+    let Range { start, end } = region.range;
+    if start > end {
+        return Err(PmpError::InvalidRange);
+    }
+    if !start.is_multiple_of(4) || !end.is_multiple_of(4) {
+        return Err(PmpError::Misaligned);
+    }
+    let (base, addr) = match region.mode {
+        Mode::Off => (None, end >> 2),
+        Mode::Tor => {
+            if index == 0 {
+                if start != 0 {
+                    return Err(PmpError::InvalidRange);
+                }
+                (None, end >> 2)
+            } else {
+                if read_cfg(index - 1).get_bit(LOCKED) {
+                    return Err(PmpError::Locked);
+                }
+                (Some(start >> 2), end >> 2)
+            }
+        }
+        Mode::Na4 => {
+            if end - start != 4 {
+                return Err(PmpError::InvalidRange);
+            }
+            (None, start >> 2)
+        }
+        Mode::Napot => {
+            let size = end - start;
+            if size < 8 || !size.is_power_of_two() {
+                return Err(PmpError::InvalidRange);
+            }
+            if !start.is_multiple_of(size) {
+                return Err(PmpError::Misaligned);
+            }
+            (None, (start >> 2) | ((size >> 3) - 1))
+        }
+    };
+    if addr > ADDR_MASK {
+        return Err(PmpError::InvalidRange);
+    }
+    write_cfg(index, 0);
+    if let Some(base) = base {
+        pmpaddr::write(index - 1, base as usize);
+    }
+    pmpaddr::write(index, addr as usize);
+    let mut cfg = 0usize;
+    cfg.set_bits(PERMISSIONS, region.permissions.bits() as usize);
+    cfg.set_bits(MODE, region.mode as usize);
+    cfg.set_bit(LOCKED, region.locked);
+    write_cfg(index, cfg);
+    Ok(())
+}
@@ -10,19 +10,167 @@ macro_rules! reg {
     };
 }
 
-reg!(0x3B0, pmpaddr0, __read_pmpaddr0, __write_pmpaddr0);
-reg!(0x3B1, pmpaddr1, __read_pmpaddr1, __write_pmpaddr1);
-reg!(0x3B2, pmpaddr2, __read_pmpaddr2, __write_pmpaddr2);
-reg!(0x3B3, pmpaddr3, __read_pmpaddr3, __write_pmpaddr3);
-reg!(0x3B4, pmpaddr4, __read_pmpaddr4, __write_pmpaddr4);
-reg!(0x3B5, pmpaddr5, __read_pmpaddr5, __write_pmpaddr5);
-reg!(0x3B6, pmpaddr6, __read_pmpaddr6, __write_pmpaddr6);
-reg!(0x3B7, pmpaddr7, __read_pmpaddr7, __write_pmpaddr7);
-reg!(0x3B8, pmpaddr8, __read_pmpaddr8, __write_pmpaddr8);
-reg!(0x3B9, pmpaddr9, __read_pmpaddr9, __write_pmpaddr9);
+reg!(0x3B0, pmpaddr0,  __read_pmpaddr0,  __write_pmpaddr0);
+reg!(0x3B1, pmpaddr1,  __read_pmpaddr1,  __write_pmpaddr1);
+reg!(0x3B2, pmpaddr2,  __read_pmpaddr2,  __write_pmpaddr2);
+reg!(0x3B3, pmpaddr3,  __read_pmpaddr3,  __write_pmpaddr3);
+reg!(0x3B4, pmpaddr4,  __read_pmpaddr4,  __write_pmpaddr4);
+reg!(0x3B5, pmpaddr5,  __read_pmpaddr5,  __write_pmpaddr5);
+reg!(0x3B6, pmpaddr6,  __read_pmpaddr6,  __write_pmpaddr6);
+reg!(0x3B7, pmpaddr7,  __read_pmpaddr7,  __write_pmpaddr7);
+reg!(0x3B8, pmpaddr8,  __read_pmpaddr8,  __write_pmpaddr8);
+reg!(0x3B9, pmpaddr9,  __read_pmpaddr9,  __write_pmpaddr9);
 reg!(0x3BA, pmpaddr10, __read_pmpaddr10, __write_pmpaddr10);
 reg!(0x3BB, pmpaddr11, __read_pmpaddr11, __write_pmpaddr11);
 reg!(0x3BC, pmpaddr12, __read_pmpaddr12, __write_pmpaddr12);
 reg!(0x3BD, pmpaddr13, __read_pmpaddr13, __write_pmpaddr13);
 reg!(0x3BE, pmpaddr14, __read_pmpaddr14, __write_pmpaddr14);
 reg!(0x3BF, pmpaddr15, __read_pmpaddr15, __write_pmpaddr15);
+reg!(0x3C0, pmpaddr16, __read_pmpaddr16, __write_pmpaddr16);
+reg!(0x3C1, pmpaddr17, __read_pmpaddr17, __write_pmpaddr17);
+reg!(0x3C2, pmpaddr18, __read_pmpaddr18, __write_pmpaddr18);
+reg!(0x3C3, pmpaddr19, __read_pmpaddr19, __write_pmpaddr19);
+reg!(0x3C4, pmpaddr20, __read_pmpaddr20, __write_pmpaddr20);
+reg!(0x3C5, pmpaddr21, __read_pmpaddr21, __write_pmpaddr21);
+reg!(0x3C6, pmpaddr22, __read_pmpaddr22, __write_pmpaddr22);
+reg!(0x3C7, pmpaddr23, __read_pmpaddr23, __write_pmpaddr23);
+reg!(0x3C8, pmpaddr24, __read_pmpaddr24, __write_pmpaddr24);
+reg!(0x3C9, pmpaddr25, __read_pmpaddr25, __write_pmpaddr25);
+reg!(0x3CA, pmpaddr26, __read_pmpaddr26, __write_pmpaddr26);
+reg!(0x3CB, pmpaddr27, __read_pmpaddr27, __write_pmpaddr27);
+reg!(0x3CC, pmpaddr28, __read_pmpaddr28, __write_pmpaddr28);
+reg!(0x3CD, pmpaddr29, __read_pmpaddr29, __write_pmpaddr29);
+reg!(0x3CE, pmpaddr30, __read_pmpaddr30, __write_pmpaddr30);
+reg!(0x3CF, pmpaddr31, __read_pmpaddr31, __write_pmpaddr31);
+reg!(0x3D0, pmpaddr32, __read_pmpaddr32, __write_pmpaddr32);
+reg!(0x3D1, pmpaddr33, __read_pmpaddr33, __write_pmpaddr33);
+reg!(0x3D2, pmpaddr34, __read_pmpaddr34, __write_pmpaddr34);
+reg!(0x3D3, pmpaddr35, __read_pmpaddr35, __write_pmpaddr35);
+reg!(0x3D4, pmpaddr36, __read_pmpaddr36, __write_pmpaddr36);
+reg!(0x3D5, pmpaddr37, __read_pmpaddr37, __write_pmpaddr37);
+reg!(0x3D6, pmpaddr38, __read_pmpaddr38, __write_pmpaddr38);
+reg!(0x3D7, pmpaddr39, __read_pmpaddr39, __write_pmpaddr39);
+reg!(0x3D8, pmpaddr40, __read_pmpaddr40, __write_pmpaddr40);
+reg!(0x3D9, pmpaddr41, __read_pmpaddr41, __write_pmpaddr41);
+reg!(0x3DA, pmpaddr42, __read_pmpaddr42, __write_pmpaddr42);
+reg!(0x3DB, pmpaddr43, __read_pmpaddr43, __write_pmpaddr43);
+reg!(0x3DC, pmpaddr44, __read_pmpaddr44, __write_pmpaddr44);
+reg!(0x3DD, pmpaddr45, __read_pmpaddr45, __write_pmpaddr45);
+reg!(0x3DE, pmpaddr46, __read_pmpaddr46, __write_pmpaddr46);
+reg!(0x3DF, pmpaddr47, __read_pmpaddr47, __write_pmpaddr47);
+reg!(0x3E0, pmpaddr48, __read_pmpaddr48, __write_pmpaddr48);
+reg!(0x3E1, pmpaddr49, __read_pmpaddr49, __write_pmpaddr49);
+reg!(0x3E2, pmpaddr50, __read_pmpaddr50, __write_pmpaddr50);
+reg!(0x3E3, pmpaddr51, __read_pmpaddr51, __write_pmpaddr51);
+reg!(0x3E4, pmpaddr52, __read_pmpaddr52, __write_pmpaddr52);
+reg!(0x3E5, pmpaddr53, __read_pmpaddr53, __write_pmpaddr53);
+reg!(0x3E6, pmpaddr54, __read_pmpaddr54, __write_pmpaddr54);
+reg!(0x3E7, pmpaddr55, __read_pmpaddr55, __write_pmpaddr55);
+reg!(0x3E8, pmpaddr56, __read_pmpaddr56, __write_pmpaddr56);
+reg!(0x3E9, pmpaddr57, __read_pmpaddr57, __write_pmpaddr57);
+reg!(0x3EA, pmpaddr58, __read_pmpaddr58, __write_pmpaddr58);
+reg!(0x3EB, pmpaddr59, __read_pmpaddr59, __write_pmpaddr59);
+reg!(0x3EC, pmpaddr60, __read_pmpaddr60, __write_pmpaddr60);
+reg!(0x3ED, pmpaddr61, __read_pmpaddr61, __write_pmpaddr61);
+reg!(0x3EE, pmpaddr62, __read_pmpaddr62, __write_pmpaddr62);
+reg!(0x3EF, pmpaddr63, __read_pmpaddr63, __write_pmpaddr63);
+
+macro_rules! indexed {
+    ($($index:expr => $csr:ident),+) => {
+        /// Runtime-indexed access to the physical memory protection address registers
+        pub mod pmpaddr {
+            /// Reads `pmpaddr<index>`
+            ///
+            /// # Panics
+            ///
+            /// Panics if `index` is not in the range 0-63.
+            #[inline]
+            pub fn read(index: usize) -> usize {
+                match index {
+                    $($index => super::$csr::read(),)+
+                    _ => panic!("invalid pmpaddr index {}", index),
+                }
+            }
+
+            /// Writes `pmpaddr<index>`
+            ///
+            /// # Panics
+            ///
+            /// Panics if `index` is not in the range 0-63.
+            #[inline]
+            pub fn write(index: usize, bits: usize) {
+                match index {
+                    $($index => super::$csr::write(bits),)+
+                    _ => panic!("invalid pmpaddr index {}", index),
+                }
+            }
+        }
+    }
+}
+
+indexed!(
+    0 => pmpaddr0,
+    1 => pmpaddr1,
+    2 => pmpaddr2,
+    3 => pmpaddr3,
+    4 => pmpaddr4,
+    5 => pmpaddr5,
+    6 => pmpaddr6,
+    7 => pmpaddr7,
+    8 => pmpaddr8,
+    9 => pmpaddr9,
+    10 => pmpaddr10,
+    11 => pmpaddr11,
+    12 => pmpaddr12,
+    13 => pmpaddr13,
+    14 => pmpaddr14,
+    15 => pmpaddr15,
+    16 => pmpaddr16,
+    17 => pmpaddr17,
+    18 => pmpaddr18,
+    19 => pmpaddr19,
+    20 => pmpaddr20,
+    21 => pmpaddr21,
+    22 => pmpaddr22,
+    23 => pmpaddr23,
+    24 => pmpaddr24,
+    25 => pmpaddr25,
+    26 => pmpaddr26,
+    27 => pmpaddr27,
+    28 => pmpaddr28,
+    29 => pmpaddr29,
+    30 => pmpaddr30,
+    31 => pmpaddr31,
+    32 => pmpaddr32,
+    33 => pmpaddr33,
+    34 => pmpaddr34,
+    35 => pmpaddr35,
+    36 => pmpaddr36,
+    37 => pmpaddr37,
+    38 => pmpaddr38,
+    39 => pmpaddr39,
+    40 => pmpaddr40,
+    41 => pmpaddr41,
+    42 => pmpaddr42,
+    43 => pmpaddr43,
+    44 => pmpaddr44,
+    45 => pmpaddr45,
+    46 => pmpaddr46,
+    47 => pmpaddr47,
+    48 => pmpaddr48,
+    49 => pmpaddr49,
+    50 => pmpaddr50,
+    51 => pmpaddr51,
+    52 => pmpaddr52,
+    53 => pmpaddr53,
+    54 => pmpaddr54,
+    55 => pmpaddr55,
+    56 => pmpaddr56,
+    57 => pmpaddr57,
+    58 => pmpaddr58,
+    59 => pmpaddr59,
+    60 => pmpaddr60,
+    61 => pmpaddr61,
+    62 => pmpaddr62,
+    63 => pmpaddr63
+);
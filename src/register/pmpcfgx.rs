@@ -1,23 +1,94 @@
-/// Physical memory protection configuration
-pub mod pmpcfg0 {
-    read_csr_as_usize!(0x3A0, __read_pmpcfg0);
-    write_csr_as_usize!(0x3A0, __write_pmpcfg0);
+macro_rules! reg {
+    (
+        $addr:expr, $csr:ident, $readf:ident, $writef:ident
+    ) => {
+        /// Physical memory protection configuration
+        pub mod $csr {
+            read_csr_as_usize!($addr, $readf);
+            write_csr_as_usize!($addr, $writef);
+        }
+    };
 }
 
-/// Physical memory protection configuration, RV32 only
-pub mod pmpcfg1 {
-    read_csr_as_usize_rv32!(0x3A1, __read_pmpcfg1);
-    write_csr_as_usize_rv32!(0x3A1, __write_pmpcfg1);
+macro_rules! reg_rv32 {
+    (
+        $addr:expr, $csr:ident, $readf:ident, $writef:ident
+    ) => {
+        /// Physical memory protection configuration, RV32 only
+        pub mod $csr {
+            read_csr_as_usize_rv32!($addr, $readf);
+            write_csr_as_usize_rv32!($addr, $writef);
+        }
+    };
 }
 
-/// Physical memory protection configuration
-pub mod pmpcfg2 {
-    read_csr_as_usize!(0x3A2, __read_pmpcfg2);
-    write_csr_as_usize!(0x3A2, __write_pmpcfg2);
-}
+reg!(0x3A0, pmpcfg0,  __read_pmpcfg0,  __write_pmpcfg0);
+reg_rv32!(0x3A1, pmpcfg1,  __read_pmpcfg1,  __write_pmpcfg1);
+reg!(0x3A2, pmpcfg2,  __read_pmpcfg2,  __write_pmpcfg2);
+reg_rv32!(0x3A3, pmpcfg3,  __read_pmpcfg3,  __write_pmpcfg3);
+reg!(0x3A4, pmpcfg4,  __read_pmpcfg4,  __write_pmpcfg4);
+reg_rv32!(0x3A5, pmpcfg5,  __read_pmpcfg5,  __write_pmpcfg5);
+reg!(0x3A6, pmpcfg6,  __read_pmpcfg6,  __write_pmpcfg6);
+reg_rv32!(0x3A7, pmpcfg7,  __read_pmpcfg7,  __write_pmpcfg7);
+reg!(0x3A8, pmpcfg8,  __read_pmpcfg8,  __write_pmpcfg8);
+reg_rv32!(0x3A9, pmpcfg9,  __read_pmpcfg9,  __write_pmpcfg9);
+reg!(0x3AA, pmpcfg10, __read_pmpcfg10, __write_pmpcfg10);
+reg_rv32!(0x3AB, pmpcfg11, __read_pmpcfg11, __write_pmpcfg11);
+reg!(0x3AC, pmpcfg12, __read_pmpcfg12, __write_pmpcfg12);
+reg_rv32!(0x3AD, pmpcfg13, __read_pmpcfg13, __write_pmpcfg13);
+reg!(0x3AE, pmpcfg14, __read_pmpcfg14, __write_pmpcfg14);
+reg_rv32!(0x3AF, pmpcfg15, __read_pmpcfg15, __write_pmpcfg15);
+
+macro_rules! indexed {
+    ($($index:expr => $csr:ident),+) => {
+        /// Runtime-indexed access to the physical memory protection configuration registers
+        ///
+        /// The odd-numbered registers only exist on RV32.
+        pub mod pmpcfg {
+            /// Reads `pmpcfg<index>`
+            ///
+            /// # Panics
+            ///
+            /// Panics if `index` is not in the range 0-15.
+            #[inline]
+            pub fn read(index: usize) -> usize {
+                match index {
+                    $($index => super::$csr::read(),)+
+                    _ => panic!("invalid pmpcfg index {}", index),
+                }
+            }
 
-/// Physical memory protection configuration, RV32 only
-pub mod pmpcfg3 {
-    read_csr_as_usize_rv32!(0x3A3, __read_pmpcfg3);
-    write_csr_as_usize_rv32!(0x3A3, __write_pmpcfg3);
+            /// Writes `pmpcfg<index>`
+            ///
+            /// # Panics
+            ///
+            /// Panics if `index` is not in the range 0-15.
+            #[inline]
+            pub fn write(index: usize, bits: usize) {
+                match index {
+                    $($index => super::$csr::write(bits),)+
+                    _ => panic!("invalid pmpcfg index {}", index),
+                }
+            }
+        }
+    }
 }
+
+indexed!(
+    0 => pmpcfg0,
+    1 => pmpcfg1,
+    2 => pmpcfg2,
+    3 => pmpcfg3,
+    4 => pmpcfg4,
+    5 => pmpcfg5,
+    6 => pmpcfg6,
+    7 => pmpcfg7,
+    8 => pmpcfg8,
+    9 => pmpcfg9,
+    10 => pmpcfg10,
+    11 => pmpcfg11,
+    12 => pmpcfg12,
+    13 => pmpcfg13,
+    14 => pmpcfg14,
+    15 => pmpcfg15
+);
@@ -2,11 +2,27 @@
 
 #[cfg(riscv)]
 use addr::Frame;
-#[cfg(riscv)]
+use addr::{FrameWith, PhysicalAddress};
 use bit_field::BitField;
+use core::ops::Range;
+
+#[cfg(target_pointer_width = "32")]
+const MODE: Range<usize> = 31..32;
+#[cfg(target_pointer_width = "32")]
+const ASID: Range<usize> = 22..31;
+#[cfg(target_pointer_width = "32")]
+const PPN: Range<usize> = 0..22;
+
+#[cfg(target_pointer_width = "64")]
+const MODE: Range<usize> = 60..64;
+#[cfg(target_pointer_width = "64")]
+const ASID: Range<usize> = 44..60;
+#[cfg(target_pointer_width = "64")]
+const PPN: Range<usize> = 0..44;
 
 /// satp register
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Satp {
     bits: usize,
 }
@@ -19,55 +35,32 @@ impl Satp {
     }
 
     /// Current address-translation scheme
+    ///
+    /// # Panics
+    ///
+    /// Panics if the MODE field holds a reserved value.
     #[inline]
-    #[cfg(riscv32)]
     pub fn mode(&self) -> Mode {
-        match self.bits.get_bit(31) {
-            false => Mode::Bare,
-            true => Mode::Sv32,
-        }
+        self.try_mode().expect("reserved satp mode")
     }
 
-    /// Current address-translation scheme
+    /// Current address-translation scheme, or `None` if the MODE field
+    /// holds a reserved value
     #[inline]
-    #[cfg(riscv64)]
-    pub fn mode(&self) -> Mode {
-        match self.bits.get_bits(60..64) {
-            0 => Mode::Bare,
-            8 => Mode::Sv39,
-            9 => Mode::Sv48,
-            10 => Mode::Sv57,
-            11 => Mode::Sv64,
-            _ => unreachable!(),
-        }
+    pub fn try_mode(&self) -> Option<Mode> {
+        Mode::from(self.bits.get_bits(MODE))
     }
 
     /// Address space identifier
     #[inline]
-    #[cfg(riscv32)]
     pub fn asid(&self) -> usize {
-        self.bits.get_bits(22..31)
-    }
-
-    /// Address space identifier
-    #[inline]
-    #[cfg(riscv64)]
-    pub fn asid(&self) -> usize {
-        self.bits.get_bits(44..60)
-    }
-
-    /// Physical page number
-    #[inline]
-    #[cfg(riscv32)]
-    pub fn ppn(&self) -> usize {
-        self.bits.get_bits(0..22)
+        self.bits.get_bits(ASID)
     }
 
     /// Physical page number
     #[inline]
-    #[cfg(riscv64)]
     pub fn ppn(&self) -> usize {
-        self.bits.get_bits(0..44)
+        self.bits.get_bits(PPN)
     }
 
     /// Physical frame
@@ -76,17 +69,37 @@ impl Satp {
     pub fn frame(&self) -> Frame {
         Frame::of_ppn(self.ppn())
     }
+
+    /// Physical frame, for an explicit physical address type
+    #[inline]
+    pub fn frame_with<P: PhysicalAddress>(&self) -> FrameWith<P> {
+        FrameWith::of_ppn(self.ppn())
+    }
 }
 
-#[cfg(riscv32)]
+#[cfg(target_pointer_width = "32")]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Mode {
     Bare = 0,
     Sv32 = 1,
 }
 
-#[cfg(riscv64)]
+#[cfg(target_pointer_width = "32")]
+impl Mode {
+    #[inline]
+    fn from(bits: usize) -> Option<Mode> {
+        match bits {
+            0 => Some(Mode::Bare),
+            1 => Some(Mode::Sv32),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(target_pointer_width = "64")]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Mode {
     Bare = 0,
     Sv39 = 8,
@@ -95,25 +108,40 @@ pub enum Mode {
     Sv64 = 11,
 }
 
+#[cfg(target_pointer_width = "64")]
+impl Mode {
+    #[inline]
+    fn from(bits: usize) -> Option<Mode> {
+        match bits {
+            0 => Some(Mode::Bare),
+            8 => Some(Mode::Sv39),
+            9 => Some(Mode::Sv48),
+            10 => Some(Mode::Sv57),
+            11 => Some(Mode::Sv64),
+            _ => None,
+        }
+    }
+}
+
 read_csr_as!(Satp, 0x180, __read_satp);
 write_csr_as_usize!(0x180, __write_satp);
+swap_csr_as!(Satp, 0x180, __swap_satp);
+read_set_clear_csr_as!(Satp, 0x180, __read_set_satp, __read_clear_satp);
 
+/// Sets the translation mode, address space identifier and root page table
+/// physical page number
 #[inline]
-#[cfg(riscv32)]
 pub unsafe fn set(mode: Mode, asid: usize, ppn: usize) {
     let mut bits = 0usize;
-    bits.set_bits(31..32, mode as usize);
-    bits.set_bits(22..31, asid);
-    bits.set_bits(0..22, ppn);
+    bits.set_bits(MODE, mode as usize);
+    bits.set_bits(ASID, asid);
+    bits.set_bits(PPN, ppn);
     _write(bits);
 }
 
+/// Sets the translation mode, address space identifier and root page table
+/// frame
 #[inline]
-#[cfg(riscv64)]
-pub unsafe fn set(mode: Mode, asid: usize, ppn: usize) {
-    let mut bits = 0usize;
-    bits.set_bits(60..64, mode as usize);
-    bits.set_bits(44..60, asid);
-    bits.set_bits(0..44, ppn);
-    _write(bits);
+pub unsafe fn set_frame<P: PhysicalAddress>(mode: Mode, asid: usize, frame: FrameWith<P>) {
+    set(mode, asid, frame.number());
 }
@@ -3,97 +3,15 @@
 use bit_field::BitField;
 use core::mem::size_of;
 
+pub use trap::{Exception, Interrupt, Trap};
+
 /// scause register
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Scause {
     bits: usize,
 }
 
-/// Trap Cause
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
-pub enum Trap {
-    Interrupt(Interrupt),
-    Exception(Exception),
-}
-
-/// Interrupt
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
-pub enum Interrupt {
-    UserSoft,
-    VirtualSupervisorSoft,
-    SupervisorSoft,
-    UserTimer,
-    VirtualSupervisorTimer,
-    SupervisorTimer,
-    UserExternal,
-    VirtualSupervisorExternal,
-    SupervisorExternal,
-    Unknown,
-}
-
-/// Exception
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
-pub enum Exception {
-    InstructionMisaligned,
-    InstructionFault,
-    IllegalInstruction,
-    Breakpoint,
-    LoadFault,
-    StoreMisaligned,
-    StoreFault,
-    UserEnvCall,
-    VirtualSupervisorEnvCall,
-    InstructionPageFault,
-    LoadPageFault,
-    StorePageFault,
-    InstructionGuestPageFault,
-    LoadGuestPageFault,
-    VirtualInstruction,
-    StoreGuestPageFault,
-    Unknown,
-}
-
-impl Interrupt {
-    pub fn from(nr: usize) -> Self {
-        match nr {
-            0 => Interrupt::UserSoft,
-            1 => Interrupt::SupervisorSoft,
-            2 => Interrupt::VirtualSupervisorSoft,
-            4 => Interrupt::UserTimer,
-            5 => Interrupt::SupervisorTimer,
-            6 => Interrupt::VirtualSupervisorTimer,
-            8 => Interrupt::UserExternal,
-            9 => Interrupt::SupervisorExternal,
-            10 => Interrupt::VirtualSupervisorExternal,
-            _ => Interrupt::Unknown,
-        }
-    }
-}
-
-impl Exception {
-    pub fn from(nr: usize) -> Self {
-        match nr {
-            0 => Exception::InstructionMisaligned,
-            1 => Exception::InstructionFault,
-            2 => Exception::IllegalInstruction,
-            3 => Exception::Breakpoint,
-            5 => Exception::LoadFault,
-            6 => Exception::StoreMisaligned,
-            7 => Exception::StoreFault,
-            8 => Exception::UserEnvCall,
-            10 => Exception::VirtualSupervisorEnvCall,
-            12 => Exception::InstructionPageFault,
-            13 => Exception::LoadPageFault,
-            15 => Exception::StorePageFault,
-            20 => Exception::InstructionGuestPageFault,
-            21 => Exception::LoadGuestPageFault,
-            22 => Exception::VirtualInstruction,
-            23 => Exception::StoreGuestPageFault,
-            _ => Exception::Unknown,
-        }
-    }
-}
-
 impl Scause {
     /// Returns the contents of the register as raw bits
     #[inline]
@@ -110,11 +28,7 @@ impl Scause {
     /// Trap Cause
     #[inline]
     pub fn cause(&self) -> Trap {
-        if self.is_interrupt() {
-            Trap::Interrupt(Interrupt::from(self.code()))
-        } else {
-            Trap::Exception(Exception::from(self.code()))
-        }
+        Trap::from_code(self.is_interrupt(), self.code())
     }
 
     /// Is trap cause an interrupt.
@@ -131,3 +45,10 @@ impl Scause {
 }
 
 read_csr_as!(Scause, 0x142, __read_scause);
+write_csr!(0x142, __write_scause);
+
+/// Writes the CSR
+#[inline]
+pub unsafe fn write(bits: usize) {
+    _write(bits)
+}
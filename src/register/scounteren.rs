@@ -0,0 +1,71 @@
+//! scounteren register
+
+use bit_field::BitField;
+
+/// scounteren register
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Scounteren {
+    bits: usize,
+}
+
+impl Scounteren {
+    /// Returns the contents of the register as raw bits
+    #[inline]
+    pub fn bits(&self) -> usize {
+        self.bits
+    }
+
+    /// User "cycle\[h\]" Enable
+    #[inline]
+    pub fn cy(&self) -> bool {
+        self.bits.get_bit(0)
+    }
+
+    /// User "time\[h\]" Enable
+    #[inline]
+    pub fn tm(&self) -> bool {
+        self.bits.get_bit(1)
+    }
+
+    /// User "instret\[h\]" Enable
+    #[inline]
+    pub fn ir(&self) -> bool {
+        self.bits.get_bit(2)
+    }
+
+    /// User "hpm\[x\]" Enable (bits 3-31)
+    #[inline]
+    pub fn hpm(&self, index: usize) -> bool {
+        assert!((3..32).contains(&index));
+        self.bits.get_bit(index)
+    }
+}
+
+read_csr_as!(Scounteren, 0x106, __read_scounteren);
+set!(0x106, __set_scounteren);
+clear!(0x106, __clear_scounteren);
+
+set_clear_csr!(
+    /// User "cycle\[h\]" Enable
+    , set_cy, clear_cy, 1 << 0);
+set_clear_csr!(
+    /// User "time\[h\]" Enable
+    , set_tm, clear_tm, 1 << 1);
+set_clear_csr!(
+    /// User "instret\[h\]" Enable
+    , set_ir, clear_ir, 1 << 2);
+
+/// User "hpm\[x\]" Enable (bits 3-31)
+#[inline]
+pub unsafe fn set_hpm(index: usize) {
+    assert!((3..32).contains(&index));
+    _set(1 << index);
+}
+
+/// User "hpm\[x\]" Disable (bits 3-31)
+#[inline]
+pub unsafe fn clear_hpm(index: usize) {
+    assert!((3..32).contains(&index));
+    _clear(1 << index);
+}
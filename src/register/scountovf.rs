@@ -0,0 +1,34 @@
+//! scountovf register (Sscofpmf)
+
+use bit_field::BitField;
+
+/// Supervisor count overflow register
+///
+/// Read-only shadow of the OF bits of `mhpmevent3`-`mhpmevent31`, for the
+/// counters that are accessible to S-mode through `mcounteren`.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Scountovf {
+    bits: usize,
+}
+
+impl Scountovf {
+    /// Returns the contents of the register as raw bits
+    #[inline]
+    pub fn bits(&self) -> usize {
+        self.bits
+    }
+
+    /// Overflow bit of `hpmcounter<index>`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not in the range 3-31.
+    #[inline]
+    pub fn of(&self, index: usize) -> bool {
+        assert!((3..32).contains(&index));
+        self.bits.get_bit(index)
+    }
+}
+
+read_csr_as!(Scountovf, 0xDA0, __read_scountovf);
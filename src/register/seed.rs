@@ -0,0 +1,78 @@
+//! seed register (Zkr)
+//!
+//! The seed CSR must be accessed with a read-write instruction; reads are
+//! therefore performed by swapping in a zero value.
+
+use bit_field::BitField;
+
+/// Entropy source register
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Seed {
+    bits: usize,
+}
+
+/// Entropy source status
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum OPST {
+    /// Built-in self-test in progress; no entropy available yet
+    Bist = 0b00,
+    /// Entropy is being collected; retry later
+    Wait = 0b01,
+    /// 16 bits of entropy are available
+    Es16 = 0b10,
+    /// Unrecoverable self-test error; the source is not operational
+    Dead = 0b11,
+}
+
+impl Seed {
+    /// Returns the contents of the register as raw bits
+    #[inline]
+    pub fn bits(&self) -> usize {
+        self.bits
+    }
+
+    /// Status of the entropy source
+    #[inline]
+    pub fn opst(&self) -> OPST {
+        match self.bits.get_bits(30..32) {
+            0b00 => OPST::Bist,
+            0b01 => OPST::Wait,
+            0b10 => OPST::Es16,
+            0b11 => OPST::Dead,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Entropy bits, only valid when `opst` is `Es16`
+    #[inline]
+    pub fn entropy(&self) -> u16 {
+        self.bits.get_bits(0..16) as u16
+    }
+}
+
+swap!(0x015, __swap_seed);
+
+/// Reads the CSR
+#[inline]
+pub fn read() -> Seed {
+    Seed {
+        bits: unsafe { _swap(0) },
+    }
+}
+
+/// Polls the entropy source until 16 bits of entropy are available
+///
+/// Returns `None` if the entropy source reports `Dead`.
+#[inline]
+pub fn get_entropy_u16() -> Option<u16> {
+    loop {
+        let seed = read();
+        match seed.opst() {
+            OPST::Es16 => return Some(seed.entropy()),
+            OPST::Dead => return None,
+            OPST::Bist | OPST::Wait => continue,
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! senvcfg register
+
+pub use super::menvcfg::CBIE;
+use bit_field::BitField;
+
+/// Supervisor environment configuration register
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Senvcfg {
+    bits: usize,
+}
+
+impl Senvcfg {
+    /// Returns the contents of the register as raw bits
+    #[inline]
+    pub fn bits(&self) -> usize {
+        self.bits
+    }
+
+    /// Fence of I/O implies Memory
+    #[inline]
+    pub fn fiom(&self) -> bool {
+        self.bits.get_bit(0)
+    }
+
+    /// Cache block invalidate instruction enable, or `None` if reserved
+    #[inline]
+    pub fn cbie(&self) -> Option<CBIE> {
+        CBIE::from(self.bits.get_bits(4..6))
+    }
+
+    /// Cache block clean and flush instruction enable
+    #[inline]
+    pub fn cbcfe(&self) -> bool {
+        self.bits.get_bit(6)
+    }
+
+    /// Cache block zero instruction enable
+    #[inline]
+    pub fn cbze(&self) -> bool {
+        self.bits.get_bit(7)
+    }
+}
+
+read_csr_as!(Senvcfg, 0x10A, __read_senvcfg);
+write_csr!(0x10A, __write_senvcfg);
+set!(0x10A, __set_senvcfg);
+clear!(0x10A, __clear_senvcfg);
+
+set_clear_csr!(
+    /// Fence of I/O implies Memory
+    , set_fiom, clear_fiom, 1 << 0);
+set_clear_csr!(
+    /// Cache block clean and flush instruction enable
+    , set_cbcfe, clear_cbcfe, 1 << 6);
+set_clear_csr!(
+    /// Cache block zero instruction enable
+    , set_cbze, clear_cbze, 1 << 7);
+
+/// Cache block invalidate instruction enable
+#[inline]
+pub unsafe fn set_cbie(cbie: CBIE) {
+    let mut value = _read();
+    value.set_bits(4..6, cbie as usize);
+    _write(value);
+}
@@ -4,6 +4,7 @@ use bit_field::BitField;
 
 /// sie register
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Sie {
     bits: usize,
 }
@@ -55,6 +56,8 @@ impl Sie {
 read_csr_as!(Sie, 0x104, __read_sie);
 set!(0x104, __set_sie);
 clear!(0x104, __clear_sie);
+swap_csr_as!(Sie, 0x104, __swap_sie);
+read_set_clear_csr_as!(Sie, 0x104, __read_set_sie, __read_clear_sie);
 
 set_clear_csr!(
     /// User Software Interrupt Enable
@@ -4,6 +4,7 @@ use bit_field::BitField;
 
 /// sip register
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Sip {
     bits: usize,
 }
@@ -53,3 +54,11 @@ impl Sip {
 }
 
 read_csr_as!(Sip, 0x144, __read_sip);
+set!(0x144, __set_sip);
+clear!(0x144, __clear_sip);
+swap_csr_as!(Sip, 0x144, __swap_sip);
+read_set_clear_csr_as!(Sip, 0x144, __read_set_sip, __read_clear_sip);
+
+set_clear_csr!(
+    /// Supervisor Software Interrupt Pending
+    , set_ssoft, clear_ssoft, 1 << 1);
@@ -0,0 +1,22 @@
+//! sireg register
+
+read_csr_as_usize!(0x151, __read_sireg);
+write_csr_as_usize!(0x151, __write_sireg);
+
+/// Reads the indirectly accessed register selected by `select`
+///
+/// Writes `siselect`, so it must not race with other users of the window.
+#[inline]
+pub fn read_indirect(select: usize) -> usize {
+    super::siselect::write(select);
+    read()
+}
+
+/// Writes the indirectly accessed register selected by `select`
+///
+/// Writes `siselect`, so it must not race with other users of the window.
+#[inline]
+pub fn write_indirect(select: usize, bits: usize) {
+    super::siselect::write(select);
+    write(bits);
+}
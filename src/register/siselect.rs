@@ -0,0 +1,4 @@
+//! siselect register
+
+read_csr_as_usize!(0x150, __read_siselect);
+write_csr_as_usize!(0x150, __write_siselect);
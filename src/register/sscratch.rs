@@ -2,3 +2,4 @@
 
 read_csr_as_usize!(0x140, __read_sscratch);
 write_csr_as_usize!(0x140, __write_sscratch);
+swap_csr_as_usize!(0x140, __swap_sscratch);
@@ -1,17 +1,19 @@
 //! sstatus register
 
-pub use super::mstatus::FS;
+pub use super::mstatus::{FS, XLEN};
 use bit_field::BitField;
 use core::mem::size_of;
 
 /// Supervisor Status Register
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Sstatus {
     bits: usize,
 }
 
 /// Supervisor Previous Privilege Mode
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum SPP {
     Supervisor = 1,
     User = 0,
@@ -69,6 +71,18 @@ impl Sstatus {
         }
     }
 
+    /// The status of the vector unit
+    #[inline]
+    pub fn vs(&self) -> FS {
+        match self.bits.get_bits(9..11) {
+            0 => FS::Off,
+            1 => FS::Initial,
+            2 => FS::Clean,
+            3 => FS::Dirty,
+            _ => unreachable!(),
+        }
+    }
+
     /// The status of additional user-mode extensions
     /// and associated state
     #[inline]
@@ -94,6 +108,27 @@ impl Sstatus {
         self.bits.get_bit(19)
     }
 
+    /// Supervisor Double Trap
+    ///
+    /// Set on trap entry into S-mode when double-trap detection is enabled
+    /// (`menvcfg.DTE`); a trap taken while it is set is a double trap.
+    #[inline]
+    pub fn sdt(&self) -> bool {
+        self.bits.get_bit(24)
+    }
+
+    /// Effective XLEN of U-mode, or `None` if the field is hardwired to zero
+    #[inline]
+    #[cfg(target_pointer_width = "64")]
+    pub fn uxl(&self) -> Option<XLEN> {
+        match self.bits.get_bits(32..34) {
+            1 => Some(XLEN::XLEN32),
+            2 => Some(XLEN::XLEN64),
+            3 => Some(XLEN::XLEN128),
+            _ => None,
+        }
+    }
+
     /// Whether either the FS field or XS field
     /// signals the presence of some dirty state
     #[inline]
@@ -115,12 +150,25 @@ impl Sstatus {
     pub fn set_spp(&mut self, val: SPP) {
         self.bits.set_bit(8, val == SPP::Supervisor);
     }
+
+    #[inline]
+    pub fn set_fs(&mut self, val: FS) {
+        self.bits.set_bits(13..15, val as usize);
+    }
+
+    #[inline]
+    pub fn set_vs(&mut self, val: FS) {
+        self.bits.set_bits(9..11, val as usize);
+    }
 }
 
 read_csr_as!(Sstatus, 0x100, __read_sstatus);
 write_csr!(0x100, __write_sstatus);
 set!(0x100, __set_sstatus);
 clear!(0x100, __clear_sstatus);
+swap_csr_as!(Sstatus, 0x100, __swap_sstatus);
+read_set_clear_csr_as!(Sstatus, 0x100, __read_set_sstatus, __read_clear_sstatus);
+modify_csr!(Sstatus);
 
 set_clear_csr!(
     /// User Interrupt Enable
@@ -140,6 +188,9 @@ set_clear_csr!(
 set_clear_csr!(
     /// Permit Supervisor User Memory access
     , set_sum, clear_sum, 1 << 18);
+set_clear_csr!(
+    /// Supervisor Double Trap
+    , set_sdt, clear_sdt, 1 << 24);
 
 /// Supervisor Previous Privilege Mode
 #[inline]
@@ -0,0 +1,30 @@
+//! stimecmp register (Sstc)
+
+read_csr_as_usize!(0x14D, __read_stimecmp);
+write_csr_as_usize!(0x14D, __write_stimecmp);
+read_composite_csr!(super::stimecmph::read(), read());
+
+/// Writes the CSR as a 64-bit value
+///
+/// On RV32 the low half is first set to all ones, so that no spurious
+/// timer interrupt is raised while the two halves are updated.
+#[inline]
+pub fn write64(value: u64) {
+    match () {
+        #[cfg(riscv32)]
+        () => {
+            write(usize::max_value());
+            super::stimecmph::write((value >> 32) as usize);
+            write(value as usize);
+        }
+
+        #[cfg(not(riscv32))]
+        () => write(value as usize),
+    }
+}
+
+/// Raises a supervisor timer interrupt `ticks` ticks of `time` from now
+#[inline]
+pub fn set_timer_after(ticks: u64) {
+    write64(super::time::read64().wrapping_add(ticks));
+}
@@ -0,0 +1,4 @@
+//! stimecmph register
+
+read_csr_as_usize_rv32!(0x15D, __read_stimecmph);
+write_csr_as_usize_rv32!(0x15D, __write_stimecmph);
@@ -0,0 +1,49 @@
+//! stopei register
+
+use bit_field::BitField;
+
+/// Supervisor top external interrupt register
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Stopei {
+    bits: usize,
+}
+
+impl Stopei {
+    /// Returns the contents of the register as raw bits
+    #[inline]
+    pub fn bits(&self) -> usize {
+        self.bits
+    }
+
+    /// Identity of the highest-priority pending and enabled external interrupt,
+    /// or 0 if there is none
+    #[inline]
+    pub fn identity(&self) -> usize {
+        self.bits.get_bits(16..27)
+    }
+
+    /// Priority of the interrupt, equal to its identity
+    #[inline]
+    pub fn priority(&self) -> usize {
+        self.bits.get_bits(0..11)
+    }
+}
+
+read_csr_as!(Stopei, 0x15C, __read_stopei);
+swap!(0x15C, __swap_stopei);
+
+/// Claims the highest-priority pending and enabled external interrupt
+///
+/// Atomically reads the register and clears the pending bit of the reported
+/// interrupt. Returns `None` if no interrupt was pending.
+#[inline]
+pub fn claim() -> Option<Stopei> {
+    let topei = Stopei {
+        bits: unsafe { _swap(0) },
+    };
+    match topei.identity() {
+        0 => None,
+        _ => Some(topei),
+    }
+}
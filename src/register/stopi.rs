@@ -0,0 +1,33 @@
+//! stopi register
+
+use bit_field::BitField;
+
+/// Supervisor top interrupt register
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Stopi {
+    bits: usize,
+}
+
+impl Stopi {
+    /// Returns the contents of the register as raw bits
+    #[inline]
+    pub fn bits(&self) -> usize {
+        self.bits
+    }
+
+    /// Major identity (interrupt cause code) of the highest-priority pending
+    /// and enabled interrupt, or 0 if there is none
+    #[inline]
+    pub fn iid(&self) -> usize {
+        self.bits.get_bits(16..28)
+    }
+
+    /// Priority of the interrupt (lower is higher priority)
+    #[inline]
+    pub fn iprio(&self) -> usize {
+        self.bits.get_bits(0..8)
+    }
+}
+
+read_csr_as!(Stopi, 0xDB0, __read_stopi);
@@ -1,9 +1,10 @@
 //! stvec register
 
-pub use crate::register::mtvec::TrapMode;
+pub use crate::register::mtvec::{slot_address, Misaligned, TrapMode};
 
 /// stvec register
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Stvec {
     bits: usize,
 }
@@ -16,25 +17,87 @@ impl Stvec {
 
     /// Returns the trap-vector base-address
     pub fn address(&self) -> usize {
-        self.bits - (self.bits & 0b11)
+        match self.trap_mode() {
+            Some(TrapMode::Clic) => self.bits & !TrapMode::Clic.mask(),
+            _ => self.bits - (self.bits & 0b11),
+        }
     }
 
     /// Returns the trap-vector mode
     pub fn trap_mode(&self) -> Option<TrapMode> {
-        let mode = self.bits & 0b11;
-        match mode {
-            0 => Some(TrapMode::Direct),
-            1 => Some(TrapMode::Vectored),
-            _ => None,
-        }
+        TrapMode::from_bits(self.bits)
+    }
+
+    /// Returns the address interrupts with the given cause `code` trap to,
+    /// or `None` if the mode is reserved
+    pub fn slot_address(&self, code: usize) -> Option<usize> {
+        self.trap_mode()
+            .map(|mode| slot_address(self.address(), mode, code))
+    }
+
+    /// Sets the trap-vector base-address
+    ///
+    /// `addr` must be aligned for the current trap mode, see
+    /// [`TrapMode::alignment`].
+    pub fn set_address(&mut self, addr: usize) {
+        let mask = self.trap_mode().map_or(0b11, TrapMode::mask);
+        debug_assert!(
+            addr & mask == 0,
+            "stvec base-address is not aligned for the trap mode"
+        );
+        self.bits = (addr & !mask) | (self.bits & mask);
+    }
+
+    /// Sets the trap-vector mode
+    ///
+    /// The current base-address must be aligned for `mode`, see
+    /// [`TrapMode::alignment`].
+    pub fn set_trap_mode(&mut self, mode: TrapMode) {
+        debug_assert!(
+            self.address() & mode.mask() == 0,
+            "stvec base-address is not aligned for the trap mode"
+        );
+        self.bits = (self.bits & !mode.mask()) | mode as usize;
     }
 }
 
 read_csr_as!(Stvec, 0x105, __read_stvec);
 write_csr!(0x105, __write_stvec);
+modify_csr!(Stvec);
 
 /// Writes the CSR
 #[inline]
 pub unsafe fn write(addr: usize, mode: TrapMode) {
+    debug_assert!(
+        addr & mode.mask() == 0,
+        "stvec base-address is not aligned for the trap mode"
+    );
     _write(addr + mode as usize);
 }
+
+/// Writes the CSR if `addr` satisfies the alignment required by `mode`
+#[inline]
+pub unsafe fn try_write(addr: usize, mode: TrapMode) -> Result<(), Misaligned> {
+    if addr & (mode.alignment() - 1) != 0 {
+        return Err(Misaligned);
+    }
+    _write(addr + mode as usize);
+    Ok(())
+}
+
+/// Rounds `addr` up to the alignment required by `mode`, writes the CSR
+/// and returns the base-address that was written
+///
+/// # Panics
+///
+/// Panics if rounding up `addr` overflows.
+#[inline]
+pub unsafe fn write_aligned(addr: usize, mode: TrapMode) -> usize {
+    let align = mode.alignment();
+    let base = addr
+        .checked_add(align - 1)
+        .expect("stvec base-address overflows when aligned")
+        & !(align - 1);
+    _write(base + mode as usize);
+    base
+}
@@ -0,0 +1,20 @@
+//! stvt register
+//!
+//! Base address of the CLIC trap vector table, used for interrupts with
+//! selective hardware vectoring while `stvec` is in `TrapMode::Clic`. Each
+//! entry holds the XLEN-wide address of the handler of one interrupt.
+
+read_csr_as_usize!(0x107, __read_stvt);
+write_csr_as_usize!(0x107, __write_stvt);
+
+/// Minimum alignment of the table
+///
+/// Implementations may ignore more low bits, depending on the number of
+/// interrupts they support.
+pub const ALIGN: usize = 64;
+
+/// Returns the address of the table entry for interrupt `id`
+#[inline]
+pub fn entry_address(id: usize) -> usize {
+    read() + id * core::mem::size_of::<usize>()
+}
@@ -0,0 +1,42 @@
+//! tcontrol register
+
+use bit_field::BitField;
+
+/// Trigger control register
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Tcontrol {
+    bits: usize,
+}
+
+impl Tcontrol {
+    /// Returns the contents of the register as raw bits
+    #[inline]
+    pub fn bits(&self) -> usize {
+        self.bits
+    }
+
+    /// M-mode previous trigger enable
+    #[inline]
+    pub fn mpte(&self) -> bool {
+        self.bits.get_bit(7)
+    }
+
+    /// M-mode trigger enable
+    #[inline]
+    pub fn mte(&self) -> bool {
+        self.bits.get_bit(3)
+    }
+}
+
+read_csr_as!(Tcontrol, 0x7A5, __read_tcontrol);
+write_csr!(0x7A5, __write_tcontrol);
+set!(0x7A5, __set_tcontrol);
+clear!(0x7A5, __clear_tcontrol);
+
+set_clear_csr!(
+    /// M-mode previous trigger enable
+    , set_mpte, clear_mpte, 1 << 7);
+set_clear_csr!(
+    /// M-mode trigger enable
+    , set_mte, clear_mte, 1 << 3);
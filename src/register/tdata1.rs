@@ -0,0 +1,4 @@
+//! tdata1 register
+
+read_csr_as_usize!(0x7A1, __read_tdata1);
+write_csr_as_usize!(0x7A1, __write_tdata1);
@@ -0,0 +1,4 @@
+//! tdata2 register
+
+read_csr_as_usize!(0x7A2, __read_tdata2);
+write_csr_as_usize!(0x7A2, __write_tdata2);
@@ -0,0 +1,4 @@
+//! tdata3 register
+
+read_csr_as_usize!(0x7A3, __read_tdata3);
+write_csr_as_usize!(0x7A3, __write_tdata3);
@@ -0,0 +1,32 @@
+//! tinfo register
+
+use bit_field::BitField;
+
+/// Trigger info register
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Tinfo {
+    bits: usize,
+}
+
+impl Tinfo {
+    /// Returns the contents of the register as raw bits
+    #[inline]
+    pub fn bits(&self) -> usize {
+        self.bits
+    }
+
+    /// Bit mask of the trigger types supported by the selected trigger
+    #[inline]
+    pub fn info(&self) -> usize {
+        self.bits.get_bits(0..16)
+    }
+
+    /// Version of the Sdtrig extension implemented
+    #[inline]
+    pub fn version(&self) -> usize {
+        self.bits.get_bits(24..32)
+    }
+}
+
+read_csr_as!(Tinfo, 0x7A4, __read_tinfo);
@@ -0,0 +1,716 @@
+//! Hardware triggers (Sdtrig)
+//!
+//! Enumerates the triggers selected through `tselect`, decodes `tdata1` and
+//! arms address/data match breakpoints and watchpoints.
+
+use bit_field::BitField;
+use core::mem::size_of;
+
+use super::{tdata1, tdata2, tselect};
+
+const XLEN: usize = size_of::<usize>() * 8;
+
+/// Trigger type, encoded in the top four bits of `tdata1`
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Type {
+    /// No trigger exists at this index
+    None = 0,
+    /// Legacy SiFive address match trigger
+    Legacy = 1,
+    /// Address/data match trigger
+    Mcontrol = 2,
+    /// Instruction count trigger
+    Icount = 3,
+    /// Interrupt trigger
+    Itrigger = 4,
+    /// Exception trigger
+    Etrigger = 5,
+    /// Address/data match trigger, Sdtrig 1.0 layout
+    Mcontrol6 = 6,
+    /// External trigger input
+    Tmexttrigger = 7,
+    /// Trigger exists but is currently disabled
+    Disabled = 15,
+}
+
+/// Action taken when a trigger fires
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Action {
+    /// Raise a breakpoint exception
+    Breakpoint = 0,
+    /// Enter Debug Mode
+    DebugMode = 1,
+    /// Start tracing
+    TraceOn = 2,
+    /// Stop tracing
+    TraceOff = 3,
+    /// Emit a trace notification
+    TraceNotify = 4,
+    /// Signal external trigger output 0
+    External0 = 8,
+    /// Signal external trigger output 1
+    External1 = 9,
+}
+
+/// Comparison performed by a match trigger
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Match {
+    /// Value equals `tdata2`
+    Equal = 0,
+    /// Top bits of the value match the NAPOT range in `tdata2`
+    Napot = 1,
+    /// Value is greater than or equal to `tdata2`
+    GreaterEqual = 2,
+    /// Value is less than `tdata2`
+    Less = 3,
+    /// Low half of the value, masked by the high half of `tdata2`, matches
+    MaskLow = 4,
+    /// High half of the value, masked by the high half of `tdata2`, matches
+    MaskHigh = 5,
+    /// Negation of `Equal`
+    NotEqual = 8,
+    /// Negation of `Napot`
+    NotNapot = 9,
+    /// Negation of `MaskLow`
+    NotMaskLow = 12,
+    /// Negation of `MaskHigh`
+    NotMaskHigh = 13,
+}
+
+impl Action {
+    #[inline]
+    fn from(bits: usize) -> Option<Action> {
+        match bits {
+            0 => Some(Action::Breakpoint),
+            1 => Some(Action::DebugMode),
+            2 => Some(Action::TraceOn),
+            3 => Some(Action::TraceOff),
+            4 => Some(Action::TraceNotify),
+            8 => Some(Action::External0),
+            9 => Some(Action::External1),
+            _ => None,
+        }
+    }
+}
+
+impl Match {
+    #[inline]
+    fn from(bits: usize) -> Option<Match> {
+        match bits {
+            0 => Some(Match::Equal),
+            1 => Some(Match::Napot),
+            2 => Some(Match::GreaterEqual),
+            3 => Some(Match::Less),
+            4 => Some(Match::MaskLow),
+            5 => Some(Match::MaskHigh),
+            8 => Some(Match::NotEqual),
+            9 => Some(Match::NotNapot),
+            12 => Some(Match::NotMaskLow),
+            13 => Some(Match::NotMaskHigh),
+            _ => None,
+        }
+    }
+}
+
+/// Contents of `tdata1` for the selected trigger
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Tdata1 {
+    bits: usize,
+}
+
+impl Tdata1 {
+    /// Returns the contents of the register as raw bits
+    #[inline]
+    pub fn bits(&self) -> usize {
+        self.bits
+    }
+
+    /// Trigger type, or `None` if reserved
+    #[inline]
+    pub fn trigger_type(&self) -> Option<Type> {
+        match self.bits.get_bits(XLEN - 4..XLEN) {
+            0 => Some(Type::None),
+            1 => Some(Type::Legacy),
+            2 => Some(Type::Mcontrol),
+            3 => Some(Type::Icount),
+            4 => Some(Type::Itrigger),
+            5 => Some(Type::Etrigger),
+            6 => Some(Type::Mcontrol6),
+            7 => Some(Type::Tmexttrigger),
+            15 => Some(Type::Disabled),
+            _ => None,
+        }
+    }
+
+    /// Trigger is only writable from Debug Mode
+    #[inline]
+    pub fn dmode(&self) -> bool {
+        self.bits.get_bit(XLEN - 5)
+    }
+
+    /// Decodes the type-specific fields
+    #[inline]
+    pub fn decode(&self) -> Trigger {
+        match self.trigger_type() {
+            Some(Type::Mcontrol) => Trigger::Mcontrol(Mcontrol { bits: self.bits }),
+            Some(Type::Mcontrol6) => Trigger::Mcontrol6(Mcontrol6 { bits: self.bits }),
+            Some(Type::Icount) => Trigger::Icount(Icount { bits: self.bits }),
+            Some(Type::Itrigger) => Trigger::Itrigger(Itrigger { bits: self.bits }),
+            Some(Type::Etrigger) => Trigger::Etrigger(Etrigger { bits: self.bits }),
+            Some(Type::None) => Trigger::None,
+            Some(Type::Disabled) => Trigger::Disabled,
+            _ => Trigger::Other(self.bits),
+        }
+    }
+}
+
+/// Decoded `tdata1` contents
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Trigger {
+    /// No trigger exists at this index
+    None,
+    /// Trigger exists but is currently disabled
+    Disabled,
+    Mcontrol(Mcontrol),
+    Mcontrol6(Mcontrol6),
+    Icount(Icount),
+    Itrigger(Itrigger),
+    Etrigger(Etrigger),
+    /// Trigger type without a decoder, with the raw `tdata1` bits
+    Other(usize),
+}
+
+/// Address/data match trigger (type 2)
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Mcontrol {
+    bits: usize,
+}
+
+impl Mcontrol {
+    /// Trigger has fired
+    #[inline]
+    pub fn hit(&self) -> bool {
+        self.bits.get_bit(20)
+    }
+
+    /// Match on the data value instead of the address
+    #[inline]
+    pub fn select(&self) -> bool {
+        self.bits.get_bit(19)
+    }
+
+    /// Fire after the instruction executes instead of before
+    #[inline]
+    pub fn timing(&self) -> bool {
+        self.bits.get_bit(18)
+    }
+
+    /// Action taken when the trigger fires, or `None` if reserved
+    #[inline]
+    pub fn action(&self) -> Option<Action> {
+        Action::from(self.bits.get_bits(12..16))
+    }
+
+    /// Trigger is chained with the next one
+    #[inline]
+    pub fn chain(&self) -> bool {
+        self.bits.get_bit(11)
+    }
+
+    /// Comparison performed, or `None` if reserved
+    #[inline]
+    pub fn match_type(&self) -> Option<Match> {
+        Match::from(self.bits.get_bits(7..11))
+    }
+
+    /// Enabled in M-mode
+    #[inline]
+    pub fn m(&self) -> bool {
+        self.bits.get_bit(6)
+    }
+
+    /// Enabled in S-mode
+    #[inline]
+    pub fn s(&self) -> bool {
+        self.bits.get_bit(4)
+    }
+
+    /// Enabled in U-mode
+    #[inline]
+    pub fn u(&self) -> bool {
+        self.bits.get_bit(3)
+    }
+
+    /// Matches instruction fetches
+    #[inline]
+    pub fn execute(&self) -> bool {
+        self.bits.get_bit(2)
+    }
+
+    /// Matches stores
+    #[inline]
+    pub fn store(&self) -> bool {
+        self.bits.get_bit(1)
+    }
+
+    /// Matches loads
+    #[inline]
+    pub fn load(&self) -> bool {
+        self.bits.get_bit(0)
+    }
+}
+
+/// Address/data match trigger, Sdtrig 1.0 layout (type 6)
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Mcontrol6 {
+    bits: usize,
+}
+
+impl Mcontrol6 {
+    /// Trigger has fired (`hit1:hit0`)
+    #[inline]
+    pub fn hit(&self) -> usize {
+        (self.bits.get_bit(25) as usize) << 1 | self.bits.get_bit(22) as usize
+    }
+
+    /// Enabled in VS-mode
+    #[inline]
+    pub fn vs(&self) -> bool {
+        self.bits.get_bit(24)
+    }
+
+    /// Enabled in VU-mode
+    #[inline]
+    pub fn vu(&self) -> bool {
+        self.bits.get_bit(23)
+    }
+
+    /// Match on the data value instead of the address
+    #[inline]
+    pub fn select(&self) -> bool {
+        self.bits.get_bit(21)
+    }
+
+    /// Access size matched (0 = any)
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.bits.get_bits(16..19)
+    }
+
+    /// Action taken when the trigger fires, or `None` if reserved
+    #[inline]
+    pub fn action(&self) -> Option<Action> {
+        Action::from(self.bits.get_bits(12..16))
+    }
+
+    /// Trigger is chained with the next one
+    #[inline]
+    pub fn chain(&self) -> bool {
+        self.bits.get_bit(11)
+    }
+
+    /// Comparison performed, or `None` if reserved
+    #[inline]
+    pub fn match_type(&self) -> Option<Match> {
+        Match::from(self.bits.get_bits(7..11))
+    }
+
+    /// Enabled in M-mode
+    #[inline]
+    pub fn m(&self) -> bool {
+        self.bits.get_bit(6)
+    }
+
+    /// Enabled in S-mode
+    #[inline]
+    pub fn s(&self) -> bool {
+        self.bits.get_bit(4)
+    }
+
+    /// Enabled in U-mode
+    #[inline]
+    pub fn u(&self) -> bool {
+        self.bits.get_bit(3)
+    }
+
+    /// Matches instruction fetches
+    #[inline]
+    pub fn execute(&self) -> bool {
+        self.bits.get_bit(2)
+    }
+
+    /// Matches stores
+    #[inline]
+    pub fn store(&self) -> bool {
+        self.bits.get_bit(1)
+    }
+
+    /// Matches loads
+    #[inline]
+    pub fn load(&self) -> bool {
+        self.bits.get_bit(0)
+    }
+}
+
+/// Instruction count trigger (type 3)
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Icount {
+    bits: usize,
+}
+
+impl Icount {
+    /// Enabled in VS-mode
+    #[inline]
+    pub fn vs(&self) -> bool {
+        self.bits.get_bit(26)
+    }
+
+    /// Enabled in VU-mode
+    #[inline]
+    pub fn vu(&self) -> bool {
+        self.bits.get_bit(25)
+    }
+
+    /// Trigger has fired
+    #[inline]
+    pub fn hit(&self) -> bool {
+        self.bits.get_bit(24)
+    }
+
+    /// Remaining instructions until the trigger fires
+    #[inline]
+    pub fn count(&self) -> usize {
+        self.bits.get_bits(10..24)
+    }
+
+    /// Enabled in M-mode
+    #[inline]
+    pub fn m(&self) -> bool {
+        self.bits.get_bit(9)
+    }
+
+    /// Trigger fires once the current instruction retires
+    #[inline]
+    pub fn pending(&self) -> bool {
+        self.bits.get_bit(8)
+    }
+
+    /// Enabled in S-mode
+    #[inline]
+    pub fn s(&self) -> bool {
+        self.bits.get_bit(7)
+    }
+
+    /// Enabled in U-mode
+    #[inline]
+    pub fn u(&self) -> bool {
+        self.bits.get_bit(6)
+    }
+
+    /// Action taken when the trigger fires, or `None` if reserved
+    #[inline]
+    pub fn action(&self) -> Option<Action> {
+        Action::from(self.bits.get_bits(0..6))
+    }
+}
+
+/// Interrupt trigger (type 4)
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Itrigger {
+    bits: usize,
+}
+
+impl Itrigger {
+    /// Trigger has fired
+    #[inline]
+    pub fn hit(&self) -> bool {
+        self.bits.get_bit(XLEN - 6)
+    }
+
+    /// Enabled in VS-mode
+    #[inline]
+    pub fn vs(&self) -> bool {
+        self.bits.get_bit(12)
+    }
+
+    /// Enabled in VU-mode
+    #[inline]
+    pub fn vu(&self) -> bool {
+        self.bits.get_bit(11)
+    }
+
+    /// Fires on non-maskable interrupts
+    #[inline]
+    pub fn nmi(&self) -> bool {
+        self.bits.get_bit(10)
+    }
+
+    /// Enabled in M-mode
+    #[inline]
+    pub fn m(&self) -> bool {
+        self.bits.get_bit(9)
+    }
+
+    /// Enabled in S-mode
+    #[inline]
+    pub fn s(&self) -> bool {
+        self.bits.get_bit(7)
+    }
+
+    /// Enabled in U-mode
+    #[inline]
+    pub fn u(&self) -> bool {
+        self.bits.get_bit(6)
+    }
+
+    /// Action taken when the trigger fires, or `None` if reserved
+    #[inline]
+    pub fn action(&self) -> Option<Action> {
+        Action::from(self.bits.get_bits(0..6))
+    }
+}
+
+/// Exception trigger (type 5)
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Etrigger {
+    bits: usize,
+}
+
+impl Etrigger {
+    /// Trigger has fired
+    #[inline]
+    pub fn hit(&self) -> bool {
+        self.bits.get_bit(XLEN - 6)
+    }
+
+    /// Enabled in VS-mode
+    #[inline]
+    pub fn vs(&self) -> bool {
+        self.bits.get_bit(12)
+    }
+
+    /// Enabled in VU-mode
+    #[inline]
+    pub fn vu(&self) -> bool {
+        self.bits.get_bit(11)
+    }
+
+    /// Enabled in M-mode
+    #[inline]
+    pub fn m(&self) -> bool {
+        self.bits.get_bit(9)
+    }
+
+    /// Enabled in S-mode
+    #[inline]
+    pub fn s(&self) -> bool {
+        self.bits.get_bit(7)
+    }
+
+    /// Enabled in U-mode
+    #[inline]
+    pub fn u(&self) -> bool {
+        self.bits.get_bit(6)
+    }
+
+    /// Action taken when the trigger fires, or `None` if reserved
+    #[inline]
+    pub fn action(&self) -> Option<Action> {
+        Action::from(self.bits.get_bits(0..6))
+    }
+}
+
+/// Selects trigger `index`, returning `false` if it is not implemented
+#[inline]
+pub fn select(index: usize) -> bool {
+    tselect::write(index);
+    tselect::read() == index
+}
+
+/// Reads `tdata1` of the selected trigger
+#[inline]
+pub fn read() -> Tdata1 {
+    Tdata1 {
+        bits: tdata1::read(),
+    }
+}
+
+/// Returns the number of implemented triggers
+pub fn count() -> usize {
+    let mut index = 0;
+    while select(index) && read().trigger_type() != Some(Type::None) {
+        index += 1;
+    }
+    index
+}
+
+/// Disables trigger `index`
+///
+/// # Safety
+///
+/// Removes a breakpoint or watchpoint that other code may rely on.
+#[inline]
+pub unsafe fn disarm(index: usize) {
+    if select(index) {
+        tdata1::write(0);
+    }
+}
+
+/// Builder for an address/data match trigger
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MatchTrigger {
+    value: usize,
+    select: bool,
+    execute: bool,
+    load: bool,
+    store: bool,
+    m: bool,
+    s: bool,
+    u: bool,
+    action: Action,
+    matching: Match,
+}
+
+impl MatchTrigger {
+    #[inline]
+    fn new(address: usize) -> Self {
+        MatchTrigger {
+            value: address,
+            select: false,
+            execute: false,
+            load: false,
+            store: false,
+            m: true,
+            s: true,
+            u: true,
+            action: Action::Breakpoint,
+            matching: Match::Equal,
+        }
+    }
+
+    /// Breakpoint on instruction fetches from `address`
+    #[inline]
+    pub fn execute(address: usize) -> Self {
+        MatchTrigger {
+            execute: true,
+            ..Self::new(address)
+        }
+    }
+
+    /// Watchpoint on loads from `address`
+    #[inline]
+    pub fn load(address: usize) -> Self {
+        MatchTrigger {
+            load: true,
+            ..Self::new(address)
+        }
+    }
+
+    /// Watchpoint on stores to `address`
+    #[inline]
+    pub fn store(address: usize) -> Self {
+        MatchTrigger {
+            store: true,
+            ..Self::new(address)
+        }
+    }
+
+    /// Watchpoint on loads and stores of `address`
+    #[inline]
+    pub fn access(address: usize) -> Self {
+        MatchTrigger {
+            load: true,
+            store: true,
+            ..Self::new(address)
+        }
+    }
+
+    /// Match the accessed data value against `value` instead of the address
+    #[inline]
+    pub fn data(self, value: usize) -> Self {
+        MatchTrigger {
+            value,
+            select: true,
+            ..self
+        }
+    }
+
+    /// Privilege modes in which the trigger is enabled
+    #[inline]
+    pub fn modes(self, m: bool, s: bool, u: bool) -> Self {
+        MatchTrigger { m, s, u, ..self }
+    }
+
+    /// Action taken when the trigger fires
+    #[inline]
+    pub fn action(self, action: Action) -> Self {
+        MatchTrigger { action, ..self }
+    }
+
+    /// Comparison performed against the programmed value
+    #[inline]
+    pub fn matching(self, matching: Match) -> Self {
+        MatchTrigger { matching, ..self }
+    }
+
+    #[inline]
+    fn common_bits(&self) -> usize {
+        let mut bits = 0usize;
+        bits.set_bits(12..16, self.action as usize);
+        bits.set_bits(7..11, self.matching as usize);
+        bits.set_bit(6, self.m);
+        bits.set_bit(4, self.s);
+        bits.set_bit(3, self.u);
+        bits.set_bit(2, self.execute);
+        bits.set_bit(1, self.store);
+        bits.set_bit(0, self.load);
+        bits
+    }
+
+    /// Programs trigger `index`, returning the trigger type used
+    ///
+    /// The Sdtrig 1.0 `mcontrol6` layout is tried first, falling back to
+    /// `mcontrol`. Returns `None` if the trigger does not exist or supports
+    /// neither type.
+    ///
+    /// # Safety
+    ///
+    /// Overwrites any breakpoint or watchpoint already programmed at `index`.
+    pub unsafe fn arm(self, index: usize) -> Option<Type> {
+        if !select(index) {
+            return None;
+        }
+        tdata1::write(0);
+        tdata2::write(self.value);
+
+        let mut bits = self.common_bits();
+        bits.set_bits(XLEN - 4..XLEN, Type::Mcontrol6 as usize);
+        bits.set_bit(21, self.select);
+        tdata1::write(bits);
+        if read().trigger_type() == Some(Type::Mcontrol6) {
+            return Some(Type::Mcontrol6);
+        }
+
+        let mut bits = self.common_bits();
+        bits.set_bits(XLEN - 4..XLEN, Type::Mcontrol as usize);
+        bits.set_bit(19, self.select);
+        tdata1::write(bits);
+        if read().trigger_type() == Some(Type::Mcontrol) {
+            return Some(Type::Mcontrol);
+        }
+
+        tdata1::write(0);
+        None
+    }
+}
@@ -0,0 +1,4 @@
+//! tselect register
+
+read_csr_as_usize!(0x7A0, __read_tselect);
+write_csr_as_usize!(0x7A0, __write_tselect);
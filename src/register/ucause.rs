@@ -2,6 +2,7 @@
 
 /// ucause register
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Ucause {
     bits: usize,
 }
@@ -4,6 +4,7 @@ use bit_field::BitField;
 
 /// uip register
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Uip {
     bits: usize,
 }
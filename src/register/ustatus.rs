@@ -5,6 +5,7 @@ use bit_field::BitField;
 
 /// ustatus register
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Ustatus {
     bits: usize,
 }
@@ -4,6 +4,7 @@ pub use crate::register::mtvec::TrapMode;
 
 /// stvec register
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Utvec {
     bits: usize,
 }
@@ -0,0 +1,41 @@
+//! vcsr register
+
+use bit_field::BitField;
+
+pub use super::vxrm::RoundingMode;
+
+/// Vector control and status register
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Vcsr {
+    bits: usize,
+}
+
+impl Vcsr {
+    /// Returns the contents of the register as raw bits
+    #[inline]
+    pub fn bits(&self) -> usize {
+        self.bits
+    }
+
+    /// Fixed-point saturation flag
+    #[inline]
+    pub fn vxsat(&self) -> bool {
+        self.bits.get_bit(0)
+    }
+
+    /// Fixed-point rounding mode
+    #[inline]
+    pub fn vxrm(&self) -> RoundingMode {
+        RoundingMode::from(self.bits.get_bits(1..3))
+    }
+}
+
+read_csr_as!(Vcsr, 0x00F, __read_vcsr);
+write_csr!(0x00F, __write_vcsr);
+
+/// Writes the CSR
+#[inline]
+pub unsafe fn write(vxrm: RoundingMode, vxsat: bool) {
+    _write(((vxrm as usize) << 1) | vxsat as usize);
+}
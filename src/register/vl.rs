@@ -0,0 +1,3 @@
+//! vl register
+
+read_csr_as_usize!(0xC20, __read_vl);
@@ -0,0 +1,3 @@
+//! vlenb register
+
+read_csr_as_usize!(0xC22, __read_vlenb);
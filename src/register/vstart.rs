@@ -0,0 +1,4 @@
+//! vstart register
+
+read_csr_as_usize!(0x008, __read_vstart);
+write_csr_as_usize!(0x008, __write_vstart);
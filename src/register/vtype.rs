@@ -0,0 +1,97 @@
+//! vtype register
+
+use bit_field::BitField;
+use core::mem::size_of;
+
+/// Vector data type register
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Vtype {
+    bits: usize,
+}
+
+/// Selected element width
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Sew {
+    E8 = 0b000,
+    E16 = 0b001,
+    E32 = 0b010,
+    E64 = 0b011,
+}
+
+/// Vector register group multiplier
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Lmul {
+    M1 = 0b000,
+    M2 = 0b001,
+    M4 = 0b010,
+    M8 = 0b011,
+    MF8 = 0b101,
+    MF4 = 0b110,
+    MF2 = 0b111,
+}
+
+impl Sew {
+    /// Element width in bits
+    #[inline]
+    pub fn bits(self) -> usize {
+        8 << self as usize
+    }
+}
+
+impl Vtype {
+    /// Returns the contents of the register as raw bits
+    #[inline]
+    pub fn bits(&self) -> usize {
+        self.bits
+    }
+
+    /// Vector register group multiplier, or `None` if reserved
+    #[inline]
+    pub fn vlmul(&self) -> Option<Lmul> {
+        match self.bits.get_bits(0..3) {
+            0b000 => Some(Lmul::M1),
+            0b001 => Some(Lmul::M2),
+            0b010 => Some(Lmul::M4),
+            0b011 => Some(Lmul::M8),
+            0b101 => Some(Lmul::MF8),
+            0b110 => Some(Lmul::MF4),
+            0b111 => Some(Lmul::MF2),
+            _ => None,
+        }
+    }
+
+    /// Selected element width, or `None` if reserved
+    #[inline]
+    pub fn vsew(&self) -> Option<Sew> {
+        match self.bits.get_bits(3..6) {
+            0b000 => Some(Sew::E8),
+            0b001 => Some(Sew::E16),
+            0b010 => Some(Sew::E32),
+            0b011 => Some(Sew::E64),
+            _ => None,
+        }
+    }
+
+    /// Vector tail agnostic
+    #[inline]
+    pub fn vta(&self) -> bool {
+        self.bits.get_bit(6)
+    }
+
+    /// Vector mask agnostic
+    #[inline]
+    pub fn vma(&self) -> bool {
+        self.bits.get_bit(7)
+    }
+
+    /// Illegal value: set when a `vset{i}vl{i}` requested an unsupported configuration
+    #[inline]
+    pub fn vill(&self) -> bool {
+        self.bits.get_bit(size_of::<usize>() * 8 - 1)
+    }
+}
+
+read_csr_as!(Vtype, 0xC21, __read_vtype);
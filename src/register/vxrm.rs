@@ -0,0 +1,42 @@
+//! vxrm register
+
+/// Fixed-point rounding mode
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum RoundingMode {
+    /// Round-to-nearest-up
+    RoundToNearestUp = 0b00,
+    /// Round-to-nearest-even
+    RoundToNearestEven = 0b01,
+    /// Round-down (truncate)
+    RoundDown = 0b10,
+    /// Round-to-odd
+    RoundToOdd = 0b11,
+}
+
+impl From<usize> for RoundingMode {
+    #[inline]
+    fn from(bits: usize) -> Self {
+        match bits & 0b11 {
+            0b00 => RoundingMode::RoundToNearestUp,
+            0b01 => RoundingMode::RoundToNearestEven,
+            0b10 => RoundingMode::RoundDown,
+            _ => RoundingMode::RoundToOdd,
+        }
+    }
+}
+
+read_csr!(0x00A, __read_vxrm);
+write_csr!(0x00A, __write_vxrm);
+
+/// Reads the CSR
+#[inline]
+pub fn read() -> RoundingMode {
+    RoundingMode::from(unsafe { _read() })
+}
+
+/// Writes the CSR
+#[inline]
+pub unsafe fn write(mode: RoundingMode) {
+    _write(mode as usize);
+}
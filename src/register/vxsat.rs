@@ -0,0 +1,23 @@
+//! vxsat register
+
+read_csr!(0x009, __read_vxsat);
+set!(0x009, __set_vxsat);
+clear!(0x009, __clear_vxsat);
+
+/// Reads the fixed-point saturation flag
+#[inline]
+pub fn read() -> bool {
+    unsafe { _read() & 1 != 0 }
+}
+
+/// Sets the fixed-point saturation flag
+#[inline]
+pub unsafe fn set() {
+    _set(1);
+}
+
+/// Clears the fixed-point saturation flag
+#[inline]
+pub unsafe fn clear() {
+    _clear(1);
+}
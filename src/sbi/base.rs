@@ -0,0 +1,115 @@
+//! Base extension
+//!
+//! Always implemented by SBI v0.2 and later; its calls cannot fail.
+
+use super::call;
+
+/// Extension ID
+pub const EID: usize = 0x10;
+
+const GET_SPEC_VERSION: usize = 0;
+const GET_IMPL_ID: usize = 1;
+const GET_IMPL_VERSION: usize = 2;
+const PROBE_EXTENSION: usize = 3;
+const GET_MVENDORID: usize = 4;
+const GET_MARCHID: usize = 5;
+const GET_MIMPID: usize = 6;
+
+/// Version of the SBI specification
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Version {
+    pub major: usize,
+    pub minor: usize,
+}
+
+/// SBI implementation IDs assigned by the specification
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ImplId {
+    BerkeleyBootLoader,
+    OpenSbi,
+    Xvisor,
+    Kvm,
+    RustSbi,
+    Diosix,
+    Coffer,
+    XenProject,
+    PolarFireHartSoftwareServices,
+    Coreboot,
+    Oreboot,
+    Bhyve,
+    /// ID not known to this crate
+    Other(usize),
+}
+
+impl ImplId {
+    /// Decodes a raw implementation ID
+    #[inline]
+    pub fn from_raw(id: usize) -> Self {
+        match id {
+            0 => ImplId::BerkeleyBootLoader,
+            1 => ImplId::OpenSbi,
+            2 => ImplId::Xvisor,
+            3 => ImplId::Kvm,
+            4 => ImplId::RustSbi,
+            5 => ImplId::Diosix,
+            6 => ImplId::Coffer,
+            7 => ImplId::XenProject,
+            8 => ImplId::PolarFireHartSoftwareServices,
+            9 => ImplId::Coreboot,
+            10 => ImplId::Oreboot,
+            11 => ImplId::Bhyve,
+            _ => ImplId::Other(id),
+        }
+    }
+}
+
+#[inline]
+fn base_call(fid: usize, arg: usize) -> usize {
+    call(EID, fid, [arg, 0, 0, 0, 0, 0]).value
+}
+
+/// Returns the version of the SBI specification implemented by the firmware
+#[inline]
+pub fn get_spec_version() -> Version {
+    let version = base_call(GET_SPEC_VERSION, 0);
+    Version {
+        major: (version >> 24) & 0x7f,
+        minor: version & 0xff_ffff,
+    }
+}
+
+/// Returns the ID of the SBI implementation
+#[inline]
+pub fn get_impl_id() -> ImplId {
+    ImplId::from_raw(base_call(GET_IMPL_ID, 0))
+}
+
+/// Returns the implementation-specific version of the SBI implementation
+#[inline]
+pub fn get_impl_version() -> usize {
+    base_call(GET_IMPL_VERSION, 0)
+}
+
+/// Returns true if the extension `eid` is implemented
+#[inline]
+pub fn probe_extension(eid: usize) -> bool {
+    base_call(PROBE_EXTENSION, eid) != 0
+}
+
+/// Returns the `mvendorid` of the calling hart
+#[inline]
+pub fn get_mvendorid() -> usize {
+    base_call(GET_MVENDORID, 0)
+}
+
+/// Returns the `marchid` of the calling hart
+#[inline]
+pub fn get_marchid() -> usize {
+    base_call(GET_MARCHID, 0)
+}
+
+/// Returns the `mimpid` of the calling hart
+#[inline]
+pub fn get_mimpid() -> usize {
+    base_call(GET_MIMPID, 0)
+}
@@ -0,0 +1,90 @@
+//! Console
+//!
+//! The debug console (DBCN) extension, the legacy `console_putchar` and
+//! `console_getchar` calls, and [`Console`], a `fmt::Write` adapter that
+//! uses whichever the firmware implements.
+
+use core::fmt;
+
+use super::{call, legacy_call, split_u64, Error, Result};
+
+/// Extension ID of the debug console extension
+pub const EID: usize = 0x4442_434E;
+
+const CONSOLE_WRITE: usize = 0;
+const CONSOLE_READ: usize = 1;
+const CONSOLE_WRITE_BYTE: usize = 2;
+
+const LEGACY_CONSOLE_PUTCHAR: usize = 0x01;
+const LEGACY_CONSOLE_GETCHAR: usize = 0x02;
+
+/// Writes up to `len` bytes at physical address `phys_addr` to the debug
+/// console and returns the number of bytes written
+///
+/// # Safety
+///
+/// `phys_addr` must be the physical address of `len` readable bytes.
+#[inline]
+pub unsafe fn write(phys_addr: usize, len: usize) -> Result<usize> {
+    let [lo, hi] = split_u64(phys_addr as u64);
+    call(EID, CONSOLE_WRITE, [len, lo, hi, 0, 0, 0]).into_result()
+}
+
+/// Reads up to `len` bytes from the debug console to physical address
+/// `phys_addr` without blocking and returns the number of bytes read
+///
+/// # Safety
+///
+/// `phys_addr` must be the physical address of `len` writable bytes.
+#[inline]
+pub unsafe fn read(phys_addr: usize, len: usize) -> Result<usize> {
+    let [lo, hi] = split_u64(phys_addr as u64);
+    call(EID, CONSOLE_READ, [len, lo, hi, 0, 0, 0]).into_result()
+}
+
+/// Writes a byte to the debug console, blocking until it is written
+///
+/// Falls back to the legacy `console_putchar` if the firmware does not
+/// implement the debug console extension.
+#[inline]
+pub fn write_byte(byte: u8) -> Result<()> {
+    match call(EID, CONSOLE_WRITE_BYTE, [byte as usize, 0, 0, 0, 0, 0]).into_result() {
+        Err(Error::NotSupported) => {
+            legacy_putchar(byte);
+            Ok(())
+        }
+        result => result.map(|_| ()),
+    }
+}
+
+/// Writes a byte with the legacy `console_putchar` call
+#[inline]
+pub fn legacy_putchar(byte: u8) {
+    legacy_call(LEGACY_CONSOLE_PUTCHAR, [byte as usize, 0, 0, 0]);
+}
+
+/// Reads a byte with the legacy `console_getchar` call, returns `None` if
+/// no byte is available
+#[inline]
+pub fn legacy_getchar() -> Option<u8> {
+    match legacy_call(LEGACY_CONSOLE_GETCHAR, [0; 4]) {
+        c if c >= 0 => Some(c as u8),
+        _ => None,
+    }
+}
+
+/// `fmt::Write` adapter for the SBI console
+///
+/// Writes byte by byte, so it needs no physical addresses and works before
+/// paging is set up, e.g. for early boot messages.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Console;
+
+impl fmt::Write for Console {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for byte in s.bytes() {
+            write_byte(byte).map_err(|_| fmt::Error)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+//! Hart state management (HSM) extension
+
+use super::{call, Error, Result};
+
+/// Extension ID
+pub const EID: usize = 0x48_534D;
+
+const HART_START: usize = 0;
+const HART_STOP: usize = 1;
+const HART_GET_STATUS: usize = 2;
+const HART_SUSPEND: usize = 3;
+
+/// State of a hart
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum HartState {
+    Started = 0,
+    Stopped = 1,
+    StartPending = 2,
+    StopPending = 3,
+    Suspended = 4,
+    SuspendPending = 5,
+    ResumePending = 6,
+}
+
+/// Type of a hart suspension
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SuspendType {
+    /// Default retentive suspend: `hart_suspend` returns on resumption
+    DefaultRetentive,
+    /// Default non-retentive suspend: the hart resumes at `resume_addr`
+    DefaultNonRetentive,
+    /// Platform-specific suspend type; bit 31 selects non-retentive
+    Platform(u32),
+}
+
+impl SuspendType {
+    /// Returns the raw suspend type
+    #[inline]
+    pub fn bits(self) -> u32 {
+        match self {
+            SuspendType::DefaultRetentive => 0x0000_0000,
+            SuspendType::DefaultNonRetentive => 0x8000_0000,
+            SuspendType::Platform(bits) => bits,
+        }
+    }
+
+    /// Returns true if the hart state is lost and it resumes at
+    /// `resume_addr`
+    #[inline]
+    pub fn is_non_retentive(self) -> bool {
+        self.bits() & 0x8000_0000 != 0
+    }
+}
+
+/// Starts hart `hartid` in S-mode at `start_addr`
+///
+/// The hart starts with `a0` holding its hart ID, `a1` holding `opaque`,
+/// translation disabled and interrupts disabled.
+///
+/// # Safety
+///
+/// `start_addr` must be the physical address of code that can run in this
+/// state.
+#[inline]
+pub unsafe fn hart_start(hartid: usize, start_addr: usize, opaque: usize) -> Result<()> {
+    call(EID, HART_START, [hartid, start_addr, opaque, 0, 0, 0])
+        .into_result()
+        .map(|_| ())
+}
+
+/// Stops the calling hart and returns it to the SBI implementation
+///
+/// Only returns if the hart could not be stopped.
+#[inline]
+pub fn hart_stop() -> Error {
+    match call(EID, HART_STOP, [0; 6]).into_result() {
+        Err(error) => error,
+        Ok(_) => Error::Failed,
+    }
+}
+
+/// Returns the current state of hart `hartid`
+#[inline]
+pub fn hart_get_status(hartid: usize) -> Result<HartState> {
+    let state = call(EID, HART_GET_STATUS, [hartid, 0, 0, 0, 0, 0]).into_result()?;
+    Ok(match state {
+        0 => HartState::Started,
+        1 => HartState::Stopped,
+        2 => HartState::StartPending,
+        3 => HartState::StopPending,
+        4 => HartState::Suspended,
+        5 => HartState::SuspendPending,
+        6 => HartState::ResumePending,
+        _ => return Err(Error::Failed),
+    })
+}
+
+/// Suspends the calling hart until an interrupt or platform event
+///
+/// A retentive suspend returns `Ok(())` on resumption. After a
+/// non-retentive suspend the hart resumes like `hart_start` at
+/// `resume_addr` with `opaque` in `a1`, and this call only returns on error.
+///
+/// # Safety
+///
+/// For non-retentive suspend types, `resume_addr` must be the physical
+/// address of code that can restore the hart's state.
+#[inline]
+pub unsafe fn hart_suspend(
+    suspend_type: SuspendType,
+    resume_addr: usize,
+    opaque: usize,
+) -> Result<()> {
+    call(
+        EID,
+        HART_SUSPEND,
+        [suspend_type.bits() as usize, resume_addr, opaque, 0, 0, 0],
+    )
+    .into_result()
+    .map(|_| ())
+}
@@ -0,0 +1,33 @@
+//! IPI extension
+
+use super::{call, legacy_call, Error, HartMask, Result};
+
+/// Extension ID
+pub const EID: usize = 0x73_5049;
+
+const SEND_IPI: usize = 0;
+const LEGACY_SEND_IPI: usize = 0x04;
+
+/// Raises a supervisor software interrupt on the harts in `hart_mask`
+///
+/// Falls back to the legacy call if the firmware does not implement the IPI
+/// extension and the harts fit into its mask.
+#[inline]
+pub fn send_ipi(hart_mask: HartMask) -> Result<()> {
+    match call(
+        EID,
+        SEND_IPI,
+        [hart_mask.mask(), hart_mask.base(), 0, 0, 0, 0],
+    )
+    .into_result()
+    {
+        Err(Error::NotSupported) => {
+            let mask = hart_mask.legacy_mask().ok_or(Error::InvalidParam)?;
+            match legacy_call(LEGACY_SEND_IPI, [&mask as *const usize as usize, 0, 0, 0]) {
+                0 => Ok(()),
+                code => Err(Error::from_code(code)),
+            }
+        }
+        result => result.map(|_| ()),
+    }
+}
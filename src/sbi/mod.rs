@@ -0,0 +1,239 @@
+//! Supervisor Binary Interface (SBI)
+//!
+//! Typed bindings for the calls an S-mode kernel makes into the SEE (e.g.
+//! OpenSBI) with `ECALL`. Every extension lives in its own module; use
+//! [`base::probe_extension`] to find out which ones the firmware implements.
+
+use asm;
+use core::fmt;
+
+pub mod base;
+pub mod console;
+pub mod hsm;
+pub mod ipi;
+pub mod pmu;
+pub mod rfence;
+pub mod timer;
+
+/// Values returned by an SBI call in `a0` and `a1`
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct SbiRet {
+    /// Error code, 0 on success
+    pub error: isize,
+    /// Return value
+    pub value: usize,
+}
+
+/// Error returned by an SBI call
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Error {
+    Failed,
+    NotSupported,
+    InvalidParam,
+    Denied,
+    InvalidAddress,
+    AlreadyAvailable,
+    AlreadyStarted,
+    AlreadyStopped,
+    NoShmem,
+    InvalidState,
+    BadRange,
+    Timeout,
+    Io,
+    /// Error code not defined by the specification
+    Unknown(isize),
+}
+
+impl Error {
+    /// Decodes a non-zero SBI error code
+    #[inline]
+    pub fn from_code(code: isize) -> Self {
+        match code {
+            -1 => Error::Failed,
+            -2 => Error::NotSupported,
+            -3 => Error::InvalidParam,
+            -4 => Error::Denied,
+            -5 => Error::InvalidAddress,
+            -6 => Error::AlreadyAvailable,
+            -7 => Error::AlreadyStarted,
+            -8 => Error::AlreadyStopped,
+            -9 => Error::NoShmem,
+            -10 => Error::InvalidState,
+            -11 => Error::BadRange,
+            -12 => Error::Timeout,
+            -13 => Error::Io,
+            _ => Error::Unknown(code),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Error::Failed => "SBI call failed",
+            Error::NotSupported => "SBI call not supported",
+            Error::InvalidParam => "invalid parameter",
+            Error::Denied => "denied",
+            Error::InvalidAddress => "invalid address",
+            Error::AlreadyAvailable => "already available",
+            Error::AlreadyStarted => "already started",
+            Error::AlreadyStopped => "already stopped",
+            Error::NoShmem => "shared memory not available",
+            Error::InvalidState => "invalid state",
+            Error::BadRange => "bad range",
+            Error::Timeout => "timed out",
+            Error::Io => "I/O error",
+            Error::Unknown(code) => return write!(f, "unknown SBI error {}", code),
+        })
+    }
+}
+
+#[cfg(feature = "core-error")]
+impl core::error::Error for Error {}
+
+/// Result of an SBI call
+pub type Result<T> = core::result::Result<T, Error>;
+
+impl SbiRet {
+    /// Returns `value` on success and the decoded error otherwise
+    #[inline]
+    pub fn into_result(self) -> Result<usize> {
+        match self.error {
+            0 => Ok(self.value),
+            code => Err(Error::from_code(code)),
+        }
+    }
+}
+
+/// Set of harts addressed by an SBI call
+///
+/// Bit `n` of `mask` selects hart `base + n`; a mask can therefore address
+/// up to XLEN consecutive hart IDs. Calls covering more harts must be split.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct HartMask {
+    mask: usize,
+    base: usize,
+}
+
+impl HartMask {
+    /// Creates an empty mask starting at hart `base`
+    #[inline]
+    pub const fn new(base: usize) -> Self {
+        HartMask { mask: 0, base }
+    }
+
+    /// Creates a mask from its raw parts
+    #[inline]
+    pub const fn from_mask_base(mask: usize, base: usize) -> Self {
+        HartMask { mask, base }
+    }
+
+    /// Creates a mask addressing every hart
+    #[inline]
+    pub const fn all() -> Self {
+        HartMask {
+            mask: 0,
+            base: usize::MAX,
+        }
+    }
+
+    /// Adds `hart`, returns `None` if it cannot be addressed from `base`
+    #[inline]
+    pub fn with(mut self, hart: usize) -> Option<Self> {
+        if self.insert(hart) {
+            Some(self)
+        } else {
+            None
+        }
+    }
+
+    /// Adds `hart`, returns false if it cannot be addressed from `base`
+    #[inline]
+    pub fn insert(&mut self, hart: usize) -> bool {
+        if self.is_all() {
+            return true;
+        }
+        match hart.checked_sub(self.base) {
+            Some(bit) if bit < usize::BITS as usize => {
+                self.mask |= 1 << bit;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns true if the mask addresses every hart
+    #[inline]
+    pub fn is_all(&self) -> bool {
+        self.base == usize::MAX
+    }
+
+    /// Returns true if `hart` is addressed
+    #[inline]
+    pub fn contains(&self, hart: usize) -> bool {
+        self.is_all()
+            || match hart.checked_sub(self.base) {
+                Some(bit) if bit < usize::BITS as usize => self.mask & (1 << bit) != 0,
+                _ => false,
+            }
+    }
+
+    /// Returns the raw `hart_mask` argument
+    #[inline]
+    pub fn mask(&self) -> usize {
+        self.mask
+    }
+
+    /// Returns the raw `hart_mask_base` argument
+    #[inline]
+    pub fn base(&self) -> usize {
+        self.base
+    }
+
+    /// Returns the mask relative to hart 0 expected by the legacy calls, or
+    /// `None` if the addressed harts do not fit
+    #[inline]
+    fn legacy_mask(&self) -> Option<usize> {
+        if self.is_all() {
+            return Some(usize::MAX);
+        }
+        if self.base < usize::BITS as usize && self.mask.leading_zeros() as usize >= self.base {
+            Some(self.mask << self.base)
+        } else {
+            None
+        }
+    }
+}
+
+/// Calls function `fid` of extension `eid` with up to six arguments
+#[inline]
+fn call(eid: usize, fid: usize, args: [usize; 6]) -> SbiRet {
+    let ret = unsafe {
+        asm::ecall([
+            args[0], args[1], args[2], args[3], args[4], args[5], fid, eid,
+        ])
+    };
+    SbiRet {
+        error: ret.a0 as isize,
+        value: ret.a1,
+    }
+}
+
+/// Calls the legacy (v0.1) extension `eid`, which returns only an error code
+/// or value in `a0`
+#[inline]
+fn legacy_call(eid: usize, args: [usize; 4]) -> isize {
+    let ret = unsafe { asm::ecall([args[0], args[1], args[2], args[3], 0, 0, 0, eid]) };
+    ret.a0 as isize
+}
+
+/// Splits a 64-bit argument into the registers the calling convention uses
+/// for it: one register on RV64, the low and high halves on RV32
+#[inline]
+fn split_u64(value: u64) -> [usize; 2] {
+    if core::mem::size_of::<usize>() == 8 {
+        [value as usize, 0]
+    } else {
+        [value as usize, (value >> 32) as usize]
+    }
+}
@@ -0,0 +1,201 @@
+//! Performance monitoring unit (PMU) extension
+//!
+//! Counters are addressed by a logical index assigned by the firmware, and
+//! sets of counters by a base index and a mask relative to it, like harts in
+//! a [`HartMask`](super::HartMask).
+
+use super::{call, split_u64, Result};
+
+/// Extension ID
+pub const EID: usize = 0x50_4D55;
+
+const NUM_COUNTERS: usize = 0;
+const COUNTER_GET_INFO: usize = 1;
+const COUNTER_CONFIG_MATCHING: usize = 2;
+const COUNTER_START: usize = 3;
+const COUNTER_STOP: usize = 4;
+const COUNTER_FW_READ: usize = 5;
+const COUNTER_FW_READ_HI: usize = 6;
+
+bitflags! {
+    /// Flags of `counter_config_matching`
+    pub struct ConfigFlags: usize {
+        /// Skip matching and use the counter given by the base index
+        const SKIP_MATCH = 1 << 0;
+        /// Clear the counter value
+        const CLEAR_VALUE = 1 << 1;
+        /// Start the counter after configuring it
+        const AUTO_START = 1 << 2;
+        /// Do not count events in VU-mode
+        const SET_VUINH = 1 << 3;
+        /// Do not count events in VS-mode
+        const SET_VSINH = 1 << 4;
+        /// Do not count events in U-mode
+        const SET_UINH = 1 << 5;
+        /// Do not count events in S-mode
+        const SET_SINH = 1 << 6;
+        /// Do not count events in M-mode
+        const SET_MINH = 1 << 7;
+    }
+}
+
+bitflags! {
+    /// Flags of `counter_start`
+    pub struct StartFlags: usize {
+        /// Set the counters to the initial value before starting them
+        const SET_INIT_VALUE = 1 << 0;
+    }
+}
+
+bitflags! {
+    /// Flags of `counter_stop`
+    pub struct StopFlags: usize {
+        /// Release the counters from their events
+        const RESET = 1 << 0;
+    }
+}
+
+/// Event index of the SBI PMU event encoding
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct EventIdx(pub usize);
+
+impl EventIdx {
+    /// Hardware general event (type 0), e.g. 1 for CPU cycles
+    #[inline]
+    pub const fn hardware(code: u16) -> Self {
+        EventIdx(code as usize)
+    }
+
+    /// Hardware cache event (type 1)
+    #[inline]
+    pub const fn cache(cache_id: u8, op_id: u8, result_id: u8) -> Self {
+        EventIdx(
+            (1 << 16)
+                | ((cache_id as usize) << 3)
+                | (((op_id as usize) & 0b11) << 1)
+                | ((result_id as usize) & 1),
+        )
+    }
+
+    /// Hardware raw event (type 2), whose selector is passed as event data
+    #[inline]
+    pub const fn raw() -> Self {
+        EventIdx(2 << 16)
+    }
+
+    /// Firmware event (type 15), e.g. 0 for misaligned loads
+    #[inline]
+    pub const fn firmware(code: u16) -> Self {
+        EventIdx((15 << 16) | code as usize)
+    }
+}
+
+/// Information about a counter
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CounterInfo {
+    /// Hardware counter
+    Hardware {
+        /// CSR number of the counter
+        csr: u16,
+        /// Number of implemented bits
+        width: u8,
+    },
+    /// Counter implemented by the firmware, read with `counter_fw_read`
+    Firmware,
+}
+
+/// Returns the number of hardware and firmware counters
+#[inline]
+pub fn num_counters() -> Result<usize> {
+    call(EID, NUM_COUNTERS, [0; 6]).into_result()
+}
+
+/// Returns information about counter `counter_idx`
+#[inline]
+pub fn counter_get_info(counter_idx: usize) -> Result<CounterInfo> {
+    let info = call(EID, COUNTER_GET_INFO, [counter_idx, 0, 0, 0, 0, 0]).into_result()?;
+    if info >> (usize::BITS - 1) != 0 {
+        Ok(CounterInfo::Firmware)
+    } else {
+        Ok(CounterInfo::Hardware {
+            csr: (info & 0xfff) as u16,
+            width: ((info >> 12) & 0x3f) as u8 + 1,
+        })
+    }
+}
+
+/// Finds a counter among the set that can monitor `event` and configures it,
+/// returns its index
+///
+/// `event_data` holds additional configuration, e.g. the raw event selector.
+#[inline]
+pub fn counter_config_matching(
+    counter_idx_base: usize,
+    counter_idx_mask: usize,
+    flags: ConfigFlags,
+    event: EventIdx,
+    event_data: u64,
+) -> Result<usize> {
+    let [lo, hi] = split_u64(event_data);
+    call(
+        EID,
+        COUNTER_CONFIG_MATCHING,
+        [
+            counter_idx_base,
+            counter_idx_mask,
+            flags.bits(),
+            event.0,
+            lo,
+            hi,
+        ],
+    )
+    .into_result()
+}
+
+/// Starts the counters in the set, optionally setting them to
+/// `initial_value` first
+#[inline]
+pub fn counter_start(
+    counter_idx_base: usize,
+    counter_idx_mask: usize,
+    flags: StartFlags,
+    initial_value: u64,
+) -> Result<()> {
+    let [lo, hi] = split_u64(initial_value);
+    call(
+        EID,
+        COUNTER_START,
+        [counter_idx_base, counter_idx_mask, flags.bits(), lo, hi, 0],
+    )
+    .into_result()
+    .map(|_| ())
+}
+
+/// Stops the counters in the set
+#[inline]
+pub fn counter_stop(
+    counter_idx_base: usize,
+    counter_idx_mask: usize,
+    flags: StopFlags,
+) -> Result<()> {
+    call(
+        EID,
+        COUNTER_STOP,
+        [counter_idx_base, counter_idx_mask, flags.bits(), 0, 0, 0],
+    )
+    .into_result()
+    .map(|_| ())
+}
+
+/// Reads the firmware counter `counter_idx`
+///
+/// On RV32 the upper half is read with a second call.
+#[inline]
+pub fn counter_fw_read(counter_idx: usize) -> Result<u64> {
+    let lo = call(EID, COUNTER_FW_READ, [counter_idx, 0, 0, 0, 0, 0]).into_result()?;
+    if usize::BITS == 64 {
+        return Ok(lo as u64);
+    }
+    let hi = call(EID, COUNTER_FW_READ_HI, [counter_idx, 0, 0, 0, 0, 0]).into_result()?;
+    Ok(((hi as u64) << 32) | lo as u64)
+}
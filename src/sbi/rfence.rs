@@ -0,0 +1,139 @@
+//! RFENCE extension
+//!
+//! Remote fences on the harts in a [`HartMask`]. Address ranges are given as
+//! `start` and `size` in bytes; a `size` of `usize::MAX` (or a `start` and
+//! `size` of 0) flushes the whole address space.
+
+use super::{call, legacy_call, Error, HartMask, Result};
+
+/// Extension ID
+pub const EID: usize = 0x5246_4E43;
+
+const REMOTE_FENCE_I: usize = 0;
+const REMOTE_SFENCE_VMA: usize = 1;
+const REMOTE_SFENCE_VMA_ASID: usize = 2;
+const REMOTE_HFENCE_GVMA_VMID: usize = 3;
+const REMOTE_HFENCE_GVMA: usize = 4;
+const REMOTE_HFENCE_VVMA_ASID: usize = 5;
+const REMOTE_HFENCE_VVMA: usize = 6;
+
+const LEGACY_REMOTE_FENCE_I: usize = 0x05;
+const LEGACY_REMOTE_SFENCE_VMA: usize = 0x06;
+const LEGACY_REMOTE_SFENCE_VMA_ASID: usize = 0x07;
+
+#[inline]
+fn rfence(fid: usize, hart_mask: HartMask, args: [usize; 3]) -> Result<()> {
+    call(
+        EID,
+        fid,
+        [
+            hart_mask.mask(),
+            hart_mask.base(),
+            args[0],
+            args[1],
+            args[2],
+            0,
+        ],
+    )
+    .into_result()
+    .map(|_| ())
+}
+
+/// Retries a call the firmware does not support with the legacy call `eid`,
+/// which takes a pointer to the mask followed by `args`
+#[inline]
+fn with_legacy(
+    result: Result<()>,
+    eid: usize,
+    hart_mask: HartMask,
+    args: [usize; 3],
+) -> Result<()> {
+    match result {
+        Err(Error::NotSupported) => {
+            let mask = hart_mask.legacy_mask().ok_or(Error::InvalidParam)?;
+            let mask_ptr = &mask as *const usize as usize;
+            match legacy_call(eid, [mask_ptr, args[0], args[1], args[2]]) {
+                0 => Ok(()),
+                code => Err(Error::from_code(code)),
+            }
+        }
+        result => result,
+    }
+}
+
+/// Executes `FENCE.I` on the harts in `hart_mask`
+#[inline]
+pub fn remote_fence_i(hart_mask: HartMask) -> Result<()> {
+    let result = rfence(REMOTE_FENCE_I, hart_mask, [0, 0, 0]);
+    with_legacy(result, LEGACY_REMOTE_FENCE_I, hart_mask, [0, 0, 0])
+}
+
+/// Executes `SFENCE.VMA` for the virtual address range on the harts in
+/// `hart_mask`, in all address spaces
+#[inline]
+pub fn remote_sfence_vma(hart_mask: HartMask, start: usize, size: usize) -> Result<()> {
+    let result = rfence(REMOTE_SFENCE_VMA, hart_mask, [start, size, 0]);
+    with_legacy(
+        result,
+        LEGACY_REMOTE_SFENCE_VMA,
+        hart_mask,
+        [start, size, 0],
+    )
+}
+
+/// Executes `SFENCE.VMA` for the virtual address range of address space
+/// `asid` on the harts in `hart_mask`
+#[inline]
+pub fn remote_sfence_vma_asid(
+    hart_mask: HartMask,
+    start: usize,
+    size: usize,
+    asid: usize,
+) -> Result<()> {
+    let result = rfence(REMOTE_SFENCE_VMA_ASID, hart_mask, [start, size, asid]);
+    with_legacy(
+        result,
+        LEGACY_REMOTE_SFENCE_VMA_ASID,
+        hart_mask,
+        [start, size, asid],
+    )
+}
+
+/// Executes `HFENCE.GVMA` for the guest physical address range of virtual
+/// machine `vmid` on the harts in `hart_mask`
+#[inline]
+pub fn remote_hfence_gvma_vmid(
+    hart_mask: HartMask,
+    start: usize,
+    size: usize,
+    vmid: usize,
+) -> Result<()> {
+    rfence(REMOTE_HFENCE_GVMA_VMID, hart_mask, [start, size, vmid])
+}
+
+/// Executes `HFENCE.GVMA` for the guest physical address range on the harts
+/// in `hart_mask`, for all virtual machines
+#[inline]
+pub fn remote_hfence_gvma(hart_mask: HartMask, start: usize, size: usize) -> Result<()> {
+    rfence(REMOTE_HFENCE_GVMA, hart_mask, [start, size, 0])
+}
+
+/// Executes `HFENCE.VVMA` for the guest virtual address range of guest
+/// address space `asid` of the current virtual machine on the harts in
+/// `hart_mask`
+#[inline]
+pub fn remote_hfence_vvma_asid(
+    hart_mask: HartMask,
+    start: usize,
+    size: usize,
+    asid: usize,
+) -> Result<()> {
+    rfence(REMOTE_HFENCE_VVMA_ASID, hart_mask, [start, size, asid])
+}
+
+/// Executes `HFENCE.VVMA` for the guest virtual address range of the current
+/// virtual machine on the harts in `hart_mask`, in all guest address spaces
+#[inline]
+pub fn remote_hfence_vvma(hart_mask: HartMask, start: usize, size: usize) -> Result<()> {
+    rfence(REMOTE_HFENCE_VVMA, hart_mask, [start, size, 0])
+}
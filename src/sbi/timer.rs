@@ -0,0 +1,26 @@
+//! Timer extension
+
+use super::{call, legacy_call, split_u64, Error, Result};
+
+/// Extension ID
+pub const EID: usize = 0x5449_4D45;
+
+const SET_TIMER: usize = 0;
+const LEGACY_SET_TIMER: usize = 0x00;
+
+/// Programs the supervisor timer interrupt for when `time` reaches
+/// `stime_value`, and clears the pending timer interrupt
+///
+/// Pass `u64::MAX` to cancel the timer. Falls back to the legacy call if the
+/// firmware does not implement the timer extension.
+#[inline]
+pub fn set_timer(stime_value: u64) -> Result<()> {
+    let [lo, hi] = split_u64(stime_value);
+    match call(EID, SET_TIMER, [lo, hi, 0, 0, 0, 0]).into_result() {
+        Err(Error::NotSupported) => match legacy_call(LEGACY_SET_TIMER, [lo, hi, 0, 0]) {
+            0 => Ok(()),
+            code => Err(Error::from_code(code)),
+        },
+        result => result.map(|_| ()),
+    }
+}
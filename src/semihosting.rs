@@ -0,0 +1,183 @@
+//! Semihosting
+//!
+//! Lets programs running under a debugger or an emulator such as QEMU
+//! (`-semihosting`) use the I/O of the host. A request is an `EBREAK`
+//! surrounded by the uncompressed `slli x0, x0, 0x1f` and `srai x0, x0, 7`
+//! marker instructions; without a debugger attached it raises a breakpoint
+//! exception instead.
+
+use core::ffi::CStr;
+use core::fmt;
+
+/// Operation numbers of the semihosting calls
+pub mod op {
+    pub const SYS_OPEN: usize = 0x01;
+    pub const SYS_CLOSE: usize = 0x02;
+    pub const SYS_WRITEC: usize = 0x03;
+    pub const SYS_WRITE0: usize = 0x04;
+    pub const SYS_WRITE: usize = 0x05;
+    pub const SYS_READ: usize = 0x06;
+    pub const SYS_EXIT: usize = 0x18;
+    pub const SYS_EXIT_EXTENDED: usize = 0x20;
+}
+
+/// `ADP_Stopped_ApplicationExit`, the reason passed to `SYS_EXIT`
+const APPLICATION_EXIT: usize = 0x20026;
+
+/// Issues semihosting call `op` with the parameter (block) `param`
+///
+/// # Safety
+///
+/// `param` must be valid for the operation, e.g. point to a parameter block
+/// of the expected size.
+#[inline]
+#[allow(unused_variables)]
+pub unsafe fn syscall(op: usize, param: usize) -> usize {
+    match () {
+        #[cfg(all(riscv, feature = "inline-asm"))]
+        () => {
+            let ret;
+            core::arch::asm!(
+                ".balign 16",
+                ".word 32509971",
+                ".word 1048691",
+                ".word 1081102355",
+                inlateout("a0") op => ret,
+                in("a1") param,
+            );
+            ret
+        }
+
+        #[cfg(all(riscv, not(feature = "inline-asm")))]
+        () => {
+            extern "C" {
+                fn __semihosting_call(op: usize, param: usize) -> usize;
+            }
+
+            __semihosting_call(op, param)
+        }
+
+        #[cfg(not(riscv))]
+        () => unimplemented!(),
+    }
+}
+
+/// Mode of `open`, mirroring the `fopen` modes
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum OpenMode {
+    Read = 0,
+    ReadBinary = 1,
+    ReadWrite = 2,
+    ReadWriteBinary = 3,
+    Write = 4,
+    WriteBinary = 5,
+    WriteRead = 6,
+    WriteReadBinary = 7,
+    Append = 8,
+    AppendBinary = 9,
+    AppendRead = 10,
+    AppendReadBinary = 11,
+}
+
+/// Returns the special file name of the debugger console
+#[inline]
+fn console() -> &'static CStr {
+    unsafe { CStr::from_bytes_with_nul_unchecked(b":tt\0") }
+}
+
+/// Handle of a file opened on the host
+///
+/// Implements `fmt::Write`, so that `Handle::stdout()` can be used with
+/// `write!`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Handle(pub usize);
+
+impl Handle {
+    /// Opens the debugger console (`:tt`) for writing
+    #[inline]
+    pub fn stdout() -> Option<Handle> {
+        open(console(), OpenMode::Write)
+    }
+
+    /// Opens the debugger console (`:tt`) for appending, which some hosts
+    /// treat as standard error
+    #[inline]
+    pub fn stderr() -> Option<Handle> {
+        open(console(), OpenMode::Append)
+    }
+}
+
+impl fmt::Write for Handle {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        match write(*self, s.as_bytes()) {
+            0 => Ok(()),
+            _ => Err(fmt::Error),
+        }
+    }
+}
+
+/// Writes a NUL-terminated string to the debugger console
+#[inline]
+pub fn write0(s: &CStr) {
+    unsafe { syscall(op::SYS_WRITE0, s.as_ptr() as usize) };
+}
+
+/// Writes a character to the debugger console
+#[inline]
+pub fn writec(c: u8) {
+    unsafe { syscall(op::SYS_WRITEC, &c as *const u8 as usize) };
+}
+
+/// Opens the file `path` on the host, returns `None` on error
+#[inline]
+pub fn open(path: &CStr, mode: OpenMode) -> Option<Handle> {
+    let bytes = path.to_bytes();
+    let params = [bytes.as_ptr() as usize, mode as usize, bytes.len()];
+    match unsafe { syscall(op::SYS_OPEN, params.as_ptr() as usize) } as isize {
+        -1 => None,
+        handle => Some(Handle(handle as usize)),
+    }
+}
+
+/// Closes `handle`, returns false on error
+#[inline]
+pub fn close(handle: Handle) -> bool {
+    let params = [handle.0];
+    unsafe { syscall(op::SYS_CLOSE, params.as_ptr() as usize) == 0 }
+}
+
+/// Writes `buf` to `handle`, returns the number of bytes that were not
+/// written
+#[inline]
+pub fn write(handle: Handle, buf: &[u8]) -> usize {
+    let params = [handle.0, buf.as_ptr() as usize, buf.len()];
+    unsafe { syscall(op::SYS_WRITE, params.as_ptr() as usize) }
+}
+
+/// Reads into `buf` from `handle`, returns the number of bytes that were not
+/// read
+///
+/// A result equal to `buf.len()` indicates end of file.
+#[inline]
+pub fn read(handle: Handle, buf: &mut [u8]) -> usize {
+    let params = [handle.0, buf.as_mut_ptr() as usize, buf.len()];
+    unsafe { syscall(op::SYS_READ, params.as_ptr() as usize) }
+}
+
+/// Terminates the program with exit status `code`, e.g. to end a test run
+/// under QEMU
+///
+/// Waits for interrupts forever if the host ignores the request.
+#[inline]
+pub fn exit(code: i32) -> ! {
+    let params = [APPLICATION_EXIT, code as isize as usize];
+    let op = if usize::BITS == 64 {
+        op::SYS_EXIT
+    } else {
+        op::SYS_EXIT_EXTENDED
+    };
+    unsafe { syscall(op, params.as_ptr() as usize) };
+    loop {
+        unsafe { ::asm::wfi() };
+    }
+}
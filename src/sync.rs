@@ -0,0 +1,726 @@
+//! Synchronization across harts
+//!
+//! `interrupt::free` and the re-exported `bare_metal::Mutex` only mask
+//! interrupts on the calling hart, so they do not protect data shared with
+//! other harts. This module provides [`HartLocal`] (usually declared with
+//! [`hart_local!`](crate::hart_local)) for data each hart keeps to itself,
+//! and [`SpinMutex`] (on targets with the A extension) for data shared
+//! between harts. On cores without the A extension, the `soft-atomics`
+//! feature provides `SpinMutex` on top of the `atomic` module, whose
+//! critical sections must then exclude all harts that share the data.
+//!
+//! For bring-up on multi-hart systems, the ticket lock [`SpinLock`],
+//! [`Once`] and [`Barrier`] use the 32-bit `core` atomics, or `LR.W`/`SC.W`
+//! and `AMOSWAP.W` directly on targets without them, and wait with the
+//! Zihintpause `PAUSE` hint.
+//!
+//! # The `tp` register
+//!
+//! This crate keeps the index of the current hart in `tp`. It is set with
+//! [`init_hart`], or with `mhartid::save_to_tp`, which uses the hart id as
+//! the index, and read with [`hart_index`]. Indices must be unique among the
+//! harts, and select the slots of `HartLocal` and of the per-hart state of
+//! `interrupt::DisableGuard`. In S-mode, where `mhartid` is not accessible,
+//! [`hart_id`] returns this index too, so firmware that hands the hart id
+//! over in `tp` keeps both meanings the same.
+
+#[cfg(not(target_has_atomic = "32"))]
+use asm::FenceSet;
+#[cfg(all(not(target_has_atomic = "8"), feature = "soft-atomics"))]
+use atomic::AtomicBool;
+use core::cell::UnsafeCell;
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+#[cfg(target_has_atomic = "8")]
+use core::sync::atomic::AtomicBool;
+#[cfg(target_has_atomic = "32")]
+use core::sync::atomic::AtomicU32;
+#[cfg(any(
+    target_has_atomic = "8",
+    target_has_atomic = "32",
+    feature = "soft-atomics"
+))]
+use core::sync::atomic::Ordering;
+use interrupt::CriticalSection;
+
+/// Returns the id of the current hart
+///
+/// Read from `mhartid` in M-mode. With the `s-mode` feature this is the hart
+/// index in `tp` (see [the module docs](self#the-tp-register)), which is the
+/// hart id if firmware stored it with `mhartid::save_to_tp`.
+#[inline]
+pub fn hart_id() -> usize {
+    match () {
+        #[cfg(not(feature = "s-mode"))]
+        () => ::register::mhartid::read(),
+        #[cfg(feature = "s-mode")]
+        () => hart_index(),
+    }
+}
+
+/// Sets the index of the current hart, which selects its `HartLocal` slots
+///
+/// Call this once on every hart at boot, before using any `HartLocal`. The
+/// index is kept in `tp` (see [the module docs](self#the-tp-register)), so
+/// hart-local accesses need neither a CSR read nor a lookup table. Indices
+/// may differ from hart ids, which allows dense slots on platforms with
+/// sparse `mhartid` values.
+///
+/// Returns the token that grants access to the hart's `HartLocal` slots.
+///
+/// # Safety
+///
+/// Overwrites `tp`, see [`asm::write_tp`](crate::asm::write_tp). `index`
+/// must be unique among the harts.
+#[inline]
+pub unsafe fn init_hart(index: usize) -> HartToken {
+    ::asm::write_tp(index);
+    HartToken::assume_init()
+}
+
+/// Returns the index of the current hart set by [`init_hart`]
+#[inline]
+pub fn hart_index() -> usize {
+    ::asm::read_tp()
+}
+
+/// Proof that the index of the current hart has been set with [`init_hart`]
+///
+/// Required to access `HartLocal` slots, since with an unset or clobbered
+/// `tp` two harts could select the same slot. The token cannot be sent to
+/// another hart.
+#[derive(Copy, Clone, Debug)]
+pub struct HartToken {
+    _not_send: PhantomData<*const ()>,
+}
+
+impl HartToken {
+    /// Returns the token of the current hart without setting its index
+    ///
+    /// # Safety
+    ///
+    /// [`init_hart`] must have been called on the current hart, and `tp` must
+    /// not have been changed since.
+    #[inline]
+    pub unsafe fn assume_init() -> Self {
+        HartToken {
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Returns the index of the hart
+    #[inline]
+    pub fn index(self) -> usize {
+        hart_index()
+    }
+}
+
+/// One value per hart, each only accessible from its own hart
+///
+/// Slots are selected by the hart index in `tp`, and accessing them requires
+/// the [`HartToken`] returned by [`init_hart`]. Access also requires a
+/// critical section, which keeps the current task from being preempted and
+/// migrated to another hart while it holds a reference.
+pub struct HartLocal<T, const HARTS: usize> {
+    slots: [T; HARTS],
+}
+
+// Each slot is only ever accessed from the hart it belongs to
+unsafe impl<T: Send, const HARTS: usize> Sync for HartLocal<T, HARTS> {}
+
+impl<T, const HARTS: usize> HartLocal<T, HARTS> {
+    /// Creates a new `HartLocal` from the values of harts `0..HARTS`
+    #[inline]
+    pub const fn new(slots: [T; HARTS]) -> Self {
+        HartLocal { slots }
+    }
+
+    /// Returns the value of the current hart
+    ///
+    /// # Panics
+    ///
+    /// Panics if the current hart index is not below `HARTS`.
+    #[inline]
+    pub fn borrow<'cs>(&'cs self, hart: HartToken, _cs: &'cs CriticalSection) -> &'cs T {
+        &self.slots[hart.index()]
+    }
+
+    /// Runs `f` on the value of the current hart inside `interrupt::free`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the current hart index is not below `HARTS`.
+    #[inline]
+    pub fn with<F, R>(&self, hart: HartToken, f: F) -> R
+    where
+        F: FnOnce(&T) -> R,
+    {
+        ::interrupt::free(|cs| f(self.borrow(hart, cs)))
+    }
+
+    /// Returns the value of the hart with index `index`
+    ///
+    /// # Safety
+    ///
+    /// `index` must be the current hart, and the caller must not migrate
+    /// to another hart while the reference is alive.
+    #[inline]
+    pub unsafe fn get_unchecked(&self, index: usize) -> &T {
+        &self.slots[index]
+    }
+
+    /// Returns mutable access to the values of all harts
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut [T; HARTS] {
+        &mut self.slots
+    }
+}
+
+/// Declares `HartLocal` statics
+///
+/// Each slot is initialized with the given expression, which must be
+/// constant.
+///
+/// ```ignore
+/// hart_local! {
+///     /// Timer ticks seen by each of the 4 harts
+///     static TICKS: [Cell<u64>; 4] = Cell::new(0);
+/// }
+///
+/// let hart = unsafe { sync::init_hart(index) };
+/// TICKS.with(hart, |ticks| ticks.set(ticks.get() + 1));
+/// ```
+#[macro_export]
+macro_rules! hart_local {
+    ($(#[$attr:meta])* $vis:vis static $name:ident: [$ty:ty; $harts:expr] = $init:expr; $($rest:tt)*) => {
+        $(#[$attr])*
+        $vis static $name: $crate::sync::HartLocal<$ty, { $harts }> = {
+            const INIT: $ty = $init;
+            $crate::sync::HartLocal::new([INIT; $harts])
+        };
+        $crate::hart_local!($($rest)*);
+    };
+    () => {};
+}
+
+/// Mutual exclusion lock for data shared between harts
+///
+/// Waiting harts spin on an atomic flag. Locking does not mask interrupts,
+/// so a lock that is also taken by an interrupt handler must be acquired
+/// inside `interrupt::free` (or with a `DisableGuard` alive) to avoid
+/// deadlocking the hart against itself.
+#[cfg(any(target_has_atomic = "8", feature = "soft-atomics"))]
+pub struct SpinMutex<T> {
+    locked: AtomicBool,
+    data: UnsafeCell<T>,
+}
+
+#[cfg(any(target_has_atomic = "8", feature = "soft-atomics"))]
+unsafe impl<T: Send> Sync for SpinMutex<T> {}
+#[cfg(any(target_has_atomic = "8", feature = "soft-atomics"))]
+unsafe impl<T: Send> Send for SpinMutex<T> {}
+
+#[cfg(any(target_has_atomic = "8", feature = "soft-atomics"))]
+impl<T> SpinMutex<T> {
+    /// Creates a new unlocked mutex
+    #[inline]
+    pub const fn new(data: T) -> Self {
+        SpinMutex {
+            locked: AtomicBool::new(false),
+            data: UnsafeCell::new(data),
+        }
+    }
+
+    /// Acquires the lock, spinning until it is available
+    #[inline]
+    pub fn lock(&self) -> SpinMutexGuard<'_, T> {
+        loop {
+            if let Some(guard) = self.try_lock() {
+                return guard;
+            }
+            while self.locked.load(Ordering::Relaxed) {
+                core::hint::spin_loop();
+            }
+        }
+    }
+
+    /// Acquires the lock if it is available
+    #[inline]
+    pub fn try_lock(&self) -> Option<SpinMutexGuard<'_, T>> {
+        match self
+            .locked
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+        {
+            Ok(_) => Some(SpinMutexGuard { mutex: self }),
+            Err(_) => None,
+        }
+    }
+
+    /// Returns mutable access to the data without locking
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.data.get() }
+    }
+
+    /// Consumes the mutex, returning the data
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+/// Holds a `SpinMutex` locked until dropped
+#[cfg(any(target_has_atomic = "8", feature = "soft-atomics"))]
+pub struct SpinMutexGuard<'a, T> {
+    mutex: &'a SpinMutex<T>,
+}
+
+#[cfg(any(target_has_atomic = "8", feature = "soft-atomics"))]
+impl<T> Deref for SpinMutexGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        unsafe { &*self.mutex.data.get() }
+    }
+}
+
+#[cfg(any(target_has_atomic = "8", feature = "soft-atomics"))]
+impl<T> DerefMut for SpinMutexGuard<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.mutex.data.get() }
+    }
+}
+
+#[cfg(any(target_has_atomic = "8", feature = "soft-atomics"))]
+impl<T> Drop for SpinMutexGuard<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        self.mutex.locked.store(false, Ordering::Release);
+    }
+}
+
+/// Adds `value` to the word at `ptr` with an `LR.W.AQRL`/`SC.W.RL` loop,
+/// returning the previous value
+#[cfg(not(target_has_atomic = "32"))]
+#[inline]
+unsafe fn fetch_add(ptr: *mut u32, value: u32) -> u32 {
+    match () {
+        #[cfg(all(riscv, feature = "inline-asm"))]
+        () => {
+            let previous: u32;
+            core::arch::asm!(
+                "2:",
+                ".word 369436207",
+                "add a3, a2, a1",
+                ".word 450176687",
+                "bnez a3, 2b",
+                in("a0") ptr,
+                in("a1") value,
+                out("a2") previous,
+                out("a3") _,
+            );
+            previous
+        }
+
+        #[cfg(all(riscv, not(feature = "inline-asm")))]
+        () => {
+            extern "C" {
+                fn __lrsc_fetch_add_w(ptr: *mut u32, value: u32) -> u32;
+            }
+
+            __lrsc_fetch_add_w(ptr, value)
+        }
+
+        #[cfg(not(riscv))]
+        () => {
+            let _ = (ptr, value);
+            unimplemented!()
+        }
+    }
+}
+
+/// Replaces the word at `ptr` with `new` if it holds `current`, with an
+/// `LR.W.AQ`/`SC.W.RL` loop, returning the previous value
+#[cfg(not(target_has_atomic = "32"))]
+#[inline]
+unsafe fn compare_exchange(ptr: *mut u32, current: u32, new: u32) -> u32 {
+    match () {
+        #[cfg(all(riscv, feature = "inline-asm"))]
+        () => {
+            let previous: u32;
+            // `LR.W` sign-extends the loaded word, so `current` must be as well
+            core::arch::asm!(
+                "2:",
+                ".word 335881903",
+                "bne a3, a1, 3f",
+                ".word 449128239",
+                "bnez a4, 2b",
+                "3:",
+                in("a0") ptr,
+                in("a1") current as i32 as isize,
+                in("a2") new,
+                out("a3") previous,
+                out("a4") _,
+            );
+            previous
+        }
+
+        #[cfg(all(riscv, not(feature = "inline-asm")))]
+        () => {
+            extern "C" {
+                fn __lrsc_compare_exchange_w(ptr: *mut u32, current: u32, new: u32) -> u32;
+            }
+
+            __lrsc_compare_exchange_w(ptr, current, new)
+        }
+
+        #[cfg(not(riscv))]
+        () => {
+            let _ = (ptr, current, new);
+            unimplemented!()
+        }
+    }
+}
+
+/// Stores `value` to the word at `ptr` with `AMOSWAP.W.RL`, returning the
+/// previous value
+#[cfg(not(target_has_atomic = "32"))]
+#[inline]
+unsafe fn swap_release(ptr: *mut u32, value: u32) -> u32 {
+    match () {
+        #[cfg(all(riscv, feature = "inline-asm"))]
+        () => {
+            let previous: u32;
+            core::arch::asm!(
+                ".word 179643695",
+                inout("a0") ptr => previous,
+                in("a1") value,
+            );
+            previous
+        }
+
+        #[cfg(all(riscv, not(feature = "inline-asm")))]
+        () => {
+            extern "C" {
+                fn __amoswap_release_w(ptr: *mut u32, value: u32) -> u32;
+            }
+
+            __amoswap_release_w(ptr, value)
+        }
+
+        #[cfg(not(riscv))]
+        () => {
+            let _ = (ptr, value);
+            unimplemented!()
+        }
+    }
+}
+
+/// Loads the word at `ptr`, ordering it before all later memory accesses
+#[cfg(not(target_has_atomic = "32"))]
+#[inline]
+unsafe fn load_acquire(ptr: *const u32) -> u32 {
+    let value = core::ptr::read_volatile(ptr);
+    ::asm::fence(FenceSet::R, FenceSet::RW);
+    value
+}
+
+/// Word the [`SpinLock`], [`Once`] and [`Barrier`] synchronize on
+///
+/// If the target has 32-bit `core` atomics this is an `AtomicU32`. Otherwise
+/// the word is accessed with `LR.W`/`SC.W` and `AMOSWAP.W` directly, which
+/// only requires the A extension at run time, and acquire loads are plain
+/// loads followed by `FENCE R, RW`. Both give the same orderings:
+///
+/// - `fetch_add` is acquire-release (`LR.W.AQRL`/`SC.W.RL`)
+/// - `compare_exchange` is acquire-release on success and acquire on
+///   failure (`LR.W.AQ`/`SC.W.RL`)
+/// - `swap_release` is release (`AMOSWAP.W.RL`)
+/// - `load_acquire` is acquire
+/// - `load_relaxed` and `store_relaxed` are unordered, and only used on words
+///   no other hart writes at the time
+struct Word {
+    #[cfg(target_has_atomic = "32")]
+    inner: AtomicU32,
+    #[cfg(not(target_has_atomic = "32"))]
+    inner: UnsafeCell<u32>,
+}
+
+impl Word {
+    #[inline]
+    const fn new(value: u32) -> Self {
+        match () {
+            #[cfg(target_has_atomic = "32")]
+            () => Word {
+                inner: AtomicU32::new(value),
+            },
+            #[cfg(not(target_has_atomic = "32"))]
+            () => Word {
+                inner: UnsafeCell::new(value),
+            },
+        }
+    }
+
+    #[inline]
+    fn fetch_add(&self, value: u32) -> u32 {
+        match () {
+            #[cfg(target_has_atomic = "32")]
+            () => self.inner.fetch_add(value, Ordering::AcqRel),
+            #[cfg(not(target_has_atomic = "32"))]
+            () => unsafe { fetch_add(self.inner.get(), value) },
+        }
+    }
+
+    /// Returns the previous value, which equals `current` on success
+    #[inline]
+    fn compare_exchange(&self, current: u32, new: u32) -> u32 {
+        match () {
+            #[cfg(target_has_atomic = "32")]
+            () => {
+                match self
+                    .inner
+                    .compare_exchange(current, new, Ordering::AcqRel, Ordering::Acquire)
+                {
+                    Ok(previous) | Err(previous) => previous,
+                }
+            }
+            #[cfg(not(target_has_atomic = "32"))]
+            () => unsafe { compare_exchange(self.inner.get(), current, new) },
+        }
+    }
+
+    #[inline]
+    fn swap_release(&self, value: u32) -> u32 {
+        match () {
+            #[cfg(target_has_atomic = "32")]
+            () => self.inner.swap(value, Ordering::Release),
+            #[cfg(not(target_has_atomic = "32"))]
+            () => unsafe { swap_release(self.inner.get(), value) },
+        }
+    }
+
+    #[inline]
+    fn load_acquire(&self) -> u32 {
+        match () {
+            #[cfg(target_has_atomic = "32")]
+            () => self.inner.load(Ordering::Acquire),
+            #[cfg(not(target_has_atomic = "32"))]
+            () => unsafe { load_acquire(self.inner.get()) },
+        }
+    }
+
+    #[inline]
+    fn load_relaxed(&self) -> u32 {
+        match () {
+            #[cfg(target_has_atomic = "32")]
+            () => self.inner.load(Ordering::Relaxed),
+            #[cfg(not(target_has_atomic = "32"))]
+            () => unsafe { core::ptr::read_volatile(self.inner.get()) },
+        }
+    }
+
+    #[inline]
+    fn store_relaxed(&self, value: u32) {
+        match () {
+            #[cfg(target_has_atomic = "32")]
+            () => self.inner.store(value, Ordering::Relaxed),
+            #[cfg(not(target_has_atomic = "32"))]
+            () => unsafe { core::ptr::write_volatile(self.inner.get(), value) },
+        }
+    }
+}
+
+/// Fair mutual exclusion lock for data shared between harts
+///
+/// A ticket lock: harts draw tickets with an atomic add and enter in the
+/// order they arrived, spinning with `PAUSE` (Zihintpause) until their
+/// ticket is served. Unlike [`SpinMutex`] it does not need the `core` atomics
+/// of the target, but it requires the A extension at run time. As with
+/// `SpinMutex`, locking does not mask interrupts.
+pub struct SpinLock<T> {
+    next: Word,
+    serving: Word,
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T: Send> Sync for SpinLock<T> {}
+unsafe impl<T: Send> Send for SpinLock<T> {}
+
+impl<T> SpinLock<T> {
+    /// Creates a new unlocked lock
+    #[inline]
+    pub const fn new(data: T) -> Self {
+        SpinLock {
+            next: Word::new(0),
+            serving: Word::new(0),
+            data: UnsafeCell::new(data),
+        }
+    }
+
+    /// Acquires the lock, spinning until it is the caller's turn
+    #[inline]
+    pub fn lock(&self) -> SpinLockGuard<'_, T> {
+        let ticket = self.next.fetch_add(1);
+        while self.serving.load_acquire() != ticket {
+            unsafe { ::asm::pause() };
+        }
+        SpinLockGuard { lock: self }
+    }
+
+    /// Acquires the lock if no hart holds or waits for it
+    #[inline]
+    pub fn try_lock(&self) -> Option<SpinLockGuard<'_, T>> {
+        let serving = self.serving.load_acquire();
+        if self.next.compare_exchange(serving, serving.wrapping_add(1)) == serving {
+            Some(SpinLockGuard { lock: self })
+        } else {
+            None
+        }
+    }
+
+    /// Returns mutable access to the data without locking
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.data.get() }
+    }
+
+    /// Consumes the lock, returning the data
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+/// Holds a `SpinLock` locked until dropped
+pub struct SpinLockGuard<'a, T> {
+    lock: &'a SpinLock<T>,
+}
+
+impl<T> Deref for SpinLockGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<T> DerefMut for SpinLockGuard<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.lock.data.get() }
+    }
+}
+
+impl<T> Drop for SpinLockGuard<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        // Only the holder writes `serving`, so it can be read without ordering
+        let serving = self.lock.serving.load_relaxed();
+        self.lock.serving.swap_release(serving.wrapping_add(1));
+    }
+}
+
+const ONCE_INCOMPLETE: u32 = 0;
+const ONCE_RUNNING: u32 = 1;
+const ONCE_COMPLETE: u32 = 2;
+
+/// One-time initialization shared between harts
+///
+/// The first hart to call [`call_once`](Once::call_once) runs the closure
+/// while the others spin with `PAUSE` until it has finished. Requires the A
+/// extension.
+pub struct Once {
+    state: Word,
+}
+
+unsafe impl Sync for Once {}
+unsafe impl Send for Once {}
+
+impl Once {
+    /// Creates a new `Once`
+    #[inline]
+    pub const fn new() -> Self {
+        Once {
+            state: Word::new(ONCE_INCOMPLETE),
+        }
+    }
+
+    /// Runs `f` if no hart has run it yet, and returns once it has completed
+    ///
+    /// If `f` panics, the `Once` is never completed and other callers spin
+    /// forever.
+    pub fn call_once<F: FnOnce()>(&self, f: F) {
+        if self.state.load_acquire() == ONCE_COMPLETE {
+            return;
+        }
+        if self.state.compare_exchange(ONCE_INCOMPLETE, ONCE_RUNNING) == ONCE_INCOMPLETE {
+            f();
+            self.state.swap_release(ONCE_COMPLETE);
+        } else {
+            while self.state.load_acquire() != ONCE_COMPLETE {
+                unsafe { ::asm::pause() };
+            }
+        }
+    }
+
+    /// Returns true if a call to `call_once` has completed
+    #[inline]
+    pub fn is_completed(&self) -> bool {
+        self.state.load_acquire() == ONCE_COMPLETE
+    }
+}
+
+impl Default for Once {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Rendezvous point for a fixed number of harts
+///
+/// Harts calling [`wait`](Barrier::wait) spin with `PAUSE` until `harts` of
+/// them have arrived. The barrier can be reused right away. Requires the A
+/// extension.
+pub struct Barrier {
+    arrived: Word,
+    generation: Word,
+    harts: u32,
+}
+
+unsafe impl Sync for Barrier {}
+unsafe impl Send for Barrier {}
+
+impl Barrier {
+    /// Creates a barrier for `harts` harts
+    #[inline]
+    pub const fn new(harts: u32) -> Self {
+        Barrier {
+            arrived: Word::new(0),
+            generation: Word::new(0),
+            harts,
+        }
+    }
+
+    /// Waits until all harts have arrived
+    ///
+    /// Returns true on exactly one hart, the last to arrive.
+    pub fn wait(&self) -> bool {
+        let generation = self.generation.load_acquire();
+        if self.arrived.fetch_add(1).wrapping_add(1) >= self.harts {
+            // No hart increments `arrived` again before it sees the new
+            // generation, which is released after the reset
+            self.arrived.store_relaxed(0);
+            self.generation.swap_release(generation.wrapping_add(1));
+            true
+        } else {
+            while self.generation.load_acquire() == generation {
+                unsafe { ::asm::pause() };
+            }
+            false
+        }
+    }
+}
@@ -0,0 +1,165 @@
+//! Monotonic time
+//!
+//! [`Monotonic`] reads a free-running 64-bit counter, usually `time` or a
+//! memory-mapped `mtime`, as [`Instant`]s and converts between ticks and
+//! `core::time::Duration`, so timeouts can be written without tick math:
+//!
+//! ```ignore
+//! let clock = Monotonic::time(10_000_000);
+//! let deadline = clock.deadline(Duration::from_millis(5));
+//! while !device_ready() {
+//!     if clock.is_reached(deadline) {
+//!         return Err(Timeout);
+//!     }
+//! }
+//! ```
+
+use core::time::Duration;
+
+pub use delay::Counter;
+
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+
+/// Converts `duration` to ticks of a counter running at `frequency` Hz,
+/// rounding up and saturating at `u64::MAX`
+#[inline]
+pub fn duration_to_ticks(duration: Duration, frequency: u32) -> u64 {
+    let frequency = frequency as u64;
+    let nanos = (duration.subsec_nanos() as u64 * frequency).div_ceil(NANOS_PER_SEC);
+    duration
+        .as_secs()
+        .saturating_mul(frequency)
+        .saturating_add(nanos)
+}
+
+/// Converts `ticks` of a counter running at `frequency` Hz to a duration,
+/// rounding down
+///
+/// # Panics
+///
+/// Panics if `frequency` is zero.
+#[inline]
+pub fn ticks_to_duration(ticks: u64, frequency: u32) -> Duration {
+    let frequency = frequency as u64;
+    let nanos = (ticks % frequency) * NANOS_PER_SEC / frequency;
+    Duration::new(ticks / frequency, nanos as u32)
+}
+
+/// A point in time, as a value of the counter of a [`Monotonic`]
+///
+/// Instants are only comparable when taken from the same clock.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Instant(u64);
+
+impl Instant {
+    /// Creates an instant from a counter value
+    #[inline]
+    pub const fn from_ticks(ticks: u64) -> Self {
+        Instant(ticks)
+    }
+
+    /// Returns the counter value
+    #[inline]
+    pub const fn ticks(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the ticks from `earlier` to `self`, or `None` if `earlier` is
+    /// later
+    #[inline]
+    pub fn checked_ticks_since(self, earlier: Instant) -> Option<u64> {
+        self.0.checked_sub(earlier.0)
+    }
+}
+
+/// Monotonic clock on a free-running counter
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Monotonic {
+    counter: Counter,
+    frequency: u32,
+}
+
+impl Monotonic {
+    /// Creates a clock reading `counter`, which ticks at `frequency` Hz
+    ///
+    /// # Safety
+    ///
+    /// `Counter::Mtime` must hold the address of a readable `mtime` register.
+    #[inline]
+    pub const unsafe fn new(counter: Counter, frequency: u32) -> Self {
+        Monotonic { counter, frequency }
+    }
+
+    /// Creates a clock reading the `time` CSR, which ticks at the `frequency`
+    /// Hz of the platform timebase
+    #[inline]
+    pub const fn time(frequency: u32) -> Self {
+        Monotonic {
+            counter: Counter::Time,
+            frequency,
+        }
+    }
+
+    /// Returns the counter the clock reads
+    #[inline]
+    pub fn counter(&self) -> Counter {
+        self.counter
+    }
+
+    /// Returns the counter frequency in Hz
+    #[inline]
+    pub fn frequency(&self) -> u32 {
+        self.frequency
+    }
+
+    /// Returns the current time
+    #[inline]
+    pub fn now(&self) -> Instant {
+        Instant(self.counter.read())
+    }
+
+    /// Converts `duration` to ticks, rounding up
+    #[inline]
+    pub fn ticks(&self, duration: Duration) -> u64 {
+        duration_to_ticks(duration, self.frequency)
+    }
+
+    /// Converts `ticks` to a duration, rounding down
+    #[inline]
+    pub fn duration(&self, ticks: u64) -> Duration {
+        ticks_to_duration(ticks, self.frequency)
+    }
+
+    /// Returns the time from `earlier` to `later`, or zero if `earlier` is
+    /// later
+    #[inline]
+    pub fn duration_between(&self, earlier: Instant, later: Instant) -> Duration {
+        self.duration(later.0.saturating_sub(earlier.0))
+    }
+
+    /// Returns the time elapsed since `since`
+    #[inline]
+    pub fn elapsed(&self, since: Instant) -> Duration {
+        self.duration_between(since, self.now())
+    }
+
+    /// Returns `instant` advanced by `duration`, saturating at the end of
+    /// time
+    #[inline]
+    pub fn add(&self, instant: Instant, duration: Duration) -> Instant {
+        Instant(instant.0.saturating_add(self.ticks(duration)))
+    }
+
+    /// Returns the instant `timeout` from now
+    #[inline]
+    pub fn deadline(&self, timeout: Duration) -> Instant {
+        self.add(self.now(), timeout)
+    }
+
+    /// Returns true if `deadline` has been reached
+    #[inline]
+    pub fn is_reached(&self, deadline: Instant) -> bool {
+        self.now() >= deadline
+    }
+}
@@ -0,0 +1,244 @@
+//! Countdown timers
+//!
+//! [`Timer`] counts down on the machine timer (`mtimecmp` of a CLINT) or on
+//! the supervisor timer of the Sstc extension (`stimecmp`). It can be polled,
+//! or raise a timer interrupt on expiry, e.g. to wake an async executor.
+//! With the `embedded-hal-02` feature it implements the embedded-hal 0.2
+//! `CountDown`, `Periodic` and `Cancel` traits.
+
+use core::fmt;
+use core::time::Duration;
+
+use peripheral::clint::Clint;
+use register::{mie, stimecmp, time};
+
+/// Compare register a [`Timer`] is driven by
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Comparator {
+    /// The `mtimecmp` register of the given hart, compared against `mtime`
+    Clint(Clint, usize),
+    /// The `stimecmp` CSR, compared against `time`
+    Sstc,
+}
+
+impl Comparator {
+    /// Reads the current time
+    #[inline]
+    pub fn now(self) -> u64 {
+        match self {
+            Comparator::Clint(clint, _) => clint.mtime(),
+            Comparator::Sstc => time::read64(),
+        }
+    }
+
+    /// Writes the compare register
+    #[inline]
+    pub fn set(self, deadline: u64) {
+        match self {
+            Comparator::Clint(clint, hart) => clint.set_mtimecmp(hart, deadline),
+            Comparator::Sstc => stimecmp::write64(deadline),
+        }
+    }
+}
+
+/// Error returned by `Timer::cancel` when the timer is not running
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct NotRunning;
+
+impl fmt::Display for NotRunning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("timer is not running")
+    }
+}
+
+#[cfg(feature = "core-error")]
+impl core::error::Error for NotRunning {}
+
+/// Countdown timer on a timer compare register
+///
+/// The timer owns its compare register: it programs it with the deadline
+/// while running and with `u64::MAX` otherwise.
+#[derive(Debug)]
+pub struct Timer {
+    comparator: Comparator,
+    frequency: u32,
+    deadline: Option<u64>,
+    period: Option<u64>,
+}
+
+impl Timer {
+    /// Creates a stopped timer driven by `comparator`, whose counter ticks
+    /// at `frequency` Hz
+    #[inline]
+    pub fn new(comparator: Comparator, frequency: u32) -> Self {
+        comparator.set(u64::MAX);
+        Timer {
+            comparator,
+            frequency,
+            deadline: None,
+            period: None,
+        }
+    }
+
+    /// Returns the compare register driving the timer
+    #[inline]
+    pub fn comparator(&self) -> Comparator {
+        self.comparator
+    }
+
+    /// Converts `duration` to counter ticks, rounding up
+    #[inline]
+    pub fn ticks(&self, duration: Duration) -> u64 {
+        ::time::duration_to_ticks(duration, self.frequency)
+    }
+
+    /// Starts a one-shot countdown of `ticks`
+    #[inline]
+    pub fn start_ticks(&mut self, ticks: u64) {
+        self.period = None;
+        self.arm(self.comparator.now().wrapping_add(ticks));
+    }
+
+    /// Starts a periodic countdown of `ticks`
+    ///
+    /// Each expiry is counted from the previous deadline, so the period does
+    /// not drift with the latency of `wait` or the interrupt handler.
+    #[inline]
+    pub fn start_periodic_ticks(&mut self, ticks: u64) {
+        self.period = Some(ticks);
+        self.arm(self.comparator.now().wrapping_add(ticks));
+    }
+
+    /// Starts a one-shot countdown of `duration`
+    #[inline]
+    pub fn start_oneshot(&mut self, duration: Duration) {
+        let ticks = self.ticks(duration);
+        self.start_ticks(ticks)
+    }
+
+    /// Starts a periodic countdown of `duration`
+    #[inline]
+    pub fn start_periodic(&mut self, duration: Duration) {
+        let ticks = self.ticks(duration);
+        self.start_periodic_ticks(ticks)
+    }
+
+    #[inline]
+    fn arm(&mut self, deadline: u64) {
+        self.deadline = Some(deadline);
+        self.comparator.set(deadline);
+    }
+
+    /// Returns true while a countdown is running
+    #[inline]
+    pub fn is_running(&self) -> bool {
+        self.deadline.is_some()
+    }
+
+    /// Returns true if the running countdown has expired
+    #[inline]
+    pub fn is_expired(&self) -> bool {
+        match self.deadline {
+            Some(deadline) => self.comparator.now() >= deadline,
+            None => false,
+        }
+    }
+
+    /// Handles an expiry, returns false if the countdown has not expired
+    ///
+    /// A one-shot countdown stops, a periodic one is re-armed for the next
+    /// period. Call this from the timer interrupt handler, which also
+    /// acknowledges the interrupt.
+    pub fn poll_expired(&mut self) -> bool {
+        if !self.is_expired() {
+            return false;
+        }
+        match (self.deadline, self.period) {
+            (Some(deadline), Some(period)) => self.arm(deadline.wrapping_add(period)),
+            _ => self.stop(),
+        }
+        true
+    }
+
+    /// Stops the countdown
+    #[inline]
+    pub fn stop(&mut self) {
+        self.deadline = None;
+        self.comparator.set(u64::MAX);
+    }
+
+    /// Cancels a running countdown
+    #[inline]
+    pub fn cancel(&mut self) -> Result<(), NotRunning> {
+        if !self.is_running() {
+            return Err(NotRunning);
+        }
+        self.stop();
+        Ok(())
+    }
+
+    /// Enables the timer interrupt raised on expiry
+    ///
+    /// That is the machine timer interrupt for `Comparator::Clint`, and the
+    /// supervisor timer interrupt for `Comparator::Sstc`.
+    ///
+    /// # Safety
+    ///
+    /// Enabling an interrupt can break mask-based critical sections.
+    #[inline]
+    pub unsafe fn listen(&self) {
+        match self.comparator {
+            Comparator::Clint(..) => mie::set_mtimer(),
+            #[cfg(feature = "s-mode")]
+            Comparator::Sstc => ::register::sie::set_stimer(),
+            #[cfg(not(feature = "s-mode"))]
+            Comparator::Sstc => mie::set_stimer(),
+        }
+    }
+
+    /// Disables the timer interrupt enabled by `listen`
+    #[inline]
+    pub fn unlisten(&self) {
+        unsafe {
+            match self.comparator {
+                Comparator::Clint(..) => mie::clear_mtimer(),
+                #[cfg(feature = "s-mode")]
+                Comparator::Sstc => ::register::sie::clear_stimer(),
+                #[cfg(not(feature = "s-mode"))]
+                Comparator::Sstc => mie::clear_stimer(),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "embedded-hal-02")]
+impl ::embedded_hal_02::timer::CountDown for Timer {
+    type Time = Duration;
+
+    #[inline]
+    fn start<T: Into<Duration>>(&mut self, count: T) {
+        self.start_periodic(count.into())
+    }
+
+    #[inline]
+    fn wait(&mut self) -> ::nb::Result<(), ::void::Void> {
+        if self.poll_expired() {
+            Ok(())
+        } else {
+            Err(::nb::Error::WouldBlock)
+        }
+    }
+}
+
+#[cfg(feature = "embedded-hal-02")]
+impl ::embedded_hal_02::timer::Periodic for Timer {}
+
+#[cfg(feature = "embedded-hal-02")]
+impl ::embedded_hal_02::timer::Cancel for Timer {
+    type Error = NotRunning;
+
+    #[inline]
+    fn cancel(&mut self) -> Result<(), NotRunning> {
+        Timer::cancel(self)
+    }
+}
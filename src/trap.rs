@@ -0,0 +1,513 @@
+//! Trap causes and trap frames
+//!
+//! The cause types shared by `mcause`, `scause`, `vscause` and the
+//! delegation registers, and the [`TrapFrame`] saved by the trap entry.
+//!
+//! # Trap entry
+//!
+//! With the `trap-entry` feature, the crate provides a `_trap_entry` routine
+//! suitable for `mtvec` (or `stvec` with the `s-mode` feature) in direct
+//! mode. It pushes a `TrapFrame` onto the current stack, calls the
+//! user-provided handler with it, writes back `pc` and `status` and returns
+//! with `mret` (`sret`):
+//!
+//! ```ignore
+//! #[no_mangle]
+//! extern "C" fn trap_handler(frame: &mut TrapFrame) {
+//!     // ...
+//! }
+//! ```
+//!
+//! The stack pointer is used as is, so traps from a less privileged mode
+//! that runs on its own stack need a separate entry that switches stacks.
+//!
+//! Defining `trap_handler` is optional: the crate provides a weak default
+//! that passes interrupts to [`interrupt::dispatch`](crate::interrupt::dispatch)
+//! and calls the optional hooks below for everything else, panicking with
+//! the cause, `pc` and `tval` if they are not defined either:
+//!
+//! ```ignore
+//! #[no_mangle]
+//! extern "C" fn ExceptionHandler(frame: &mut TrapFrame) {
+//!     // ...
+//! }
+//!
+//! #[no_mangle]
+//! extern "C" fn DefaultInterruptHandler(frame: &mut TrapFrame) {
+//!     // Interrupts without a handler registered with `interrupt::register`
+//! }
+//! ```
+//!
+//! # NMI entry
+//!
+//! In M-mode the feature also provides `_nmi_entry` for the resumable NMI
+//! vector of the Smrnmi extension. It switches to the stack whose top is
+//! kept in `mnscratch` (see [`set_nmi_stack`]), pushes a `TrapFrame` built
+//! from `mnepc`, `mnstatus` and `mncause` (`tval` is zero), calls
+//! `nmi_handler` and resumes with `mnret`. Changes to the saved `sp` are not
+//! written back. Like the hooks above, `nmi_handler` has a weak default that
+//! panics with the cause:
+//!
+//! ```ignore
+//! #[no_mangle]
+//! extern "C" fn nmi_handler(frame: &mut TrapFrame) {
+//!     // ...
+//! }
+//! ```
+//!
+//! With the Smdbltrp extension, a trap taken while `mstatus.MDT` is set,
+//! i.e. while a trap handler has not yet cleared it, is delivered to the
+//! RNMI handler as `Exception::DoubleTrap`, which makes `nmi_handler` the
+//! place to handle traps taken inside trap handlers.
+
+use core::mem::size_of;
+
+/// Trap Cause
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Trap {
+    Interrupt(Interrupt),
+    Exception(Exception),
+}
+
+/// Interrupt
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Interrupt {
+    UserSoft,
+    SupervisorSoft,
+    VirtualSupervisorSoft,
+    MachineSoft,
+    UserTimer,
+    SupervisorTimer,
+    VirtualSupervisorTimer,
+    MachineTimer,
+    UserExternal,
+    SupervisorExternal,
+    VirtualSupervisorExternal,
+    MachineExternal,
+    SupervisorGuestExternal,
+    Unknown,
+}
+
+/// Exception
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Exception {
+    InstructionMisaligned,
+    InstructionFault,
+    IllegalInstruction,
+    Breakpoint,
+    LoadMisaligned,
+    LoadFault,
+    StoreMisaligned,
+    StoreFault,
+    UserEnvCall,
+    SupervisorEnvCall,
+    VirtualSupervisorEnvCall,
+    MachineEnvCall,
+    InstructionPageFault,
+    LoadPageFault,
+    StorePageFault,
+    DoubleTrap,
+    InstructionGuestPageFault,
+    LoadGuestPageFault,
+    VirtualInstruction,
+    StoreGuestPageFault,
+    Unknown,
+}
+
+impl Trap {
+    /// Decodes a trap from its interrupt flag and cause code
+    #[inline]
+    pub fn from_code(interrupt: bool, code: usize) -> Self {
+        if interrupt {
+            Trap::Interrupt(Interrupt::from_code(code))
+        } else {
+            Trap::Exception(Exception::from_code(code))
+        }
+    }
+
+    /// Returns the interrupt flag and cause code, or `None` for an unknown cause
+    #[inline]
+    pub fn to_code(self) -> Option<(bool, usize)> {
+        match self {
+            Trap::Interrupt(interrupt) => interrupt.to_code().map(|code| (true, code)),
+            Trap::Exception(exception) => exception.to_code().map(|code| (false, code)),
+        }
+    }
+
+    /// Decodes the raw value of a cause register (`mcause`, `scause`, ...)
+    #[inline]
+    pub fn from_bits(bits: usize) -> Self {
+        let interrupt = 1 << (size_of::<usize>() * 8 - 1);
+        Self::from_code(bits & interrupt != 0, bits & !interrupt)
+    }
+
+    /// Encodes the trap as a cause register value, or `None` for an unknown cause
+    #[inline]
+    pub fn to_bits(self) -> Option<usize> {
+        let interrupt = 1 << (size_of::<usize>() * 8 - 1);
+        self.to_code()
+            .map(|(is_interrupt, code)| if is_interrupt { code | interrupt } else { code })
+    }
+}
+
+impl Interrupt {
+    /// Decodes an interrupt cause code
+    #[inline]
+    pub fn from_code(code: usize) -> Self {
+        match code {
+            0 => Interrupt::UserSoft,
+            1 => Interrupt::SupervisorSoft,
+            2 => Interrupt::VirtualSupervisorSoft,
+            3 => Interrupt::MachineSoft,
+            4 => Interrupt::UserTimer,
+            5 => Interrupt::SupervisorTimer,
+            6 => Interrupt::VirtualSupervisorTimer,
+            7 => Interrupt::MachineTimer,
+            8 => Interrupt::UserExternal,
+            9 => Interrupt::SupervisorExternal,
+            10 => Interrupt::VirtualSupervisorExternal,
+            11 => Interrupt::MachineExternal,
+            12 => Interrupt::SupervisorGuestExternal,
+            _ => Interrupt::Unknown,
+        }
+    }
+
+    /// Returns the cause code, or `None` for `Unknown`
+    #[inline]
+    pub fn to_code(self) -> Option<usize> {
+        match self {
+            Interrupt::UserSoft => Some(0),
+            Interrupt::SupervisorSoft => Some(1),
+            Interrupt::VirtualSupervisorSoft => Some(2),
+            Interrupt::MachineSoft => Some(3),
+            Interrupt::UserTimer => Some(4),
+            Interrupt::SupervisorTimer => Some(5),
+            Interrupt::VirtualSupervisorTimer => Some(6),
+            Interrupt::MachineTimer => Some(7),
+            Interrupt::UserExternal => Some(8),
+            Interrupt::SupervisorExternal => Some(9),
+            Interrupt::VirtualSupervisorExternal => Some(10),
+            Interrupt::MachineExternal => Some(11),
+            Interrupt::SupervisorGuestExternal => Some(12),
+            Interrupt::Unknown => None,
+        }
+    }
+
+    /// Decodes an interrupt cause code
+    #[inline]
+    pub fn from(nr: usize) -> Self {
+        Self::from_code(nr)
+    }
+}
+
+impl Exception {
+    /// Decodes an exception cause code
+    #[inline]
+    pub fn from_code(code: usize) -> Self {
+        match code {
+            0 => Exception::InstructionMisaligned,
+            1 => Exception::InstructionFault,
+            2 => Exception::IllegalInstruction,
+            3 => Exception::Breakpoint,
+            4 => Exception::LoadMisaligned,
+            5 => Exception::LoadFault,
+            6 => Exception::StoreMisaligned,
+            7 => Exception::StoreFault,
+            8 => Exception::UserEnvCall,
+            9 => Exception::SupervisorEnvCall,
+            10 => Exception::VirtualSupervisorEnvCall,
+            11 => Exception::MachineEnvCall,
+            12 => Exception::InstructionPageFault,
+            13 => Exception::LoadPageFault,
+            15 => Exception::StorePageFault,
+            16 => Exception::DoubleTrap,
+            20 => Exception::InstructionGuestPageFault,
+            21 => Exception::LoadGuestPageFault,
+            22 => Exception::VirtualInstruction,
+            23 => Exception::StoreGuestPageFault,
+            _ => Exception::Unknown,
+        }
+    }
+
+    /// Returns the cause code, or `None` for `Unknown`
+    #[inline]
+    pub fn to_code(self) -> Option<usize> {
+        match self {
+            Exception::InstructionMisaligned => Some(0),
+            Exception::InstructionFault => Some(1),
+            Exception::IllegalInstruction => Some(2),
+            Exception::Breakpoint => Some(3),
+            Exception::LoadMisaligned => Some(4),
+            Exception::LoadFault => Some(5),
+            Exception::StoreMisaligned => Some(6),
+            Exception::StoreFault => Some(7),
+            Exception::UserEnvCall => Some(8),
+            Exception::SupervisorEnvCall => Some(9),
+            Exception::VirtualSupervisorEnvCall => Some(10),
+            Exception::MachineEnvCall => Some(11),
+            Exception::InstructionPageFault => Some(12),
+            Exception::LoadPageFault => Some(13),
+            Exception::StorePageFault => Some(15),
+            Exception::DoubleTrap => Some(16),
+            Exception::InstructionGuestPageFault => Some(20),
+            Exception::LoadGuestPageFault => Some(21),
+            Exception::VirtualInstruction => Some(22),
+            Exception::StoreGuestPageFault => Some(23),
+            Exception::Unknown => None,
+        }
+    }
+
+    /// Decodes an exception cause code
+    #[inline]
+    pub fn from(nr: usize) -> Self {
+        Self::from_code(nr)
+    }
+}
+
+/// Delegates the traps a supervisor-mode kernel conventionally handles
+///
+/// Sets `medeleg` for instruction address misaligned, breakpoint,
+/// environment call from U-mode and the instruction, load and store page
+/// faults, and `mideleg` for the supervisor software, timer and external
+/// interrupts. Other delegation bits are left unchanged.
+///
+/// # Safety
+///
+/// Must be called from M-mode, and the supervisor must have a trap handler
+/// installed before any delegated trap can occur.
+pub unsafe fn delegate_default_to_supervisor() {
+    use register::{medeleg, mideleg};
+
+    for &exception in &[
+        Exception::InstructionMisaligned,
+        Exception::Breakpoint,
+        Exception::UserEnvCall,
+        Exception::InstructionPageFault,
+        Exception::LoadPageFault,
+        Exception::StorePageFault,
+    ] {
+        medeleg::delegate(exception);
+    }
+    for &interrupt in &[
+        Interrupt::SupervisorSoft,
+        Interrupt::SupervisorTimer,
+        Interrupt::SupervisorExternal,
+    ] {
+        mideleg::delegate(interrupt);
+    }
+}
+
+/// Registers saved on trap entry
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TrapFrame {
+    /// General-purpose registers `x1`-`x31` (`regs[0]` holds `x1`)
+    pub regs: [usize; 31],
+    /// Exception program counter (`mepc`/`sepc`)
+    pub pc: usize,
+    /// Status register (`mstatus`/`sstatus`)
+    pub status: usize,
+    /// Trap cause (`mcause`/`scause`)
+    pub cause: usize,
+    /// Trap value (`mtval`/`stval`)
+    pub tval: usize,
+}
+
+impl TrapFrame {
+    /// Returns general-purpose register `x<n>` (`x0` reads as zero)
+    #[inline]
+    pub fn reg(&self, n: usize) -> usize {
+        match n {
+            0 => 0,
+            _ => self.regs[n - 1],
+        }
+    }
+
+    /// Sets general-purpose register `x<n>` (writes to `x0` are ignored)
+    #[inline]
+    pub fn set_reg(&mut self, n: usize, value: usize) {
+        if n != 0 {
+            self.regs[n - 1] = value;
+        }
+    }
+
+    /// Returns the decoded trap cause
+    #[inline]
+    pub fn trap(&self) -> Trap {
+        Trap::from_bits(self.cause)
+    }
+}
+
+/// Sets the top of the stack `_nmi_entry` switches to
+///
+/// # Safety
+///
+/// `top` must be the 16-byte aligned end of a stack reserved for the NMI
+/// handler of the current hart, and an NMI must not be taken while the
+/// register is being written.
+#[inline]
+pub unsafe fn set_nmi_stack(top: usize) {
+    ::register::mnscratch::write(top);
+}
+
+/// Emits `_trap_entry` for the given register width and trap CSRs
+///
+/// The frame occupies 36 registers to keep the stack 16-byte aligned.
+#[cfg(all(riscv, feature = "trap-entry"))]
+macro_rules! trap_entry {
+    ($store:literal, $load:literal, $bytes:literal, $epc:literal, $status:literal, $cause:literal, $tval:literal, $ret:literal) => {
+        core::arch::global_asm!(concat!(
+            ".section .text._trap_entry, \"ax\", @progbits\n",
+            ".global _trap_entry\n",
+            ".align 2\n",
+            "_trap_entry:\n",
+            "    addi sp, sp, -36*", $bytes, "\n",
+            "    ", $store, " x1, 0*", $bytes, "(sp)\n",
+            "    .irp n, 3,4,5,6,7,8,9,10,11,12,13,14,15,16,17,18,19,20,21,22,23,24,25,26,27,28,29,30,31\n",
+            "    ", $store, " x\\n, (\\n-1)*", $bytes, "(sp)\n",
+            "    .endr\n",
+            "    addi t0, sp, 36*", $bytes, "\n",
+            "    ", $store, " t0, 1*", $bytes, "(sp)\n",
+            "    csrr t0, ", $epc, "\n",
+            "    ", $store, " t0, 31*", $bytes, "(sp)\n",
+            "    csrr t0, ", $status, "\n",
+            "    ", $store, " t0, 32*", $bytes, "(sp)\n",
+            "    csrr t0, ", $cause, "\n",
+            "    ", $store, " t0, 33*", $bytes, "(sp)\n",
+            "    csrr t0, ", $tval, "\n",
+            "    ", $store, " t0, 34*", $bytes, "(sp)\n",
+            "    mv a0, sp\n",
+            "    call trap_handler\n",
+            "    ", $load, " t0, 31*", $bytes, "(sp)\n",
+            "    csrw ", $epc, ", t0\n",
+            "    ", $load, " t0, 32*", $bytes, "(sp)\n",
+            "    csrw ", $status, ", t0\n",
+            "    ", $load, " x1, 0*", $bytes, "(sp)\n",
+            "    .irp n, 3,4,5,6,7,8,9,10,11,12,13,14,15,16,17,18,19,20,21,22,23,24,25,26,27,28,29,30,31\n",
+            "    ", $load, " x\\n, (\\n-1)*", $bytes, "(sp)\n",
+            "    .endr\n",
+            "    addi sp, sp, 36*", $bytes, "\n",
+            "    ", $ret, "\n",
+        ));
+    };
+}
+
+#[cfg(all(riscv32, feature = "trap-entry", not(feature = "s-mode")))]
+trap_entry!("sw", "lw", "4", "mepc", "mstatus", "mcause", "mtval", "mret");
+#[cfg(all(riscv64, feature = "trap-entry", not(feature = "s-mode")))]
+trap_entry!("sd", "ld", "8", "mepc", "mstatus", "mcause", "mtval", "mret");
+#[cfg(all(riscv32, feature = "trap-entry", feature = "s-mode"))]
+trap_entry!("sw", "lw", "4", "sepc", "sstatus", "scause", "stval", "sret");
+#[cfg(all(riscv64, feature = "trap-entry", feature = "s-mode"))]
+trap_entry!("sd", "ld", "8", "sepc", "sstatus", "scause", "stval", "sret");
+
+/// Default `trap_handler`, calling the `ExceptionHandler` and
+/// `DefaultInterruptHandler` hooks
+#[cfg(all(riscv, feature = "trap-entry"))]
+#[export_name = "__default_trap_handler"]
+extern "C" fn default_trap_handler(frame: &mut TrapFrame) {
+    extern "C" {
+        fn ExceptionHandler(frame: &mut TrapFrame);
+        fn DefaultInterruptHandler(frame: &mut TrapFrame);
+    }
+
+    match frame.trap() {
+        Trap::Interrupt(_) => {
+            if !::interrupt::dispatch(frame) {
+                unsafe { DefaultInterruptHandler(frame) }
+            }
+        }
+        Trap::Exception(_) => unsafe { ExceptionHandler(frame) },
+    }
+}
+
+/// Default `ExceptionHandler`, `DefaultInterruptHandler` and `nmi_handler`
+#[cfg(all(riscv, feature = "trap-entry"))]
+#[export_name = "__unhandled_trap"]
+extern "C" fn unhandled_trap(frame: &mut TrapFrame) -> ! {
+    panic!(
+        "unhandled {:?} at pc {:#x}, tval {:#x}",
+        frame.trap(),
+        frame.pc,
+        frame.tval
+    );
+}
+
+// Weak definitions, replaced by the application's own
+#[cfg(all(riscv, feature = "trap-entry"))]
+core::arch::global_asm!(
+    ".section .text.trap_handler, \"ax\", @progbits",
+    ".weak trap_handler",
+    "trap_handler:",
+    "    tail __default_trap_handler",
+    ".section .text.ExceptionHandler, \"ax\", @progbits",
+    ".weak ExceptionHandler",
+    "ExceptionHandler:",
+    "    tail __unhandled_trap",
+    ".section .text.DefaultInterruptHandler, \"ax\", @progbits",
+    ".weak DefaultInterruptHandler",
+    "DefaultInterruptHandler:",
+    "    tail __unhandled_trap",
+);
+
+/// Emits `_nmi_entry` for the given register width
+///
+/// The CSRs are named by number and `mnret` is emitted as a raw word, as not
+/// every assembler knows the Smrnmi extension.
+#[cfg(all(riscv, feature = "trap-entry", not(feature = "s-mode")))]
+macro_rules! nmi_entry {
+    ($store:literal, $load:literal, $bytes:literal) => {
+        core::arch::global_asm!(concat!(
+            ".section .text._nmi_entry, \"ax\", @progbits\n",
+            ".global _nmi_entry\n",
+            ".align 2\n",
+            "_nmi_entry:\n",
+            "    csrrw sp, 0x740, sp\n",
+            "    addi sp, sp, -36*", $bytes, "\n",
+            "    ", $store, " x1, 0*", $bytes, "(sp)\n",
+            "    .irp n, 3,4,5,6,7,8,9,10,11,12,13,14,15,16,17,18,19,20,21,22,23,24,25,26,27,28,29,30,31\n",
+            "    ", $store, " x\\n, (\\n-1)*", $bytes, "(sp)\n",
+            "    .endr\n",
+            "    csrr t0, 0x740\n",
+            "    ", $store, " t0, 1*", $bytes, "(sp)\n",
+            "    csrr t0, 0x741\n",
+            "    ", $store, " t0, 31*", $bytes, "(sp)\n",
+            "    csrr t0, 0x744\n",
+            "    ", $store, " t0, 32*", $bytes, "(sp)\n",
+            "    csrr t0, 0x742\n",
+            "    ", $store, " t0, 33*", $bytes, "(sp)\n",
+            "    ", $store, " x0, 34*", $bytes, "(sp)\n",
+            "    mv a0, sp\n",
+            "    call nmi_handler\n",
+            "    ", $load, " t0, 31*", $bytes, "(sp)\n",
+            "    csrw 0x741, t0\n",
+            "    ", $load, " t0, 32*", $bytes, "(sp)\n",
+            "    csrw 0x744, t0\n",
+            "    ", $load, " x1, 0*", $bytes, "(sp)\n",
+            "    .irp n, 3,4,5,6,7,8,9,10,11,12,13,14,15,16,17,18,19,20,21,22,23,24,25,26,27,28,29,30,31\n",
+            "    ", $load, " x\\n, (\\n-1)*", $bytes, "(sp)\n",
+            "    .endr\n",
+            "    addi sp, sp, 36*", $bytes, "\n",
+            "    csrrw sp, 0x740, sp\n",
+            "    .word 0x70200073\n",
+        ));
+    };
+}
+
+#[cfg(all(riscv32, feature = "trap-entry", not(feature = "s-mode")))]
+nmi_entry!("sw", "lw", "4");
+#[cfg(all(riscv64, feature = "trap-entry", not(feature = "s-mode")))]
+nmi_entry!("sd", "ld", "8");
+
+// Weak definition, replaced by the application's own
+#[cfg(all(riscv, feature = "trap-entry", not(feature = "s-mode")))]
+core::arch::global_asm!(
+    ".section .text.nmi_handler, \"ax\", @progbits",
+    ".weak nmi_handler",
+    "nmi_handler:",
+    "    tail __unhandled_trap",
+);
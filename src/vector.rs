@@ -0,0 +1,282 @@
+//! Vector state context switching
+//!
+//! The VS field of `mstatus` (or of `sstatus` with the `s-mode` feature)
+//! tracks whether the vector state was modified, like FS does for the
+//! floating-point unit (see [`fpu`](crate::fpu)): a scheduler can save the
+//! state of the outgoing task only if it is dirty ([`save_if_dirty`]), turn
+//! the unit off ([`disable`]), and restore the state of the incoming task
+//! ([`restore`]) on its first vector instruction ([`is_vector_instruction`]).
+
+use bit_field::BitField;
+
+use insn::TrappedInstruction;
+use register::{vcsr, vl, vlenb, vstart, vtype};
+
+pub use register::mstatus::FS;
+
+/// Saved vector state for harts with up to `VLENB` bytes per vector register
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct VRegs<const VLENB: usize> {
+    /// Registers `v0`-`v31`; only the first `vlenb` bytes of each are used
+    pub v: [[u8; VLENB]; 32],
+    /// Vector start position
+    pub vstart: usize,
+    /// Vector length
+    pub vl: usize,
+    /// Vector data type
+    pub vtype: usize,
+    /// Vector control and status register
+    pub vcsr: usize,
+}
+
+impl<const VLENB: usize> VRegs<VLENB> {
+    /// Creates zeroed storage
+    #[inline]
+    pub const fn new() -> Self {
+        VRegs {
+            v: [[0; VLENB]; 32],
+            vstart: 0,
+            vl: 0,
+            vtype: 0,
+            vcsr: 0,
+        }
+    }
+
+    /// Returns true if the vector registers of the current hart fit
+    ///
+    /// The vector unit must not be `Off`.
+    #[inline]
+    pub fn fits() -> bool {
+        vlenb::read() <= VLENB
+    }
+}
+
+impl<const VLENB: usize> Default for VRegs<VLENB> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the state of the vector unit
+#[inline]
+pub fn state() -> FS {
+    match () {
+        #[cfg(not(feature = "s-mode"))]
+        () => ::register::mstatus::read().vs(),
+        #[cfg(feature = "s-mode")]
+        () => ::register::sstatus::read().vs(),
+    }
+}
+
+/// Sets the state of the vector unit
+///
+/// # Safety
+///
+/// Setting the state to `Clean` or `Initial` while the registers hold unsaved
+/// values of a task may lose them.
+#[inline]
+pub unsafe fn set_state(vs: FS) {
+    match () {
+        #[cfg(not(feature = "s-mode"))]
+        () => ::register::mstatus::set_vs(vs),
+        #[cfg(feature = "s-mode")]
+        () => ::register::sstatus::modify(|s| s.set_vs(vs)),
+    }
+}
+
+/// Returns true if the vector state was written since it was last saved or
+/// restored
+#[inline]
+pub fn is_dirty() -> bool {
+    state() == FS::Dirty
+}
+
+/// Turns the vector unit off
+///
+/// Vector instructions raise an illegal instruction exception until the state
+/// is changed again.
+///
+/// # Safety
+///
+/// See [`set_state`]; the vector state of the current task should have been
+/// saved.
+#[inline]
+pub unsafe fn disable() {
+    set_state(FS::Off)
+}
+
+/// Stores `v0`-`v31` and the vector CSRs to `regs`
+///
+/// Leaves `vstart` zero.
+///
+/// # Safety
+///
+/// The vector unit must not be `Off`.
+///
+/// # Panics
+///
+/// Panics if `vlenb` is larger than `VLENB`.
+#[inline]
+pub unsafe fn vstate_save<const VLENB: usize>(regs: &mut VRegs<VLENB>) {
+    assert!(VRegs::<VLENB>::fits(), "vector registers do not fit");
+    regs.vstart = vstart::read();
+    regs.vl = vl::read();
+    regs.vtype = vtype::read().bits();
+    regs.vcsr = vcsr::read().bits();
+    // Whole register stores start at element `vstart`
+    vstart::write(0);
+    save_registers(regs.v.as_mut_ptr() as *mut u8, VLENB);
+}
+
+/// Loads `v0`-`v31` and the vector CSRs from `regs`
+///
+/// # Safety
+///
+/// The vector unit must not be `Off`, and `regs` must have been saved on a
+/// hart with the same `vlenb`.
+///
+/// # Panics
+///
+/// Panics if `vlenb` is larger than `VLENB`.
+#[inline]
+pub unsafe fn vstate_restore<const VLENB: usize>(regs: &VRegs<VLENB>) {
+    assert!(VRegs::<VLENB>::fits(), "vector registers do not fit");
+    // Whole register loads start at element `vstart`
+    vstart::write(0);
+    load_registers(regs.v.as_ptr() as *const u8, VLENB, regs.vl, regs.vtype);
+    vcsr::write(
+        vcsr::RoundingMode::from(regs.vcsr.get_bits(1..3)),
+        regs.vcsr.get_bit(0),
+    );
+    vstart::write(regs.vstart);
+}
+
+/// Stores `v0`-`v31` to `v`, `stride` bytes apart
+#[inline]
+unsafe fn save_registers(v: *mut u8, stride: usize) {
+    match () {
+        #[cfg(all(riscv, feature = "inline-asm"))]
+        // vs1r.v v0-v31, (a0)
+        () => core::arch::asm!(
+            ".set vreg, 0",
+            ".rept 32",
+            ".word 42270759 + (vreg << 7)",
+            "add a0, a0, a1",
+            ".set vreg, vreg + 1",
+            ".endr",
+            inout("x10") v => _,
+            in("x11") stride,
+            options(nostack)
+        ),
+
+        #[cfg(all(riscv, not(feature = "inline-asm")))]
+        () => {
+            extern "C" {
+                fn __vstate_save(v: *mut u8, stride: usize);
+            }
+
+            __vstate_save(v, stride);
+        }
+
+        #[cfg(not(riscv))]
+        () => {
+            let _ = (v, stride);
+            unimplemented!()
+        }
+    }
+}
+
+/// Loads `v0`-`v31` from `v`, `stride` bytes apart, and sets `vl` and `vtype`
+#[inline]
+unsafe fn load_registers(v: *const u8, stride: usize, vl: usize, vtype: usize) {
+    match () {
+        #[cfg(all(riscv, feature = "inline-asm"))]
+        // vl1re8.v v0-v31, (a0); vsetvl x0, a2, a3
+        () => core::arch::asm!(
+            ".set vreg, 0",
+            ".rept 32",
+            ".word 42270727 + (vreg << 7)",
+            "add a0, a0, a1",
+            ".set vreg, vreg + 1",
+            ".endr",
+            ".word 2161537111",
+            inout("x10") v => _,
+            in("x11") stride,
+            in("x12") vl,
+            in("x13") vtype,
+            options(nostack, readonly)
+        ),
+
+        #[cfg(all(riscv, not(feature = "inline-asm")))]
+        () => {
+            extern "C" {
+                fn __vstate_restore(v: *const u8, stride: usize, vl: usize, vtype: usize);
+            }
+
+            __vstate_restore(v, stride, vl, vtype);
+        }
+
+        #[cfg(not(riscv))]
+        () => {
+            let _ = (v, stride, vl, vtype);
+            unimplemented!()
+        }
+    }
+}
+
+/// Saves the vector state to `regs` if it is dirty and marks it clean
+///
+/// Returns true if the state was saved.
+///
+/// # Safety
+///
+/// See [`vstate_save`].
+#[inline]
+pub unsafe fn save_if_dirty<const VLENB: usize>(regs: &mut VRegs<VLENB>) -> bool {
+    if is_dirty() {
+        vstate_save(regs);
+        set_state(FS::Clean);
+        true
+    } else {
+        false
+    }
+}
+
+/// Turns the vector unit on and loads the vector state from `regs`
+///
+/// The state is left `Clean`, so it only needs saving again once it is
+/// written.
+///
+/// # Safety
+///
+/// See [`vstate_restore`].
+#[inline]
+pub unsafe fn restore<const VLENB: usize>(regs: &VRegs<VLENB>) {
+    set_state(FS::Initial);
+    vstate_restore(regs);
+    set_state(FS::Clean);
+}
+
+/// Returns true if `insn` accesses the vector unit
+///
+/// Used on illegal instruction exceptions to detect the first use of the
+/// unit while it is `Off`. Covers vector loads and stores, arithmetic and
+/// configuration instructions, and accesses to the vector CSRs.
+pub fn is_vector_instruction(insn: &TrappedInstruction) -> bool {
+    match insn.opcode() {
+        // LOAD-FP, STORE-FP: vector element widths
+        0x07 | 0x27 => !(1..=4).contains(&insn.funct3()),
+        // OP-V
+        0x57 => true,
+        // SYSTEM: CSR instructions on vstart, vxsat, vxrm, vcsr, vl, vtype
+        // or vlenb
+        0x73 => {
+            let csr = insn.bits() >> 20;
+            insn.funct3() & 0b11 != 0
+                && ((0x008..=0x00f).contains(&csr) || (0xc20..=0xc22).contains(&csr))
+        }
+        _ => false,
+    }
+}
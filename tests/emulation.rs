@@ -0,0 +1,29 @@
+#![cfg(all(
+    feature = "emulation",
+    not(any(target_arch = "riscv32", target_arch = "riscv64"))
+))]
+
+extern crate riscv;
+
+use riscv::asm;
+use riscv::register::{emulation, mcycle, mscratch};
+
+#[test]
+fn mscratch_read_write() {
+    assert_eq!(mscratch::read(), 0);
+
+    mscratch::write(0xdead_beef);
+    assert_eq!(mscratch::read(), 0xdead_beef);
+    assert_eq!(emulation::read(0x340), 0xdead_beef);
+
+    emulation::write(0x340, 42);
+    assert_eq!(mscratch::read(), 42);
+}
+
+#[test]
+fn delay_does_not_wait_for_emulated_counter() {
+    emulation::write(0xb00, 5);
+
+    asm::delay(u64::MAX);
+    assert_eq!(mcycle::read64(), 5);
+}